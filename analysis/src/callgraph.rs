@@ -0,0 +1,387 @@
+//! This module provides functionality for extracting the call graph of a module.
+//!
+//! The graph has one node per function of the module, identified by its name, and an edge from
+//! each function to every function it calls directly. Its strongly connected components group the
+//! functions that can call each other, which is how recursion is detected.
+
+extern crate llvm_sys as llvm;
+use std::collections::{HashMap, HashSet, VecDeque};
+use llvm::{core, prelude::{LLVMModuleRef, LLVMValueRef}};
+use ir::{core::{IRManager, ModuleTag}, inspect::value_name};
+use crate::cfg::{block_instructions, function_blocks};
+
+/// The call graph of a module.
+#[derive(Debug, Clone)]
+pub struct CallGraph {
+    functions: Vec<String>,
+    declarations: Vec<String>,
+    callees: HashMap<String, Vec<String>>,
+    callers: HashMap<String, Vec<String>>,
+    indirect_calls: HashMap<String, usize>,
+    components: Vec<Vec<String>>,
+    component_indices: HashMap<String, usize>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of a module.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the module.
+    /// * `module_tag` - The tag of the module to analyze.
+    ///
+    /// # Returns
+    /// Returns `Ok(CallGraph)` on success, or `Err(String)` if the module could not be found.
+    pub fn new(manager: &IRManager, module_tag: ModuleTag) -> Result<Self, String> {
+        let module_ptr = manager.get_module_ptr(module_tag).ok_or_else(|| format!("Module {:?} not found.", module_tag))?;
+
+        let mut graph = Self {
+            functions: Vec::new(),
+            declarations: Vec::new(),
+            callees: HashMap::new(),
+            callers: HashMap::new(),
+            indirect_calls: HashMap::new(),
+            components: Vec::new(),
+            component_indices: HashMap::new(),
+        };
+
+        let functions = module_functions(module_ptr);
+        for function in &functions {
+            let name = unsafe { value_name(*function) };
+            if unsafe { core::LLVMIsDeclaration(*function) } != 0 {
+                graph.declarations.push(name.clone());
+            }
+            graph.callees.insert(name.clone(), Vec::new());
+            graph.callers.insert(name.clone(), Vec::new());
+            graph.functions.push(name);
+        }
+
+        for function in functions {
+            let caller = unsafe { value_name(function) };
+            for call_site in call_sites(function) {
+                match direct_callee(call_site) {
+                    Some(callee) => graph.add_edge(&caller, &unsafe { value_name(callee) }),
+                    None => *graph.indirect_calls.entry(caller.clone()).or_default() += 1,
+                }
+            }
+        }
+
+        let indices: HashMap<&str, usize> = graph.functions.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+        let successors: Vec<Vec<usize>> = graph.functions.iter()
+            .map(|caller| graph.get_callees(caller).iter().filter_map(|callee| indices.get(callee.as_str()).copied()).collect())
+            .collect();
+        for (index, component) in strongly_connected_components(&successors).into_iter().enumerate() {
+            let members: Vec<String> = component.into_iter().map(|member| graph.functions[member].clone()).collect();
+            for member in &members {
+                graph.component_indices.insert(member.clone(), index);
+            }
+            graph.components.push(members);
+        }
+
+        Ok(graph)
+    }
+
+    /// Records a call from one function to another, unless it is already recorded.
+    ///
+    /// # Parameters
+    /// * `caller` - The name of the calling function.
+    /// * `callee` - The name of the called function.
+    fn add_edge(&mut self, caller: &str, callee: &str) {
+        let callees = self.callees.entry(caller.to_string()).or_default();
+        if !callees.iter().any(|name| name == callee) {
+            callees.push(callee.to_string());
+            self.callers.entry(callee.to_string()).or_default().push(caller.to_string());
+        }
+    }
+
+    /// Retrieves every function of the module, including declarations.
+    ///
+    /// # Returns
+    /// The names of the functions, in the order they appear in the module.
+    pub fn get_functions(&self) -> &[String] {
+        &self.functions
+    }
+
+    /// Determines whether a function is only declared in the module.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the function.
+    ///
+    /// # Returns
+    /// True if the function has no body in the module, false otherwise.
+    pub fn is_declaration(&self, function: &str) -> bool {
+        self.declarations.iter().any(|name| name == function)
+    }
+
+    /// Retrieves the functions a function calls directly.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the calling function.
+    ///
+    /// # Returns
+    /// The names of the callees in the order they are first called, or an empty slice if the
+    /// function is not part of the graph.
+    pub fn get_callees(&self, function: &str) -> &[String] {
+        self.callees.get(function).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Retrieves the functions calling a function directly.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the called function.
+    ///
+    /// # Returns
+    /// The names of the callers in module order, or an empty slice if the function is not part of
+    /// the graph.
+    pub fn get_callers(&self, function: &str) -> &[String] {
+        self.callers.get(function).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Retrieves the number of calls through function pointers a function makes.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the function.
+    ///
+    /// # Returns
+    /// The number of indirect call sites in the function's body.
+    pub fn get_indirect_call_count(&self, function: &str) -> usize {
+        self.indirect_calls.get(function).copied().unwrap_or(0)
+    }
+
+    /// Retrieves every edge of the graph.
+    ///
+    /// # Returns
+    /// The edges as `(caller, callee)` pairs, grouped by caller in module order.
+    pub fn get_edges(&self) -> Vec<(&str, &str)> {
+        self.functions.iter()
+            .flat_map(|caller| self.get_callees(caller).iter().map(move |callee| (caller.as_str(), callee.as_str())))
+            .collect()
+    }
+
+    /// Retrieves the declared functions that are called from the module.
+    ///
+    /// # Returns
+    /// The names of the external callees, in module order.
+    pub fn get_external_callees(&self) -> Vec<&str> {
+        self.declarations.iter()
+            .filter(|name| !self.get_callers(name).is_empty())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Retrieves the strongly connected components of the graph. Every function belongs to exactly
+    /// one component, and the functions of a component can all call each other.
+    ///
+    /// # Returns
+    /// The components in reverse topological order, so that every component comes after the
+    /// components it calls into. The functions of each component are in module order.
+    pub fn get_strongly_connected_components(&self) -> &[Vec<String>] {
+        &self.components
+    }
+
+    /// Retrieves the strongly connected component containing a function.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the function.
+    ///
+    /// # Returns
+    /// An `Option` containing the functions of the component, or `None` if the function is not
+    /// part of the graph.
+    pub fn get_component(&self, function: &str) -> Option<&[String]> {
+        self.component_indices.get(function).map(|index| self.components[*index].as_slice())
+    }
+
+    /// Determines whether a function can call itself, directly or through other functions.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the function.
+    ///
+    /// # Returns
+    /// True if the function calls itself or shares a component with another function, false
+    /// otherwise.
+    pub fn is_recursive(&self, function: &str) -> bool {
+        self.get_component(function).map(|component| component.len() > 1).unwrap_or(false)
+            || self.get_callees(function).iter().any(|callee| callee == function)
+    }
+
+    /// Retrieves every recursive function of the module.
+    ///
+    /// # Returns
+    /// The names of the recursive functions, in module order.
+    pub fn get_recursive_functions(&self) -> Vec<&str> {
+        self.functions.iter().filter(|name| self.is_recursive(name)).map(String::as_str).collect()
+    }
+
+    /// Retrieves the functions that can be called, directly or indirectly, from a root function.
+    ///
+    /// Calls through function pointers are not followed.
+    ///
+    /// # Parameters
+    /// * `root` - The name of the root function, such as `DEFAULT_FUNCTION_NAME`.
+    ///
+    /// # Returns
+    /// The names of the reachable functions in module order, including the root and declarations,
+    /// or an empty vector if the root is not part of the graph.
+    pub fn get_reachable_functions(&self, root: &str) -> Vec<&str> {
+        if !self.contains(root) {
+            return Vec::new();
+        }
+        let mut reached: HashSet<&str> = HashSet::from([root]);
+        let mut worklist: VecDeque<&str> = VecDeque::from([root]);
+        while let Some(function) = worklist.pop_front() {
+            for callee in self.get_callees(function) {
+                if reached.insert(callee.as_str()) {
+                    worklist.push_back(callee.as_str());
+                }
+            }
+        }
+        self.functions.iter().map(String::as_str).filter(|name| reached.contains(name)).collect()
+    }
+
+    /// Retrieves the functions with a body that cannot be called from a root function.
+    ///
+    /// # Parameters
+    /// * `root` - The name of the root function, such as `DEFAULT_FUNCTION_NAME`.
+    ///
+    /// # Returns
+    /// The names of the unreachable functions in module order. Declarations are left out, and every
+    /// function is unreachable if the root is not part of the graph.
+    pub fn get_unreachable_functions(&self, root: &str) -> Vec<&str> {
+        let reachable = self.get_reachable_functions(root);
+        self.functions.iter()
+            .map(String::as_str)
+            .filter(|name| !self.is_declaration(name) && !reachable.contains(name))
+            .collect()
+    }
+
+    /// Determines whether a function is part of the graph.
+    ///
+    /// # Parameters
+    /// * `function` - The name of the function.
+    ///
+    /// # Returns
+    /// True if the module contains the function, false otherwise.
+    pub fn contains(&self, function: &str) -> bool {
+        self.callees.contains_key(function)
+    }
+}
+
+/// Finds the strongly connected components of a graph with Tarjan's algorithm.
+///
+/// # Parameters
+/// * `successors` - The successors of each node.
+///
+/// # Returns
+/// The components in reverse topological order, each with its nodes in ascending order.
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut indices: Vec<Option<usize>> = vec![None; successors.len()];
+    let mut lowlinks = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..successors.len() {
+        if indices[root].is_some() {
+            continue;
+        }
+        let mut call_stack = vec![(root, 0)];
+        while let Some((node, next_child)) = call_stack.pop() {
+            if next_child == 0 {
+                indices[node] = Some(next_index);
+                lowlinks[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+            if next_child < successors[node].len() {
+                call_stack.push((node, next_child + 1));
+                let child = successors[node][next_child];
+                match indices[child] {
+                    None => call_stack.push((child, 0)),
+                    Some(child_index) if on_stack[child] => lowlinks[node] = lowlinks[node].min(child_index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            if let Some((parent, _)) = call_stack.last() {
+                lowlinks[*parent] = lowlinks[*parent].min(lowlinks[node]);
+            }
+            if Some(lowlinks[node]) == indices[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Lists the functions of a module in order.
+///
+/// # Parameters
+/// * `module` - The module.
+///
+/// # Returns
+/// The raw pointers of the functions.
+pub(crate) fn module_functions(module: LLVMModuleRef) -> Vec<LLVMValueRef> {
+    let mut functions = Vec::new();
+    unsafe {
+        let mut function = core::LLVMGetFirstFunction(module);
+        while !function.is_null() {
+            functions.push(function);
+            function = core::LLVMGetNextFunction(function);
+        }
+    }
+    functions
+}
+
+/// Lists the instructions of a function in order.
+///
+/// # Parameters
+/// * `function` - The function.
+///
+/// # Returns
+/// The raw pointers of the instructions, block by block.
+pub(crate) fn function_instructions(function: LLVMValueRef) -> Vec<LLVMValueRef> {
+    function_blocks(function).into_iter().flat_map(block_instructions).collect()
+}
+
+/// Lists the `call` and `invoke` instructions of a function.
+///
+/// # Parameters
+/// * `function` - The function.
+///
+/// # Returns
+/// The raw pointers of the call sites, in order.
+fn call_sites(function: LLVMValueRef) -> Vec<LLVMValueRef> {
+    function_instructions(function).into_iter()
+        .filter(|instruction| unsafe { !core::LLVMIsACallInst(*instruction).is_null() || !core::LLVMIsAInvokeInst(*instruction).is_null() })
+        .collect()
+}
+
+/// Retrieves the function a call site calls, if it is called directly.
+///
+/// # Parameters
+/// * `call_site` - A `call` or `invoke` instruction.
+///
+/// # Returns
+/// An `Option` containing the called function, or `None` for calls through a function pointer
+/// and calls to inline assembly.
+fn direct_callee(call_site: LLVMValueRef) -> Option<LLVMValueRef> {
+    unsafe {
+        let called = core::LLVMGetCalledValue(call_site);
+        if called.is_null() || core::LLVMIsAFunction(called).is_null() {
+            None
+        } else {
+            Some(called)
+        }
+    }
+}
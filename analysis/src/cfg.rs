@@ -0,0 +1,327 @@
+//! This module provides functionality for extracting the control-flow graph of a function.
+//!
+//! The graph has one node per basic block, identified by its `BasicBlockTag`, and an edge from each
+//! block to every block its terminator may transfer control to.
+
+extern crate llvm_sys as llvm;
+use std::collections::HashMap;
+use llvm::{core, prelude::{LLVMBasicBlockRef, LLVMValueRef}, LLVMOpcode};
+use ir::core::{BasicBlockTag, IRManager, ValueTag};
+
+/// The kind of instruction ending a basic block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminatorKind {
+    /// An unconditional `br` to a single successor.
+    Branch,
+    /// A conditional `br` choosing between two successors.
+    ConditionalBranch,
+    /// A `switch` over a default successor and one successor per case.
+    Switch,
+    /// A `ret`, leaving the function.
+    Return,
+    /// An `unreachable`, which control never reaches.
+    Unreachable,
+    /// Any other terminator, such as `indirectbr` or `invoke`.
+    Other,
+}
+
+/// The control-flow graph of a function.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    function: ValueTag,
+    blocks: Vec<BasicBlockTag>,
+    terminators: HashMap<BasicBlockTag, TerminatorKind>,
+    successors: HashMap<BasicBlockTag, Vec<BasicBlockTag>>,
+    predecessors: HashMap<BasicBlockTag, Vec<BasicBlockTag>>,
+}
+
+impl ControlFlowGraph {
+    /// Builds the control-flow graph of a function.
+    ///
+    /// Basic blocks that were not created through the IRManager are stored in it, so every node
+    /// of the graph has a tag.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the function.
+    /// * `function_tag` - The tag of the function to analyze.
+    ///
+    /// # Returns
+    /// Returns `Ok(ControlFlowGraph)` on success, or `Err(String)` if the tag does not refer to a
+    /// function with a body, or one of its blocks has no terminator.
+    pub fn new(manager: &mut IRManager, function_tag: ValueTag) -> Result<Self, String> {
+        let function_ptr = get_function_ptr(manager, function_tag)?;
+
+        let mut blocks = Vec::new();
+        let mut terminators = HashMap::new();
+        let mut successors = HashMap::new();
+        let mut predecessors: HashMap<BasicBlockTag, Vec<BasicBlockTag>> = HashMap::new();
+
+        let mut block_successors = Vec::new();
+        for block_ptr in function_blocks(function_ptr) {
+            let block_tag = manager.get_or_store_basic_block(block_ptr).ok_or("Failed to store basic block.")?;
+            let (kind, successor_ptrs) = terminator_of(block_ptr)
+                .ok_or_else(|| format!("Basic block {:?} has no terminator.", block_tag))?;
+            blocks.push(block_tag);
+            terminators.insert(block_tag, kind);
+            predecessors.insert(block_tag, Vec::new());
+            block_successors.push((block_tag, successor_ptrs));
+        }
+
+        for (block_tag, successor_ptrs) in block_successors {
+            let mut block_successor_tags: Vec<BasicBlockTag> = Vec::new();
+            for successor_ptr in successor_ptrs {
+                let successor_tag = manager.get_or_store_basic_block(successor_ptr).ok_or("Failed to store basic block.")?;
+                if !block_successor_tags.contains(&successor_tag) {
+                    block_successor_tags.push(successor_tag);
+                    predecessors.entry(successor_tag).or_default().push(block_tag);
+                }
+            }
+            successors.insert(block_tag, block_successor_tags);
+        }
+
+        Ok(Self { function: function_tag, blocks, terminators, successors, predecessors })
+    }
+
+    /// Retrieves the function the graph was built from.
+    ///
+    /// # Returns
+    /// The tag of the function.
+    pub fn get_function(&self) -> ValueTag {
+        self.function
+    }
+
+    /// Retrieves the entry block of the function.
+    ///
+    /// # Returns
+    /// The tag of the first basic block.
+    pub fn get_entry(&self) -> BasicBlockTag {
+        self.blocks[0]
+    }
+
+    /// Retrieves every basic block of the function.
+    ///
+    /// # Returns
+    /// The tags of the basic blocks, in the order they appear in the function.
+    pub fn get_blocks(&self) -> &[BasicBlockTag] {
+        &self.blocks
+    }
+
+    /// Retrieves the blocks control may transfer to from a block.
+    ///
+    /// A block reached by several cases of a terminator is listed once.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The successors in the order the terminator names them, or an empty slice if the block is not
+    /// part of the graph.
+    pub fn get_successors(&self, block: BasicBlockTag) -> &[BasicBlockTag] {
+        self.successors.get(&block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Retrieves the blocks control may transfer from to reach a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The predecessors in the order they appear in the function, or an empty slice if the block is
+    /// not part of the graph.
+    pub fn get_predecessors(&self, block: BasicBlockTag) -> &[BasicBlockTag] {
+        self.predecessors.get(&block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Retrieves the kind of terminator ending a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the terminator kind, or `None` if the block is not part of the graph.
+    pub fn get_terminator(&self, block: BasicBlockTag) -> Option<TerminatorKind> {
+        self.terminators.get(&block).copied()
+    }
+
+    /// Retrieves every block without successors, such as blocks ending in `ret` or `unreachable`.
+    ///
+    /// # Returns
+    /// The tags of the exit blocks, in the order they appear in the function.
+    pub fn get_exit_blocks(&self) -> Vec<BasicBlockTag> {
+        self.blocks.iter().copied().filter(|block| self.get_successors(*block).is_empty()).collect()
+    }
+
+    /// Retrieves every edge of the graph.
+    ///
+    /// # Returns
+    /// The edges as `(from, to)` pairs, grouped by source block in function order.
+    pub fn get_edges(&self) -> Vec<(BasicBlockTag, BasicBlockTag)> {
+        self.blocks.iter()
+            .flat_map(|block| self.get_successors(*block).iter().map(move |successor| (*block, *successor)))
+            .collect()
+    }
+
+    /// Orders the blocks reachable from the entry in reverse postorder.
+    ///
+    /// Every block appears before its successors, except along the back edges of loops, which makes
+    /// this the usual iteration order for forward dataflow problems.
+    ///
+    /// # Returns
+    /// The tags of the reachable blocks, starting with the entry.
+    pub fn get_reverse_postorder(&self) -> Vec<BasicBlockTag> {
+        let index: HashMap<BasicBlockTag, usize> = self.blocks.iter().enumerate().map(|(i, block)| (*block, i)).collect();
+        let successors: Vec<Vec<usize>> = self.blocks.iter()
+            .map(|block| self.get_successors(*block).iter().map(|successor| index[successor]).collect())
+            .collect();
+        reverse_postorder(&successors, 0).into_iter().map(|i| self.blocks[i]).collect()
+    }
+
+    /// Determines whether a block is part of the graph.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// True if the block belongs to the function, false otherwise.
+    pub fn contains(&self, block: BasicBlockTag) -> bool {
+        self.terminators.contains_key(&block)
+    }
+
+    /// Retrieves the number of basic blocks in the graph.
+    ///
+    /// # Returns
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Determines whether the graph has no nodes. Graphs are built from functions with a body, so
+    /// this is always false.
+    ///
+    /// # Returns
+    /// True if the graph has no basic blocks, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Orders the nodes reachable from a root in reverse postorder.
+///
+/// # Parameters
+/// * `successors` - The successors of each node.
+/// * `root` - The index of the root node.
+///
+/// # Returns
+/// The reachable nodes, each listed before its successors except along back edges.
+pub(crate) fn reverse_postorder(successors: &[Vec<usize>], root: usize) -> Vec<usize> {
+    let mut visited = vec![false; successors.len()];
+    let mut postorder = Vec::new();
+    let mut stack = vec![(root, 0)];
+    visited[root] = true;
+    while let Some((node, next_child)) = stack.pop() {
+        if next_child < successors[node].len() {
+            stack.push((node, next_child + 1));
+            let child = successors[node][next_child];
+            if !visited[child] {
+                visited[child] = true;
+                stack.push((child, 0));
+            }
+        } else {
+            postorder.push(node);
+        }
+    }
+    postorder.reverse();
+    postorder
+}
+
+/// Retrieves the raw pointer of a function with a body.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `function_tag` - The tag of the function.
+///
+/// # Returns
+/// Returns `Ok(LLVMValueRef)` on success, or `Err(String)` if the tag is unknown, does not refer to a
+/// function, or refers to a declaration.
+pub(crate) fn get_function_ptr(manager: &IRManager, function_tag: ValueTag) -> Result<LLVMValueRef, String> {
+    let function_ptr = manager.get_value_ptr(function_tag).ok_or_else(|| format!("Value {:?} not found.", function_tag))?;
+
+    unsafe {
+        if core::LLVMIsAFunction(function_ptr).is_null() {
+            return Err(format!("Value {:?} is not a function.", function_tag));
+        }
+        if core::LLVMCountBasicBlocks(function_ptr) == 0 {
+            return Err(format!("Function {:?} is a declaration without a body.", function_tag));
+        }
+    }
+    Ok(function_ptr)
+}
+
+/// Lists the basic blocks of a function in layout order.
+///
+/// # Parameters
+/// * `function` - The function.
+///
+/// # Returns
+/// The raw pointers of the blocks.
+pub(crate) fn function_blocks(function: LLVMValueRef) -> Vec<LLVMBasicBlockRef> {
+    let mut blocks = Vec::new();
+    unsafe {
+        let mut block = core::LLVMGetFirstBasicBlock(function);
+        while !block.is_null() {
+            blocks.push(block);
+            block = core::LLVMGetNextBasicBlock(block);
+        }
+    }
+    blocks
+}
+
+/// Lists the instructions of a block in order.
+///
+/// # Parameters
+/// * `block` - The basic block.
+///
+/// # Returns
+/// The raw pointers of the instructions.
+pub(crate) fn block_instructions(block: LLVMBasicBlockRef) -> Vec<LLVMValueRef> {
+    let mut instructions = Vec::new();
+    unsafe {
+        let mut instruction = core::LLVMGetFirstInstruction(block);
+        while !instruction.is_null() {
+            instructions.push(instruction);
+            instruction = core::LLVMGetNextInstruction(instruction);
+        }
+    }
+    instructions
+}
+
+/// Classifies the terminator of a block and lists its successors.
+///
+/// # Parameters
+/// * `block` - The basic block.
+///
+/// # Returns
+/// An `Option` containing the terminator kind and the successor blocks in operand order, or `None`
+/// if the block has no terminator.
+fn terminator_of(block: LLVMBasicBlockRef) -> Option<(TerminatorKind, Vec<LLVMBasicBlockRef>)> {
+    unsafe {
+        let terminator = core::LLVMGetBasicBlockTerminator(block);
+        if terminator.is_null() {
+            return None;
+        }
+
+        let kind = match core::LLVMGetInstructionOpcode(terminator) {
+            LLVMOpcode::LLVMBr if core::LLVMIsConditional(terminator) != 0 => TerminatorKind::ConditionalBranch,
+            LLVMOpcode::LLVMBr => TerminatorKind::Branch,
+            LLVMOpcode::LLVMSwitch => TerminatorKind::Switch,
+            LLVMOpcode::LLVMRet => TerminatorKind::Return,
+            LLVMOpcode::LLVMUnreachable => TerminatorKind::Unreachable,
+            _ => TerminatorKind::Other,
+        };
+
+        let successors = (0..core::LLVMGetNumSuccessors(terminator))
+            .map(|index| core::LLVMGetSuccessor(terminator, index))
+            .collect();
+        Some((kind, successors))
+    }
+}
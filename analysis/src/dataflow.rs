@@ -0,0 +1,628 @@
+//! This module provides a generic worklist solver for dataflow problems over the instructions of a
+//! function, together with liveness and reaching definitions as built-in problems.
+//!
+//! A problem chooses a direction, a lattice of facts, the fact at the function boundary and a
+//! transfer function per instruction. The solver snapshots the function into a `FunctionBody`,
+//! joins the facts flowing into each block and reapplies the transfer functions until no block
+//! changes, then records the facts before and after every instruction.
+
+extern crate llvm_sys as llvm;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use llvm::{core, prelude::LLVMValueRef, LLVMOpcode};
+use ir::core::{BasicBlockTag, IRManager, ValueTag};
+use crate::cfg::{block_instructions, ControlFlowGraph};
+
+/// The direction facts flow in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Facts flow from the entry block along the edges, as in reaching definitions.
+    Forward,
+    /// Facts flow from the exit blocks against the edges, as in liveness.
+    Backward,
+}
+
+/// A join semilattice of dataflow facts.
+pub trait Lattice: Clone + PartialEq {
+    /// Joins another fact into this one, moving it up the lattice.
+    ///
+    /// # Parameters
+    /// * `other` - The fact to join.
+    ///
+    /// # Returns
+    /// True if this fact changed, false otherwise.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+impl<T: Clone + Eq + Hash> Lattice for HashSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        let size = self.len();
+        self.extend(other.iter().cloned());
+        self.len() != size
+    }
+}
+
+/// A dataflow problem solved by `solve`.
+pub trait DataflowProblem {
+    /// The facts computed at every program point.
+    type Fact: Lattice;
+
+    /// Retrieves the direction of the problem.
+    ///
+    /// # Returns
+    /// The direction facts flow in.
+    fn direction(&self) -> Direction;
+
+    /// Creates the bottom of the lattice, which every program point starts from.
+    ///
+    /// # Returns
+    /// The initial fact.
+    fn initial(&self) -> Self::Fact;
+
+    /// Creates the fact holding at the function boundary: on entry for forward problems, and on
+    /// exit from blocks without successors for backward problems.
+    ///
+    /// # Returns
+    /// The boundary fact.
+    fn boundary(&self) -> Self::Fact;
+
+    /// Applies the effect of an instruction to a fact, in the direction of the problem.
+    ///
+    /// # Parameters
+    /// * `instruction` - The instruction.
+    /// * `fact` - The fact before the instruction in the direction of the problem, updated in place.
+    fn transfer(&self, instruction: &Instruction, fact: &mut Self::Fact);
+
+    /// Applies the effect of a control-flow edge to the fact flowing along it. By default edges
+    /// have no effect.
+    ///
+    /// # Parameters
+    /// * `from` - The block the edge leaves.
+    /// * `to` - The block the edge enters.
+    /// * `fact` - The fact flowing along the edge, updated in place. For forward problems this is the
+    ///   fact on exit from `from`, and for backward problems the fact on entry to `to`.
+    fn transfer_edge(&self, from: &Block, to: &Block, fact: &mut Self::Fact) {
+        let _ = (from, to, fact);
+    }
+}
+
+/// A snapshot of an instruction, with its operands resolved to tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    tag: ValueTag,
+    block: BasicBlockTag,
+    opcode: LLVMOpcode,
+    operands: Vec<ValueTag>,
+    incoming: Vec<(ValueTag, BasicBlockTag)>,
+    address: Option<ValueTag>,
+    defines_value: bool,
+}
+
+impl Instruction {
+    /// Retrieves the tag of the instruction, which is also the tag of the value it defines.
+    ///
+    /// # Returns
+    /// The tag of the instruction.
+    pub fn get_tag(&self) -> ValueTag {
+        self.tag
+    }
+
+    /// Retrieves the block containing the instruction.
+    ///
+    /// # Returns
+    /// The tag of the block.
+    pub fn get_block(&self) -> BasicBlockTag {
+        self.block
+    }
+
+    /// Retrieves the opcode of the instruction.
+    ///
+    /// # Returns
+    /// The LLVM opcode.
+    pub fn get_opcode(&self) -> LLVMOpcode {
+        self.opcode
+    }
+
+    /// Retrieves the variables the instruction uses: the operands that are instructions or
+    /// function parameters. Constants, globals and blocks are left out.
+    ///
+    /// # Returns
+    /// The tags of the used values, in operand order.
+    pub fn get_operands(&self) -> &[ValueTag] {
+        &self.operands
+    }
+
+    /// Retrieves the incoming values of a `phi` instruction that are variables.
+    ///
+    /// # Returns
+    /// The `(value, predecessor)` pairs, empty for other instructions.
+    pub fn get_incoming(&self) -> &[(ValueTag, BasicBlockTag)] {
+        &self.incoming
+    }
+
+    /// Retrieves the address a `load` reads or a `store` writes.
+    ///
+    /// # Returns
+    /// An `Option` containing the tag of the pointer operand, or `None` for other instructions.
+    pub fn get_address(&self) -> Option<ValueTag> {
+        self.address
+    }
+
+    /// Determines whether the instruction defines a value, i.e. whether its type is not `void`.
+    ///
+    /// # Returns
+    /// True if the instruction produces a value, false otherwise.
+    pub fn defines_value(&self) -> bool {
+        self.defines_value
+    }
+
+    /// Determines whether the instruction is a `phi`.
+    ///
+    /// # Returns
+    /// True for `phi` instructions, false otherwise.
+    pub fn is_phi(&self) -> bool {
+        self.opcode == LLVMOpcode::LLVMPHI
+    }
+}
+
+/// A snapshot of a basic block and its instructions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    tag: BasicBlockTag,
+    instructions: Vec<Instruction>,
+}
+
+impl Block {
+    /// Retrieves the tag of the block.
+    ///
+    /// # Returns
+    /// The tag of the block.
+    pub fn get_tag(&self) -> BasicBlockTag {
+        self.tag
+    }
+
+    /// Retrieves the instructions of the block.
+    ///
+    /// # Returns
+    /// The instructions, in order.
+    pub fn get_instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
+
+/// A snapshot of the blocks and instructions of a function, paired with its control-flow graph.
+#[derive(Debug, Clone)]
+pub struct FunctionBody<'a> {
+    cfg: &'a ControlFlowGraph,
+    blocks: Vec<Block>,
+    block_indices: HashMap<BasicBlockTag, usize>,
+}
+
+impl<'a> FunctionBody<'a> {
+    /// Snapshots the instructions of a function.
+    ///
+    /// Instructions and parameters that are not yet stored in the IRManager are stored while the
+    /// snapshot is taken, so every variable has a tag.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the function.
+    /// * `cfg` - The control-flow graph of the function.
+    ///
+    /// # Returns
+    /// Returns `Ok(FunctionBody)` on success, or `Err(String)` if a block or value could not be
+    /// retrieved or stored.
+    pub fn new(manager: &mut IRManager, cfg: &'a ControlFlowGraph) -> Result<Self, String> {
+        let mut blocks = Vec::with_capacity(cfg.len());
+        for block_tag in cfg.get_blocks() {
+            let block_ptr = manager.get_basic_block_ptr(*block_tag).ok_or_else(|| format!("Basic block {:?} not found.", block_tag))?;
+            let instructions = block_instructions(block_ptr).into_iter()
+                .map(|instruction| snapshot_instruction(manager, *block_tag, instruction))
+                .collect::<Result<Vec<_>, _>>()?;
+            blocks.push(Block { tag: *block_tag, instructions });
+        }
+        let block_indices = blocks.iter().enumerate().map(|(i, block)| (block.tag, i)).collect();
+        Ok(Self { cfg, blocks, block_indices })
+    }
+
+    /// Retrieves the control-flow graph of the function.
+    ///
+    /// # Returns
+    /// The control-flow graph the snapshot was taken from.
+    pub fn get_cfg(&self) -> &ControlFlowGraph {
+        self.cfg
+    }
+
+    /// Retrieves the blocks of the function.
+    ///
+    /// # Returns
+    /// The blocks, in function order.
+    pub fn get_blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Retrieves a block of the function.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the block, or `None` if it is not part of the function.
+    pub fn get_block(&self, block: BasicBlockTag) -> Option<&Block> {
+        self.block_indices.get(&block).map(|index| &self.blocks[*index])
+    }
+
+    /// Retrieves an instruction of the function.
+    ///
+    /// # Parameters
+    /// * `instruction` - The tag of the instruction.
+    ///
+    /// # Returns
+    /// An `Option` containing the instruction, or `None` if it is not part of the function.
+    pub fn get_instruction(&self, instruction: ValueTag) -> Option<&Instruction> {
+        self.get_instructions().find(|candidate| candidate.tag == instruction)
+    }
+
+    /// Iterates over every instruction of the function.
+    ///
+    /// # Returns
+    /// An iterator over the instructions, block by block.
+    pub fn get_instructions(&self) -> impl Iterator<Item = &Instruction> {
+        self.blocks.iter().flat_map(|block| block.instructions.iter())
+    }
+}
+
+/// The solution of a dataflow problem.
+///
+/// Facts are always reported in program order: the entry of a block or instruction is the point
+/// before it executes and the exit is the point after, whatever the direction of the problem.
+#[derive(Debug, Clone)]
+pub struct DataflowResults<F> {
+    block_entries: HashMap<BasicBlockTag, F>,
+    block_exits: HashMap<BasicBlockTag, F>,
+    instruction_entries: HashMap<ValueTag, F>,
+    instruction_exits: HashMap<ValueTag, F>,
+    iterations: usize,
+}
+
+impl<F> DataflowResults<F> {
+    /// Retrieves the fact on entry to a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the fact, or `None` if the block is not part of the function.
+    pub fn get_block_entry(&self, block: BasicBlockTag) -> Option<&F> {
+        self.block_entries.get(&block)
+    }
+
+    /// Retrieves the fact on exit from a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the fact, or `None` if the block is not part of the function.
+    pub fn get_block_exit(&self, block: BasicBlockTag) -> Option<&F> {
+        self.block_exits.get(&block)
+    }
+
+    /// Retrieves the fact just before an instruction.
+    ///
+    /// # Parameters
+    /// * `instruction` - The tag of the instruction.
+    ///
+    /// # Returns
+    /// An `Option` containing the fact, or `None` if the instruction is not part of the function.
+    pub fn get_instruction_entry(&self, instruction: ValueTag) -> Option<&F> {
+        self.instruction_entries.get(&instruction)
+    }
+
+    /// Retrieves the fact just after an instruction.
+    ///
+    /// # Parameters
+    /// * `instruction` - The tag of the instruction.
+    ///
+    /// # Returns
+    /// An `Option` containing the fact, or `None` if the instruction is not part of the function.
+    pub fn get_instruction_exit(&self, instruction: ValueTag) -> Option<&F> {
+        self.instruction_exits.get(&instruction)
+    }
+
+    /// Retrieves the number of times a block was processed before the solution stabilized.
+    ///
+    /// # Returns
+    /// The number of block visits, at least the number of blocks.
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+/// Solves a dataflow problem with a worklist of blocks.
+///
+/// Blocks are first visited in reverse postorder for forward problems and in postorder for
+/// backward problems, followed by unreachable blocks, and are revisited whenever a fact flowing
+/// into them changes.
+///
+/// # Parameters
+/// * `body` - The function to analyze.
+/// * `problem` - The dataflow problem.
+///
+/// # Returns
+/// The facts at the boundaries of every block and instruction.
+pub fn solve<P: DataflowProblem>(body: &FunctionBody, problem: &P) -> DataflowResults<P::Fact> {
+    let cfg = body.cfg;
+    let direction = problem.direction();
+    let mut order = cfg.get_reverse_postorder();
+    let unreachable: Vec<BasicBlockTag> = cfg.get_blocks().iter().copied().filter(|block| !order.contains(block)).collect();
+    order.extend(unreachable);
+    if direction == Direction::Backward {
+        order.reverse();
+    }
+
+    // `inputs` holds the fact flowing into each block in the direction of the problem, and
+    // `outputs` the fact leaving it.
+    let mut inputs: HashMap<BasicBlockTag, P::Fact> = order.iter().map(|block| (*block, problem.initial())).collect();
+    let mut outputs = inputs.clone();
+    let mut worklist: VecDeque<BasicBlockTag> = order.iter().copied().collect();
+    let mut queued: HashSet<BasicBlockTag> = order.iter().copied().collect();
+    let mut iterations = 0;
+
+    while let Some(block_tag) = worklist.pop_front() {
+        queued.remove(&block_tag);
+        iterations += 1;
+        let block = &body.blocks[body.block_indices[&block_tag]];
+
+        let (sources, targets) = match direction {
+            Direction::Forward => (cfg.get_predecessors(block_tag), cfg.get_successors(block_tag)),
+            Direction::Backward => (cfg.get_successors(block_tag), cfg.get_predecessors(block_tag)),
+        };
+        let at_boundary = match direction {
+            Direction::Forward => block_tag == cfg.get_entry(),
+            Direction::Backward => sources.is_empty(),
+        };
+
+        let mut input = if at_boundary { problem.boundary() } else { problem.initial() };
+        for source_tag in sources {
+            let source = &body.blocks[body.block_indices[source_tag]];
+            let mut fact = outputs[source_tag].clone();
+            match direction {
+                Direction::Forward => problem.transfer_edge(source, block, &mut fact),
+                Direction::Backward => problem.transfer_edge(block, source, &mut fact),
+            }
+            input.join(&fact);
+        }
+
+        let output = transfer_block(problem, block, &input, |_, _, _| {});
+        inputs.insert(block_tag, input);
+        if outputs[&block_tag] != output {
+            outputs.insert(block_tag, output);
+            for target in targets {
+                if queued.insert(*target) {
+                    worklist.push_back(*target);
+                }
+            }
+        }
+    }
+
+    let mut instruction_entries = HashMap::new();
+    let mut instruction_exits = HashMap::new();
+    for block in &body.blocks {
+        transfer_block(problem, block, &inputs[&block.tag], |instruction, before, after| {
+            let (entry, exit) = match direction {
+                Direction::Forward => (before, after),
+                Direction::Backward => (after, before),
+            };
+            instruction_entries.insert(instruction.tag, entry.clone());
+            instruction_exits.insert(instruction.tag, exit.clone());
+        });
+    }
+
+    let (block_entries, block_exits) = match direction {
+        Direction::Forward => (inputs, outputs),
+        Direction::Backward => (outputs, inputs),
+    };
+    DataflowResults { block_entries, block_exits, instruction_entries, instruction_exits, iterations }
+}
+
+/// Applies the transfer function of every instruction of a block, in the direction of the problem.
+///
+/// # Parameters
+/// * `problem` - The dataflow problem.
+/// * `block` - The block.
+/// * `input` - The fact flowing into the block.
+/// * `visit` - Called with each instruction and the facts before and after it is transferred.
+///
+/// # Returns
+/// The fact flowing out of the block.
+fn transfer_block<P: DataflowProblem>(problem: &P, block: &Block, input: &P::Fact, mut visit: impl FnMut(&Instruction, &P::Fact, &P::Fact)) -> P::Fact {
+    let mut fact = input.clone();
+    let mut step = |instruction: &Instruction| {
+        let before = fact.clone();
+        problem.transfer(instruction, &mut fact);
+        visit(instruction, &before, &fact);
+    };
+    match problem.direction() {
+        Direction::Forward => block.instructions.iter().for_each(&mut step),
+        Direction::Backward => block.instructions.iter().rev().for_each(&mut step),
+    }
+    fact
+}
+
+/// Liveness of variables: a variable is live at a point if some path from the point uses it before
+/// it is redefined.
+///
+/// The operands of a `phi` are live on exit from the predecessor they come from, not on entry to
+/// the block of the `phi`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Liveness;
+
+impl DataflowProblem for Liveness {
+    type Fact = HashSet<ValueTag>;
+
+    fn direction(&self) -> Direction {
+        Direction::Backward
+    }
+
+    fn initial(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn transfer(&self, instruction: &Instruction, fact: &mut Self::Fact) {
+        fact.remove(&instruction.tag);
+        if !instruction.is_phi() {
+            fact.extend(instruction.operands.iter().copied());
+        }
+    }
+
+    fn transfer_edge(&self, from: &Block, to: &Block, fact: &mut Self::Fact) {
+        for phi in to.instructions.iter().filter(|instruction| instruction.is_phi()) {
+            fact.extend(phi.incoming.iter().filter(|(_, predecessor)| *predecessor == from.tag).map(|(value, _)| *value));
+        }
+    }
+}
+
+/// Reaching definitions: a definition reaches a point if some path from the definition to the
+/// point does not overwrite it.
+///
+/// Definitions are the instructions that produce a value, which are never overwritten in SSA form,
+/// and the `store` instructions, each of which overwrites the earlier stores to the same address
+/// operand.
+#[derive(Debug, Clone, Default)]
+pub struct ReachingDefinitions {
+    store_addresses: HashMap<ValueTag, ValueTag>,
+}
+
+impl ReachingDefinitions {
+    /// Constructs the problem for a function.
+    ///
+    /// # Parameters
+    /// * `body` - The function to analyze.
+    ///
+    /// # Returns
+    /// A new instance of `ReachingDefinitions`.
+    pub fn new(body: &FunctionBody) -> Self {
+        let store_addresses = body.get_instructions()
+            .filter(|instruction| instruction.opcode == LLVMOpcode::LLVMStore)
+            .filter_map(|store| store.address.map(|address| (store.tag, address)))
+            .collect();
+        Self { store_addresses }
+    }
+}
+
+impl DataflowProblem for ReachingDefinitions {
+    type Fact = HashSet<ValueTag>;
+
+    fn direction(&self) -> Direction {
+        Direction::Forward
+    }
+
+    fn initial(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn transfer(&self, instruction: &Instruction, fact: &mut Self::Fact) {
+        if instruction.opcode == LLVMOpcode::LLVMStore {
+            if let Some(address) = instruction.address {
+                fact.retain(|definition| self.store_addresses.get(definition) != Some(&address));
+            }
+            fact.insert(instruction.tag);
+        } else if instruction.defines_value {
+            fact.insert(instruction.tag);
+        }
+    }
+}
+
+/// Computes the live variables of a function.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `cfg` - The control-flow graph of the function.
+///
+/// # Returns
+/// Returns `Ok(DataflowResults)` with the live variables at every point, or `Err(String)` if the
+/// function could not be snapshotted.
+pub fn liveness(manager: &mut IRManager, cfg: &ControlFlowGraph) -> Result<DataflowResults<HashSet<ValueTag>>, String> {
+    let body = FunctionBody::new(manager, cfg)?;
+    Ok(solve(&body, &Liveness))
+}
+
+/// Computes the reaching definitions of a function.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `cfg` - The control-flow graph of the function.
+///
+/// # Returns
+/// Returns `Ok(DataflowResults)` with the definitions reaching every point, or `Err(String)` if the
+/// function could not be snapshotted.
+pub fn reaching_definitions(manager: &mut IRManager, cfg: &ControlFlowGraph) -> Result<DataflowResults<HashSet<ValueTag>>, String> {
+    let body = FunctionBody::new(manager, cfg)?;
+    Ok(solve(&body, &ReachingDefinitions::new(&body)))
+}
+
+/// Snapshots an instruction.
+///
+/// # Parameters
+/// * `manager` - The IRManager to look values up in.
+/// * `block` - The tag of the block containing the instruction.
+/// * `instruction` - The instruction.
+///
+/// # Returns
+/// Returns `Ok(Instruction)` on success, or `Err(String)` if a value could not be stored.
+fn snapshot_instruction(manager: &mut IRManager, block: BasicBlockTag, instruction: LLVMValueRef) -> Result<Instruction, String> {
+    let tag = manager.get_or_store_value(instruction).ok_or("Failed to store value.")?;
+    let opcode = unsafe { core::LLVMGetInstructionOpcode(instruction) };
+    let defines_value = unsafe { core::LLVMGetTypeKind(core::LLVMTypeOf(instruction)) != llvm::LLVMTypeKind::LLVMVoidTypeKind };
+
+    let mut operands = Vec::new();
+    let operand_count = unsafe { core::LLVMGetNumOperands(instruction) }.max(0) as u32;
+    for index in 0..operand_count {
+        let operand = unsafe { core::LLVMGetOperand(instruction, index) };
+        if is_variable(operand) {
+            operands.push(manager.get_or_store_value(operand).ok_or("Failed to store value.")?);
+        }
+    }
+
+    let mut incoming = Vec::new();
+    if opcode == LLVMOpcode::LLVMPHI {
+        for index in 0..unsafe { core::LLVMCountIncoming(instruction) } {
+            let value = unsafe { core::LLVMGetIncomingValue(instruction, index) };
+            if is_variable(value) {
+                let predecessor = manager.get_or_store_basic_block(unsafe { core::LLVMGetIncomingBlock(instruction, index) }).ok_or("Failed to store basic block.")?;
+                incoming.push((manager.get_or_store_value(value).ok_or("Failed to store value.")?, predecessor));
+            }
+        }
+    }
+
+    let address_index = match opcode {
+        LLVMOpcode::LLVMLoad => Some(0),
+        LLVMOpcode::LLVMStore => Some(1),
+        _ => None,
+    };
+    let address = match address_index {
+        Some(index) => Some(manager.get_or_store_value(unsafe { core::LLVMGetOperand(instruction, index) }).ok_or("Failed to store value.")?),
+        None => None,
+    };
+
+    Ok(Instruction { tag, block, opcode, operands, incoming, address, defines_value })
+}
+
+/// Determines whether a value is a variable: an instruction or a function parameter.
+///
+/// # Parameters
+/// * `value` - The value.
+///
+/// # Returns
+/// True if the value is a variable, false otherwise.
+fn is_variable(value: LLVMValueRef) -> bool {
+    unsafe { !value.is_null() && (!core::LLVMIsAInstruction(value).is_null() || !core::LLVMIsAArgument(value).is_null()) }
+}
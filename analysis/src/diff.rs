@@ -0,0 +1,419 @@
+//! This module provides a structural comparison of two modules.
+//!
+//! Functions are matched by name and blocks by position. Every instruction is printed with its
+//! local names replaced by canonical ones, `%argN` for parameters, `%bbN` for blocks and `%vN` for
+//! instructions, numbered in order of definition, so that two functions differing only in value
+//! names or numbering compare equal. The instructions of matching blocks are then compared line by
+//! line.
+
+extern crate llvm_sys as llvm;
+use std::{collections::HashMap, fmt};
+use llvm::{core, prelude::LLVMValueRef};
+use ir::{core::{IRManager, ModuleTag}, inspect::{block_name, print_type, print_value, value_name}};
+use crate::{callgraph::module_functions, cfg::{block_instructions, function_blocks}};
+
+/// A line of a block diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// An instruction present in both blocks.
+    Same(String),
+    /// An instruction only present in the first block.
+    Removed(String),
+    /// An instruction only present in the second block.
+    Added(String),
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffLine::Same(line) => write!(f, "  {}", line),
+            DiffLine::Removed(line) => write!(f, "- {}", line),
+            DiffLine::Added(line) => write!(f, "+ {}", line),
+        }
+    }
+}
+
+/// The comparison of two blocks at the same position in their functions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockDiff {
+    index: usize,
+    first_name: Option<String>,
+    second_name: Option<String>,
+    lines: Vec<DiffLine>,
+}
+
+impl BlockDiff {
+    /// Retrieves the position of the blocks in their functions.
+    ///
+    /// # Returns
+    /// The index of the blocks, 0 for the entry blocks.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Retrieves the name of the block in the first function.
+    ///
+    /// # Returns
+    /// An `Option` containing the name, or `None` if the first function has fewer blocks.
+    pub fn get_first_name(&self) -> Option<&str> {
+        self.first_name.as_deref()
+    }
+
+    /// Retrieves the name of the block in the second function.
+    ///
+    /// # Returns
+    /// An `Option` containing the name, or `None` if the second function has fewer blocks.
+    pub fn get_second_name(&self) -> Option<&str> {
+        self.second_name.as_deref()
+    }
+
+    /// Retrieves the line-by-line comparison of the blocks' instructions.
+    ///
+    /// # Returns
+    /// The diff lines, in order.
+    pub fn get_lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+
+    /// Determines whether the blocks have the same instructions.
+    ///
+    /// # Returns
+    /// True if every line is the same, false otherwise.
+    pub fn is_identical(&self) -> bool {
+        self.lines.iter().all(|line| matches!(line, DiffLine::Same(_)))
+    }
+}
+
+impl fmt::Display for BlockDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  block {} ({} / {}):",
+            self.index,
+            self.first_name.as_deref().unwrap_or("missing"),
+            self.second_name.as_deref().unwrap_or("missing")
+        )?;
+        for line in &self.lines {
+            writeln!(f, "    {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// The comparison of two functions with the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionDiff {
+    name: String,
+    first_signature: String,
+    second_signature: String,
+    blocks: Vec<BlockDiff>,
+}
+
+impl FunctionDiff {
+    /// Retrieves the name of the functions.
+    ///
+    /// # Returns
+    /// The function name.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves the signatures of the functions.
+    ///
+    /// # Returns
+    /// The function types of the first and second function, as written in textual IR.
+    pub fn get_signatures(&self) -> (&str, &str) {
+        (&self.first_signature, &self.second_signature)
+    }
+
+    /// Retrieves the comparison of every block position.
+    ///
+    /// # Returns
+    /// The block diffs, one per block of the longer function.
+    pub fn get_blocks(&self) -> &[BlockDiff] {
+        &self.blocks
+    }
+
+    /// Determines whether the functions have the same signature and instructions.
+    ///
+    /// # Returns
+    /// True if the functions are structurally identical, false otherwise.
+    pub fn is_identical(&self) -> bool {
+        self.first_signature == self.second_signature && self.blocks.iter().all(BlockDiff::is_identical)
+    }
+}
+
+impl fmt::Display for FunctionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "function '{}':", self.name)?;
+        if self.first_signature != self.second_signature {
+            writeln!(f, "  - signature {}", self.first_signature)?;
+            writeln!(f, "  + signature {}", self.second_signature)?;
+        }
+        for block in self.blocks.iter().filter(|block| !block.is_identical()) {
+            write!(f, "{}", block)?;
+        }
+        Ok(())
+    }
+}
+
+/// The structural comparison of two modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleDiff {
+    only_in_first: Vec<String>,
+    only_in_second: Vec<String>,
+    functions: Vec<FunctionDiff>,
+}
+
+impl ModuleDiff {
+    /// Retrieves the functions missing from the second module.
+    ///
+    /// # Returns
+    /// The function names, in the order of the first module.
+    pub fn get_only_in_first(&self) -> &[String] {
+        &self.only_in_first
+    }
+
+    /// Retrieves the functions missing from the first module.
+    ///
+    /// # Returns
+    /// The function names, in the order of the second module.
+    pub fn get_only_in_second(&self) -> &[String] {
+        &self.only_in_second
+    }
+
+    /// Retrieves the comparison of every function present in both modules.
+    ///
+    /// # Returns
+    /// The function diffs, in the order of the first module.
+    pub fn get_functions(&self) -> &[FunctionDiff] {
+        &self.functions
+    }
+
+    /// Retrieves the comparison of a function present in both modules.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the function.
+    ///
+    /// # Returns
+    /// An `Option` containing the function diff, or `None` if either module lacks the function.
+    pub fn get_function(&self, name: &str) -> Option<&FunctionDiff> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Determines whether the modules are structurally identical.
+    ///
+    /// # Returns
+    /// True if both modules have the same functions with the same signatures and instructions,
+    /// false otherwise.
+    pub fn is_identical(&self) -> bool {
+        self.only_in_first.is_empty() && self.only_in_second.is_empty() && self.functions.iter().all(FunctionDiff::is_identical)
+    }
+}
+
+impl fmt::Display for ModuleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "modules are identical");
+        }
+        for name in &self.only_in_first {
+            writeln!(f, "- function '{}' only in first module", name)?;
+        }
+        for name in &self.only_in_second {
+            writeln!(f, "+ function '{}' only in second module", name)?;
+        }
+        for function in self.functions.iter().filter(|function| !function.is_identical()) {
+            write!(f, "{}", function)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two modules function by function and block by block, ignoring value names and
+/// numbering.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning both modules.
+/// * `first` - The tag of the first module, such as the reference IR.
+/// * `second` - The tag of the second module, such as the IR under test.
+///
+/// # Returns
+/// Returns `Ok(ModuleDiff)` on success, or `Err(String)` if either module could not be found.
+pub fn module_diff(manager: &IRManager, first: ModuleTag, second: ModuleTag) -> Result<ModuleDiff, String> {
+    let first_functions = module_functions(manager.get_module_ptr(first).ok_or_else(|| format!("Module {:?} not found.", first))?);
+    let second_functions = module_functions(manager.get_module_ptr(second).ok_or_else(|| format!("Module {:?} not found.", second))?);
+    let second_by_name: HashMap<String, LLVMValueRef> = second_functions.iter().map(|function| (unsafe { value_name(*function) }, *function)).collect();
+    let first_names: Vec<String> = first_functions.iter().map(|function| unsafe { value_name(*function) }).collect();
+
+    let mut diff = ModuleDiff { only_in_first: Vec::new(), only_in_second: Vec::new(), functions: Vec::new() };
+    for (name, function) in first_names.iter().zip(&first_functions) {
+        match second_by_name.get(name) {
+            Some(other) => diff.functions.push(function_diff(name, *function, *other)),
+            None => diff.only_in_first.push(name.clone()),
+        }
+    }
+    diff.only_in_second = second_functions.iter()
+        .map(|function| unsafe { value_name(*function) })
+        .filter(|name| !first_names.contains(name))
+        .collect();
+    Ok(diff)
+}
+
+/// Compares two functions block by block.
+///
+/// # Parameters
+/// * `name` - The name of the functions.
+/// * `first` - The first function.
+/// * `second` - The second function.
+///
+/// # Returns
+/// The function diff.
+fn function_diff(name: &str, first: LLVMValueRef, second: LLVMValueRef) -> FunctionDiff {
+    let first_blocks = canonical_blocks(first);
+    let second_blocks = canonical_blocks(second);
+
+    let blocks = (0..first_blocks.len().max(second_blocks.len()))
+        .map(|index| {
+            let (first_name, first_lines) = first_blocks.get(index).cloned().unzip();
+            let (second_name, second_lines) = second_blocks.get(index).cloned().unzip();
+            BlockDiff {
+                index,
+                first_name,
+                second_name,
+                lines: diff_lines(&first_lines.unwrap_or_default(), &second_lines.unwrap_or_default()),
+            }
+        })
+        .collect();
+
+    FunctionDiff {
+        name: name.to_string(),
+        first_signature: unsafe { print_type(core::LLVMGlobalGetValueType(first)) },
+        second_signature: unsafe { print_type(core::LLVMGlobalGetValueType(second)) },
+        blocks,
+    }
+}
+
+/// Prints the instructions of a function with canonical local names.
+///
+/// # Parameters
+/// * `function` - The function.
+///
+/// # Returns
+/// The name and canonical instructions of every block, in order.
+fn canonical_blocks(function: LLVMValueRef) -> Vec<(String, Vec<String>)> {
+    let blocks = function_blocks(function);
+
+    // Unnamed values are printed with the slot numbers LLVM assigns in order of definition:
+    // parameters first, then each block followed by its instructions.
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut next_slot = 0;
+    let mut printed_name = |value: LLVMValueRef, defines_slot: bool| -> Option<String> {
+        let name = unsafe { value_name(value) };
+        if !name.is_empty() {
+            Some(name)
+        } else if defines_slot {
+            next_slot += 1;
+            Some((next_slot - 1).to_string())
+        } else {
+            None
+        }
+    };
+
+    for index in 0..unsafe { core::LLVMCountParams(function) } {
+        if let Some(name) = printed_name(unsafe { core::LLVMGetParam(function, index) }, true) {
+            names.insert(name, format!("arg{}", index));
+        }
+    }
+    let mut next_value = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        if let Some(name) = printed_name(unsafe { core::LLVMBasicBlockAsValue(*block) }, true) {
+            names.insert(name, format!("bb{}", index));
+        }
+        for instruction in block_instructions(*block) {
+            let defines_value = unsafe { core::LLVMGetTypeKind(core::LLVMTypeOf(instruction)) } != llvm::LLVMTypeKind::LLVMVoidTypeKind;
+            if let Some(name) = printed_name(instruction, defines_value) {
+                names.insert(name, format!("v{}", next_value));
+                next_value += 1;
+            }
+        }
+    }
+
+    blocks.iter()
+        .map(|block| {
+            let lines = block_instructions(*block).into_iter().map(|instruction| rename_locals(&unsafe { print_value(instruction) }, &names)).collect();
+            (unsafe { block_name(*block) }, lines)
+        })
+        .collect()
+}
+
+/// Replaces the local names of a line of textual IR with their canonical names.
+///
+/// # Parameters
+/// * `line` - The printed instruction.
+/// * `names` - The canonical name of each local name, without the `%` sigil.
+///
+/// # Returns
+/// The line with every known local name replaced. Unknown names, such as named struct types, are
+/// kept as they are.
+fn rename_locals(line: &str, names: &HashMap<String, String>) -> String {
+    let mut renamed = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('%') {
+        renamed.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, length) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || "-$._".contains(c))).unwrap_or(after.len());
+            (&after[..end], end)
+        };
+        match names.get(name) {
+            Some(canonical) if length > 0 => {
+                renamed.push('%');
+                renamed.push_str(canonical);
+            }
+            _ => renamed.push_str(&rest[start..start + 1 + length]),
+        }
+        rest = &after[length..];
+    }
+    renamed.push_str(rest);
+    renamed
+}
+
+/// Compares two sequences of lines using their longest common subsequence.
+///
+/// # Parameters
+/// * `first` - The lines of the first block.
+/// * `second` - The lines of the second block.
+///
+/// # Returns
+/// The diff lines, with removals listed before additions at each change.
+fn diff_lines(first: &[String], second: &[String]) -> Vec<DiffLine> {
+    // common[i][j] is the length of the longest common subsequence of first[i..] and second[j..].
+    let mut common = vec![vec![0usize; second.len() + 1]; first.len() + 1];
+    for i in (0..first.len()).rev() {
+        for j in (0..second.len()).rev() {
+            common[i][j] = if first[i] == second[j] { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() || j < second.len() {
+        if i < first.len() && j < second.len() && first[i] == second[j] {
+            lines.push(DiffLine::Same(first[i].clone()));
+            i += 1;
+            j += 1;
+        } else if j == second.len() || (i < first.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(DiffLine::Removed(first[i].clone()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(second[j].clone()));
+            j += 1;
+        }
+    }
+    lines
+}
@@ -0,0 +1,342 @@
+//! This module provides dominator and post-dominator analysis on control-flow graphs.
+//!
+//! A block `a` dominates a block `b` if every path from the entry to `b` passes through `a`, and
+//! post-dominates `b` if every path from `b` to an exit passes through `a`. Immediate dominators
+//! are computed with the iterative algorithm of Cooper, Harvey and Kennedy, and dominance frontiers
+//! by walking up the tree from the predecessors of each join point.
+
+use std::collections::HashMap;
+use ir::core::BasicBlockTag;
+use crate::cfg::{reverse_postorder, ControlFlowGraph};
+
+/// The dominator tree or post-dominator tree of a function.
+///
+/// Blocks that cannot be reached from the entry, or for post-dominators cannot reach an exit, are
+/// not part of the tree.
+#[derive(Debug, Clone)]
+pub struct DominatorTree {
+    post_dominators: bool,
+    roots: Vec<BasicBlockTag>,
+    immediate_dominators: HashMap<BasicBlockTag, BasicBlockTag>,
+    children: HashMap<BasicBlockTag, Vec<BasicBlockTag>>,
+    frontiers: HashMap<BasicBlockTag, Vec<BasicBlockTag>>,
+    intervals: HashMap<BasicBlockTag, (usize, usize)>,
+}
+
+impl DominatorTree {
+    /// Computes the dominator tree of a function, rooted at its entry block.
+    ///
+    /// # Parameters
+    /// * `cfg` - The control-flow graph of the function.
+    ///
+    /// # Returns
+    /// A new instance of `DominatorTree`.
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let blocks = cfg.get_blocks();
+        let index: HashMap<BasicBlockTag, usize> = blocks.iter().enumerate().map(|(i, block)| (*block, i)).collect();
+        let successors: Vec<Vec<usize>> = blocks.iter().map(|block| cfg.get_successors(*block).iter().map(|s| index[s]).collect()).collect();
+        let predecessors: Vec<Vec<usize>> = blocks.iter().map(|block| cfg.get_predecessors(*block).iter().map(|p| index[p]).collect()).collect();
+
+        Self::from_graph(blocks, &successors, &predecessors, index[&cfg.get_entry()], false)
+    }
+
+    /// Computes the post-dominator tree of a function.
+    ///
+    /// Every exit block is a root of the tree, as if all exits led to a single virtual exit.
+    ///
+    /// # Parameters
+    /// * `cfg` - The control-flow graph of the function.
+    ///
+    /// # Returns
+    /// A new instance of `DominatorTree` describing post-dominance.
+    pub fn post_dominators(cfg: &ControlFlowGraph) -> Self {
+        let blocks = cfg.get_blocks();
+        let index: HashMap<BasicBlockTag, usize> = blocks.iter().enumerate().map(|(i, block)| (*block, i)).collect();
+        let virtual_exit = blocks.len();
+
+        // Reverse every edge and connect the virtual exit to each exit block.
+        let mut successors: Vec<Vec<usize>> = blocks.iter().map(|block| cfg.get_predecessors(*block).iter().map(|p| index[p]).collect()).collect();
+        let mut predecessors: Vec<Vec<usize>> = blocks.iter().map(|block| cfg.get_successors(*block).iter().map(|s| index[s]).collect()).collect();
+        let exits: Vec<usize> = cfg.get_exit_blocks().iter().map(|block| index[block]).collect();
+        for exit in &exits {
+            predecessors[*exit].push(virtual_exit);
+        }
+        successors.push(exits);
+        predecessors.push(Vec::new());
+
+        Self::from_graph(blocks, &successors, &predecessors, virtual_exit, true)
+    }
+
+    /// Computes immediate dominators, the tree and dominance frontiers over an indexed graph.
+    ///
+    /// # Parameters
+    /// * `blocks` - The blocks, indexed by position. A root index past the end denotes a virtual node.
+    /// * `successors` - The successors of each node.
+    /// * `predecessors` - The predecessors of each node.
+    /// * `root` - The index of the root node.
+    /// * `post_dominators` - Whether the graph is reversed.
+    ///
+    /// # Returns
+    /// The dominator tree of the graph, without the virtual node.
+    fn from_graph(blocks: &[BasicBlockTag], successors: &[Vec<usize>], predecessors: &[Vec<usize>], root: usize, post_dominators: bool) -> Self {
+        let order = reverse_postorder(successors, root);
+        let mut position = vec![usize::MAX; successors.len()];
+        for (i, node) in order.iter().enumerate() {
+            position[*node] = i;
+        }
+
+        let mut idom: Vec<Option<usize>> = vec![None; successors.len()];
+        idom[root] = Some(root);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in order.iter().skip(1) {
+                let mut new_idom: Option<usize> = None;
+                for predecessor in &predecessors[*node] {
+                    if idom[*predecessor].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => *predecessor,
+                        Some(current) => intersect(&idom, &position, current, *predecessor),
+                    });
+                }
+                if new_idom.is_some() && idom[*node] != new_idom {
+                    idom[*node] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut frontiers: Vec<Vec<usize>> = vec![Vec::new(); successors.len()];
+        for node in &order {
+            let reachable: Vec<usize> = predecessors[*node].iter().copied().filter(|p| idom[*p].is_some()).collect();
+            if reachable.len() < 2 {
+                continue;
+            }
+            let node_idom = idom[*node].expect("Reachable nodes have an immediate dominator");
+            for predecessor in reachable {
+                let mut runner = predecessor;
+                while runner != node_idom {
+                    if !frontiers[runner].contains(node) {
+                        frontiers[runner].push(*node);
+                    }
+                    runner = idom[runner].expect("Reachable nodes have an immediate dominator");
+                }
+            }
+        }
+
+        let is_block = |node: usize| node < blocks.len();
+        let mut tree = Self {
+            post_dominators,
+            roots: Vec::new(),
+            immediate_dominators: HashMap::new(),
+            children: HashMap::new(),
+            frontiers: HashMap::new(),
+            intervals: HashMap::new(),
+        };
+
+        for node in order.iter().copied().filter(|node| is_block(*node)) {
+            let block = blocks[node];
+            tree.children.insert(block, Vec::new());
+            let frontier = frontiers[node].iter().copied().filter(|n| is_block(*n)).map(|n| blocks[n]).collect();
+            tree.frontiers.insert(block, frontier);
+            match idom[node] {
+                Some(parent) if parent != node && is_block(parent) => {
+                    tree.immediate_dominators.insert(block, blocks[parent]);
+                }
+                _ => tree.roots.push(block),
+            }
+        }
+        for block in blocks {
+            if let Some(parent) = tree.immediate_dominators.get(block).copied() {
+                tree.children.entry(parent).or_default().push(*block);
+            }
+        }
+
+        let mut counter = 0;
+        for root_block in tree.roots.clone() {
+            tree.number_subtree(root_block, &mut counter);
+        }
+        tree
+    }
+
+    /// Numbers the blocks of a subtree in depth-first order, so dominance can be checked by
+    /// comparing intervals.
+    ///
+    /// # Parameters
+    /// * `root` - The root of the subtree.
+    /// * `counter` - The next number to assign.
+    fn number_subtree(&mut self, root: BasicBlockTag, counter: &mut usize) {
+        let mut stack = vec![(root, false)];
+        let mut entered: HashMap<BasicBlockTag, usize> = HashMap::new();
+        while let Some((block, finished)) = stack.pop() {
+            if finished {
+                self.intervals.insert(block, (entered[&block], *counter));
+                *counter += 1;
+                continue;
+            }
+            entered.insert(block, *counter);
+            *counter += 1;
+            stack.push((block, true));
+            for child in self.get_children(block).iter().rev() {
+                stack.push((*child, false));
+            }
+        }
+    }
+
+    /// Determines whether this tree describes post-dominance.
+    ///
+    /// # Returns
+    /// True for post-dominator trees, false for dominator trees.
+    pub fn is_post_dominator_tree(&self) -> bool {
+        self.post_dominators
+    }
+
+    /// Retrieves the roots of the tree: the entry block, or for post-dominators every exit block.
+    ///
+    /// # Returns
+    /// The tags of the root blocks.
+    pub fn get_roots(&self) -> &[BasicBlockTag] {
+        &self.roots
+    }
+
+    /// Retrieves the immediate dominator of a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the immediate (post-)dominator, or `None` for roots and blocks that
+    /// are not part of the tree.
+    pub fn get_immediate_dominator(&self, block: BasicBlockTag) -> Option<BasicBlockTag> {
+        self.immediate_dominators.get(&block).copied()
+    }
+
+    /// Retrieves the blocks a block immediately dominates.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The children of the block in the tree, or an empty slice if it is not part of the tree.
+    pub fn get_children(&self, block: BasicBlockTag) -> &[BasicBlockTag] {
+        self.children.get(&block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Retrieves every block dominating a block, from the block itself up to its root.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The dominators of the block, or an empty vector if it is not part of the tree.
+    pub fn get_dominators(&self, block: BasicBlockTag) -> Vec<BasicBlockTag> {
+        if !self.contains(block) {
+            return Vec::new();
+        }
+        let mut dominators = vec![block];
+        let mut current = block;
+        while let Some(parent) = self.get_immediate_dominator(current) {
+            dominators.push(parent);
+            current = parent;
+        }
+        dominators
+    }
+
+    /// Determines whether one block (post-)dominates another. Every block dominates itself.
+    ///
+    /// # Parameters
+    /// * `a` - The potential dominator.
+    /// * `b` - The potentially dominated block.
+    ///
+    /// # Returns
+    /// True if `a` dominates `b`, false otherwise or if either block is not part of the tree.
+    pub fn dominates(&self, a: BasicBlockTag, b: BasicBlockTag) -> bool {
+        match (self.intervals.get(&a), self.intervals.get(&b)) {
+            (Some((a_start, a_end)), Some((b_start, b_end))) => a_start <= b_start && b_end <= a_end,
+            _ => false,
+        }
+    }
+
+    /// Determines whether one block (post-)dominates another, different block.
+    ///
+    /// # Parameters
+    /// * `a` - The potential dominator.
+    /// * `b` - The potentially dominated block.
+    ///
+    /// # Returns
+    /// True if `a` dominates `b` and they are different blocks, false otherwise.
+    pub fn strictly_dominates(&self, a: BasicBlockTag, b: BasicBlockTag) -> bool {
+        a != b && self.dominates(a, b)
+    }
+
+    /// Retrieves the dominance frontier of a block: the blocks where its dominance ends.
+    ///
+    /// For post-dominator trees this is the reverse dominance frontier, the blocks the given block
+    /// is control dependent on.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The frontier, or an empty slice if the block is not part of the tree.
+    pub fn get_dominance_frontier(&self, block: BasicBlockTag) -> &[BasicBlockTag] {
+        self.frontiers.get(&block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Computes the iterated dominance frontier of a set of blocks, where SSA construction places
+    /// phi nodes for a variable assigned in those blocks.
+    ///
+    /// # Parameters
+    /// * `blocks` - The blocks assigning the variable.
+    ///
+    /// # Returns
+    /// The blocks of the iterated frontier, in the order they were found.
+    pub fn get_iterated_dominance_frontier(&self, blocks: &[BasicBlockTag]) -> Vec<BasicBlockTag> {
+        let mut frontier: Vec<BasicBlockTag> = Vec::new();
+        let mut worklist: Vec<BasicBlockTag> = blocks.to_vec();
+        while let Some(block) = worklist.pop() {
+            for candidate in self.get_dominance_frontier(block) {
+                if !frontier.contains(candidate) {
+                    frontier.push(*candidate);
+                    worklist.push(*candidate);
+                }
+            }
+        }
+        frontier
+    }
+
+    /// Determines whether a block is part of the tree.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// True if the block is reachable from the entry, or for post-dominators reaches an exit.
+    pub fn contains(&self, block: BasicBlockTag) -> bool {
+        self.intervals.contains_key(&block)
+    }
+}
+
+/// Finds the nearest common dominator of two nodes.
+///
+/// # Parameters
+/// * `idom` - The immediate dominators computed so far.
+/// * `position` - The reverse postorder position of each node.
+/// * `a` - The first node.
+/// * `b` - The second node.
+///
+/// # Returns
+/// The index of the common dominator.
+fn intersect(idom: &[Option<usize>], position: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while position[a] > position[b] {
+            a = idom[a].expect("Processed nodes have an immediate dominator");
+        }
+        while position[b] > position[a] {
+            b = idom[b].expect("Processed nodes have an immediate dominator");
+        }
+    }
+    a
+}
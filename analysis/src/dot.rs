@@ -0,0 +1,254 @@
+//! This module provides functionality for rendering control-flow graphs and call graphs as
+//! Graphviz DOT text.
+//!
+//! The output can be turned into an image with `dot -Tsvg graph.dot -o graph.svg`.
+
+extern crate llvm_sys as llvm;
+use std::fmt::Write;
+use llvm::prelude::LLVMBasicBlockRef;
+use ir::{core::{BasicBlockTag, IRManager}, inspect::{block_name, print_value, value_name}};
+use crate::{callgraph::CallGraph, cfg::{block_instructions, get_function_ptr, ControlFlowGraph, TerminatorKind}};
+
+/// The color highlighted nodes and edges are drawn in unless another is chosen.
+pub const DEFAULT_HIGHLIGHT_COLOR: &str = "red";
+
+/// Options controlling how a graph is rendered.
+///
+/// `Node` identifies the nodes of the graph: `BasicBlockTag` for control-flow graphs, and function
+/// names for call graphs.
+#[derive(Debug, Clone)]
+pub struct DotOptions<Node> {
+    highlighted_nodes: Vec<Node>,
+    highlighted_edges: Vec<(Node, Node)>,
+    highlight_color: String,
+    show_instructions: bool,
+}
+
+impl<Node> Default for DotOptions<Node> {
+    fn default() -> Self {
+        Self {
+            highlighted_nodes: Vec::new(),
+            highlighted_edges: Vec::new(),
+            highlight_color: DEFAULT_HIGHLIGHT_COLOR.to_string(),
+            show_instructions: true,
+        }
+    }
+}
+
+impl<Node: PartialEq> DotOptions<Node> {
+    /// Constructs the default options: nothing highlighted, and blocks labelled with their instructions.
+    ///
+    /// # Returns
+    /// A new instance of `DotOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highlights a node.
+    ///
+    /// # Parameters
+    /// * `node` - The block or function to highlight.
+    ///
+    /// # Returns
+    /// The updated `DotOptions`.
+    pub fn highlight_node<N: Into<Node>>(mut self, node: N) -> Self {
+        self.highlighted_nodes.push(node.into());
+        self
+    }
+
+    /// Highlights an edge.
+    ///
+    /// # Parameters
+    /// * `from` - The block or function the edge leaves.
+    /// * `to` - The block or function the edge enters.
+    ///
+    /// # Returns
+    /// The updated `DotOptions`.
+    pub fn highlight_edge<N: Into<Node>>(mut self, from: N, to: N) -> Self {
+        self.highlighted_edges.push((from.into(), to.into()));
+        self
+    }
+
+    /// Sets the color highlighted nodes and edges are drawn in.
+    ///
+    /// # Parameters
+    /// * `color` - A Graphviz color name or `#rrggbb` value.
+    ///
+    /// # Returns
+    /// The updated `DotOptions`.
+    pub fn with_highlight_color(mut self, color: &str) -> Self {
+        self.highlight_color = color.to_string();
+        self
+    }
+
+    /// Chooses whether basic blocks are labelled with their instructions or only their names.
+    /// Call graphs are not affected.
+    ///
+    /// # Parameters
+    /// * `enabled` - Whether instructions are included in block labels.
+    ///
+    /// # Returns
+    /// The updated `DotOptions`.
+    pub fn with_instructions(mut self, enabled: bool) -> Self {
+        self.show_instructions = enabled;
+        self
+    }
+
+    /// Renders the attributes highlighting a node, if it is highlighted.
+    ///
+    /// # Parameters
+    /// * `node` - The node being rendered.
+    ///
+    /// # Returns
+    /// The attributes to append to the node, starting with a comma, or an empty string.
+    fn node_style(&self, node: &Node) -> String {
+        if self.highlighted_nodes.contains(node) {
+            format!(", color=\"{0}\", fontcolor=\"{0}\", penwidth=2", escape(&self.highlight_color))
+        } else {
+            String::new()
+        }
+    }
+
+    /// Renders the attributes highlighting an edge, if it is highlighted.
+    ///
+    /// # Parameters
+    /// * `from` - The node the edge leaves.
+    /// * `to` - The node the edge enters.
+    ///
+    /// # Returns
+    /// The attributes to append to the edge, starting with a comma, or an empty string.
+    fn edge_style(&self, from: &Node, to: &Node) -> String {
+        if self.highlighted_edges.iter().any(|(edge_from, edge_to)| edge_from == from && edge_to == to) {
+            format!(", color=\"{}\", penwidth=2", escape(&self.highlight_color))
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Renders the control-flow graph of a function as DOT text.
+///
+/// Each block is labelled with its name and, unless disabled, its instructions. The edges leaving a
+/// conditional branch are labelled `true` and `false`.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `cfg` - The control-flow graph to render.
+/// * `options` - Highlighting and labelling options.
+///
+/// # Returns
+/// Returns `Ok(String)` containing the DOT text, or `Err(String)` if the function or one of its
+/// blocks could not be found.
+pub fn cfg_to_dot(manager: &IRManager, cfg: &ControlFlowGraph, options: &DotOptions<BasicBlockTag>) -> Result<String, String> {
+    let function_name = unsafe { value_name(get_function_ptr(manager, cfg.get_function())?) };
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph \"CFG for '{}'\" {{", escape(&function_name));
+    let _ = writeln!(dot, "    node [shape=box, fontname=\"monospace\"];");
+
+    for block in cfg.get_blocks() {
+        let block_ptr = manager.get_basic_block_ptr(*block).ok_or_else(|| format!("Basic block {:?} not found.", block))?;
+        let label = block_label(block_ptr, options.show_instructions);
+        let _ = writeln!(dot, "    {} [label=\"{}\"{}];", node_id(cfg, *block), label, options.node_style(block));
+    }
+
+    for block in cfg.get_blocks() {
+        let successors = cfg.get_successors(*block);
+        for (index, successor) in successors.iter().enumerate() {
+            let label = match cfg.get_terminator(*block) {
+                Some(TerminatorKind::ConditionalBranch) if successors.len() == 2 => {
+                    format!("label=\"{}\"", if index == 0 { "true" } else { "false" })
+                }
+                _ => String::new(),
+            };
+            let style = options.edge_style(block, successor);
+            let attributes = format!("{}{}", label, style).trim_start_matches(", ").to_string();
+            if attributes.is_empty() {
+                let _ = writeln!(dot, "    {} -> {};", node_id(cfg, *block), node_id(cfg, *successor));
+            } else {
+                let _ = writeln!(dot, "    {} -> {} [{}];", node_id(cfg, *block), node_id(cfg, *successor), attributes);
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+/// Renders a call graph as DOT text.
+///
+/// Functions defined in the module are drawn solid and declarations dashed.
+///
+/// # Parameters
+/// * `call_graph` - The call graph to render.
+/// * `options` - Highlighting options. Nodes and edges are identified by function name.
+///
+/// # Returns
+/// The DOT text.
+pub fn call_graph_to_dot(call_graph: &CallGraph, options: &DotOptions<String>) -> String {
+    let mut dot = String::new();
+    dot.push_str("digraph \"Call graph\" {\n");
+    dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+    for function in call_graph.get_functions() {
+        let dashed = if call_graph.is_declaration(function) { ", style=dashed" } else { "" };
+        let _ = writeln!(dot, "    \"{0}\" [label=\"{0}\"{1}{2}];", escape(function), dashed, options.node_style(function));
+    }
+
+    for (caller, callee) in call_graph.get_edges() {
+        let style = options.edge_style(&caller.to_string(), &callee.to_string());
+        let attributes = style.trim_start_matches(", ");
+        if attributes.is_empty() {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\";", escape(caller), escape(callee));
+        } else {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\" [{}];", escape(caller), escape(callee), attributes);
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Builds the DOT identifier of a block from its position in the function.
+///
+/// # Parameters
+/// * `cfg` - The graph containing the block.
+/// * `block` - The block.
+///
+/// # Returns
+/// An identifier such as `block0`.
+fn node_id(cfg: &ControlFlowGraph, block: BasicBlockTag) -> String {
+    let index = cfg.get_blocks().iter().position(|candidate| *candidate == block).unwrap_or(usize::MAX);
+    format!("block{}", index)
+}
+
+/// Builds the label of a block: its name followed by one left-aligned line per instruction.
+///
+/// # Parameters
+/// * `block` - The basic block.
+/// * `show_instructions` - Whether to include the instructions.
+///
+/// # Returns
+/// The escaped label.
+fn block_label(block: LLVMBasicBlockRef, show_instructions: bool) -> String {
+    let name = unsafe { block_name(block) };
+    if !show_instructions {
+        return escape(&name);
+    }
+
+    let mut label = format!("{}:\\l", escape(&name));
+    for instruction in block_instructions(block) {
+        let _ = write!(label, "  {}\\l", escape(&unsafe { print_value(instruction) }));
+    }
+    label
+}
+
+/// Escapes text for use inside a quoted DOT string.
+///
+/// # Parameters
+/// * `text` - The text to escape.
+///
+/// # Returns
+/// The text with backslashes, quotes and newlines escaped.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1,3 +1,32 @@
 //! Analysis Toolchain.
 
 pub mod validator;
+
+/// Control-flow graphs of functions, with basic blocks as nodes and the edges taken by their
+/// terminators.
+pub mod cfg;
+
+/// Call graphs of modules, with functions as nodes and direct calls as edges.
+pub mod callgraph;
+
+/// Rendering of control-flow graphs and call graphs as Graphviz DOT text.
+pub mod dot;
+
+/// Dominator trees, post-dominator trees and dominance frontiers of control-flow graphs.
+pub mod dominators;
+
+/// Natural loops of functions, with their headers, latches, exits and nesting.
+pub mod loops;
+
+/// Worklist dataflow analysis over the instructions of functions, with liveness and reaching
+/// definitions built in.
+pub mod dataflow;
+
+/// Lints for common code generation mistakes, reported with the function and block they occur in.
+pub mod lint;
+
+/// Size and complexity metrics of modules and functions.
+pub mod statistics;
+
+/// Structural comparison of modules, ignoring value names and numbering.
+pub mod diff;
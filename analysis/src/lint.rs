@@ -0,0 +1,277 @@
+//! This module provides a lint pass that detects common code generation mistakes before a module is
+//! handed to LLVM's verifier.
+//!
+//! Each finding names the function and block it was found in and explains the mistake in plain
+//! words. Lints only read the IR, so they can be run on modules the verifier would reject.
+
+extern crate llvm_sys as llvm;
+use std::{collections::HashSet, fmt};
+use llvm::{core, prelude::{LLVMBasicBlockRef, LLVMValueRef}, LLVMOpcode};
+use ir::{core::{IRManager, ModuleTag, ValueTag}, inspect::{block_name, print_value, value_name}};
+use crate::{callgraph::module_functions, cfg::{block_instructions, function_blocks}};
+
+/// The mistakes detected by the lint pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// A block does not end in a terminator such as `br` or `ret`.
+    MissingTerminator,
+    /// A block has instructions after its terminator, which are never executed.
+    InstructionAfterTerminator,
+    /// An `alloca` is outside the entry block, so it allocates stack space every time it executes.
+    AllocaOutsideEntry,
+    /// A stack slot is loaded from but never stored to, so the loads read undefined memory.
+    LoadFromUninitializedAlloca,
+    /// An instruction computes a value that is never used.
+    UnusedValue,
+    /// A block cannot be reached from the entry block.
+    UnreachableBlock,
+}
+
+impl LintKind {
+    /// Retrieves a short identifier for the lint.
+    ///
+    /// # Returns
+    /// The name of the lint in kebab case.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::MissingTerminator => "missing-terminator",
+            LintKind::InstructionAfterTerminator => "instruction-after-terminator",
+            LintKind::AllocaOutsideEntry => "alloca-outside-entry",
+            LintKind::LoadFromUninitializedAlloca => "load-from-uninitialized-alloca",
+            LintKind::UnusedValue => "unused-value",
+            LintKind::UnreachableBlock => "unreachable-block",
+        }
+    }
+}
+
+/// A mistake found by the lint pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    kind: LintKind,
+    function: String,
+    block: String,
+    message: String,
+}
+
+impl Lint {
+    /// Retrieves the kind of mistake.
+    ///
+    /// # Returns
+    /// The kind of the lint.
+    pub fn get_kind(&self) -> LintKind {
+        self.kind
+    }
+
+    /// Retrieves the name of the function containing the mistake.
+    ///
+    /// # Returns
+    /// The function name.
+    pub fn get_function(&self) -> &str {
+        &self.function
+    }
+
+    /// Retrieves the name of the block containing the mistake.
+    ///
+    /// # Returns
+    /// The block name, or `<block index>` for unnamed blocks.
+    pub fn get_block(&self) -> &str {
+        &self.block
+    }
+
+    /// Retrieves the explanation of the mistake.
+    ///
+    /// # Returns
+    /// The message, without the function and block names.
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] in function '{}', block '{}': {}", self.kind.as_str(), self.function, self.block, self.message)
+    }
+}
+
+/// Lints every function with a body in a module.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the module.
+/// * `module_tag` - The tag of the module to lint.
+///
+/// # Returns
+/// Returns `Ok(Vec<Lint>)` with the findings in module order, or `Err(String)` if the module could
+/// not be found.
+pub fn lint_module(manager: &IRManager, module_tag: ModuleTag) -> Result<Vec<Lint>, String> {
+    let module_ptr = manager.get_module_ptr(module_tag).ok_or_else(|| format!("Module {:?} not found.", module_tag))?;
+    Ok(module_functions(module_ptr).into_iter().flat_map(lint_function_ptr).collect())
+}
+
+/// Lints a function.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `function_tag` - The tag of the function to lint.
+///
+/// # Returns
+/// Returns `Ok(Vec<Lint>)` with the findings in block order, or `Err(String)` if the tag does not
+/// refer to a function. Declarations have no findings.
+pub fn lint_function(manager: &IRManager, function_tag: ValueTag) -> Result<Vec<Lint>, String> {
+    let function_ptr = manager.get_value_ptr(function_tag).ok_or_else(|| format!("Value {:?} not found.", function_tag))?;
+
+    if unsafe { core::LLVMIsAFunction(function_ptr) }.is_null() {
+        return Err(format!("Value {:?} is not a function.", function_tag));
+    }
+    Ok(lint_function_ptr(function_ptr))
+}
+
+/// Lints a function given its raw pointer.
+///
+/// # Parameters
+/// * `function` - The function.
+///
+/// # Returns
+/// The findings, block by block.
+fn lint_function_ptr(function: LLVMValueRef) -> Vec<Lint> {
+    let function_name = unsafe { value_name(function) };
+    let blocks = function_blocks(function);
+    let reachable = reachable_blocks(&blocks);
+    let mut lints = Vec::new();
+
+    for (index, block) in blocks.iter().enumerate() {
+        let name = unsafe { block_name(*block) };
+        let mut report = |kind: LintKind, message: String| {
+            lints.push(Lint { kind, function: function_name.clone(), block: name.clone(), message });
+        };
+
+        if !reachable.contains(block) {
+            report(LintKind::UnreachableBlock, "no branch from the entry block leads here, so this block never executes. Remove it or add a branch to it.".to_string());
+        }
+
+        let instructions = block_instructions(*block);
+        match instructions.iter().position(|instruction| is_terminator(*instruction)) {
+            None => report(LintKind::MissingTerminator, match instructions.last() {
+                Some(last) => format!("the block ends with `{}` instead of a terminator. Every block must end with a branch, `ret` or `unreachable`.", unsafe { print_value(*last) }),
+                None => "the block is empty. Every block must end with a branch, `ret` or `unreachable`.".to_string(),
+            }),
+            Some(position) if position + 1 < instructions.len() => {
+                let extra = instructions.len() - position - 1;
+                report(LintKind::InstructionAfterTerminator, format!(
+                    "{} instruction{} after the terminator `{}` will never run, starting with `{}`. Was the builder positioned at the end of a block that was already finished?",
+                    extra, if extra == 1 { "" } else { "s" }, unsafe { print_value(instructions[position]) }, unsafe { print_value(instructions[position + 1]) }
+                ));
+            }
+            Some(_) => {}
+        }
+
+        for instruction in &instructions {
+            let opcode = unsafe { core::LLVMGetInstructionOpcode(*instruction) };
+            if opcode == LLVMOpcode::LLVMAlloca {
+                if index != 0 {
+                    report(LintKind::AllocaOutsideEntry, format!(
+                        "`{}` is outside the entry block, so it reserves new stack space every time it runs. Move it to the entry block.",
+                        unsafe { print_value(*instruction) }
+                    ));
+                }
+                if is_loaded_but_never_stored(*instruction) {
+                    report(LintKind::LoadFromUninitializedAlloca, format!(
+                        "`{}` is loaded from but never stored to, so every load reads an undefined value. Store an initial value first.",
+                        unsafe { print_value(*instruction) }
+                    ));
+                }
+            }
+            if defines_value(*instruction) && !has_side_effects(opcode) && unsafe { core::LLVMGetFirstUse(*instruction) }.is_null() {
+                report(LintKind::UnusedValue, format!("the result of `{}` is never used.", unsafe { print_value(*instruction) }));
+            }
+        }
+    }
+
+    lints
+}
+
+/// Finds the blocks reachable from the entry block, following the first terminator of each block.
+///
+/// # Parameters
+/// * `blocks` - The blocks of the function, starting with the entry block.
+///
+/// # Returns
+/// The reachable blocks.
+fn reachable_blocks(blocks: &[LLVMBasicBlockRef]) -> HashSet<LLVMBasicBlockRef> {
+    let mut reachable = HashSet::new();
+    let mut worklist: Vec<LLVMBasicBlockRef> = blocks.first().copied().into_iter().collect();
+    while let Some(block) = worklist.pop() {
+        if !reachable.insert(block) {
+            continue;
+        }
+        if let Some(terminator) = block_instructions(block).into_iter().find(|instruction| is_terminator(*instruction)) {
+            for index in 0..unsafe { core::LLVMGetNumSuccessors(terminator) } {
+                worklist.push(unsafe { core::LLVMGetSuccessor(terminator, index) });
+            }
+        }
+    }
+    reachable
+}
+
+/// Determines whether an instruction is a terminator.
+///
+/// # Parameters
+/// * `instruction` - The instruction.
+///
+/// # Returns
+/// True if the instruction ends a block, false otherwise.
+fn is_terminator(instruction: LLVMValueRef) -> bool {
+    !unsafe { core::LLVMIsATerminatorInst(instruction) }.is_null()
+}
+
+/// Determines whether an instruction produces a value.
+///
+/// # Parameters
+/// * `instruction` - The instruction.
+///
+/// # Returns
+/// True if the type of the instruction is not `void`, false otherwise.
+fn defines_value(instruction: LLVMValueRef) -> bool {
+    unsafe { core::LLVMGetTypeKind(core::LLVMTypeOf(instruction)) != llvm::LLVMTypeKind::LLVMVoidTypeKind }
+}
+
+/// Determines whether instructions with an opcode may be executed for their side effects alone.
+///
+/// # Parameters
+/// * `opcode` - The opcode.
+///
+/// # Returns
+/// True for calls and atomic or exception-handling instructions, whose results may be ignored.
+fn has_side_effects(opcode: LLVMOpcode) -> bool {
+    matches!(
+        opcode,
+        LLVMOpcode::LLVMCall | LLVMOpcode::LLVMInvoke | LLVMOpcode::LLVMCallBr | LLVMOpcode::LLVMAtomicRMW
+            | LLVMOpcode::LLVMAtomicCmpXchg | LLVMOpcode::LLVMLandingPad | LLVMOpcode::LLVMCatchPad
+            | LLVMOpcode::LLVMCleanupPad | LLVMOpcode::LLVMVAArg
+    )
+}
+
+/// Determines whether an `alloca` is loaded from without ever being written.
+///
+/// Slots whose address escapes, for example by being passed to a call, may be written elsewhere and
+/// are never reported.
+///
+/// # Parameters
+/// * `alloca` - The `alloca` instruction.
+///
+/// # Returns
+/// True if the slot has at least one use and every use is a `load` from it, false otherwise.
+fn is_loaded_but_never_stored(alloca: LLVMValueRef) -> bool {
+    let mut loaded = false;
+    unsafe {
+        let mut value_use = core::LLVMGetFirstUse(alloca);
+        while !value_use.is_null() {
+            let user = core::LLVMGetUser(value_use);
+            if core::LLVMIsALoadInst(user).is_null() {
+                return false;
+            }
+            loaded = true;
+            value_use = core::LLVMGetNextUse(value_use);
+        }
+    }
+    loaded
+}
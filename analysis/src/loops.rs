@@ -0,0 +1,327 @@
+//! This module provides detection of natural loops and their nesting structure.
+//!
+//! An edge from `latch` to `header` is a back edge if `header` dominates `latch`. The natural loop
+//! of a header consists of the header and every block that can reach one of its latches without
+//! passing through the header. Loops sharing a header are merged, and a loop is nested in another
+//! if its header belongs to the other loop's body.
+
+use std::collections::HashMap;
+use ir::core::BasicBlockTag;
+use crate::{cfg::ControlFlowGraph, dominators::DominatorTree};
+
+/// A natural loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    header: BasicBlockTag,
+    latches: Vec<BasicBlockTag>,
+    blocks: Vec<BasicBlockTag>,
+    exiting_blocks: Vec<BasicBlockTag>,
+    exits: Vec<BasicBlockTag>,
+    preheader: Option<BasicBlockTag>,
+    parent: Option<BasicBlockTag>,
+    children: Vec<BasicBlockTag>,
+    depth: usize,
+}
+
+impl Loop {
+    /// Retrieves the header of the loop, the only block entered from outside it.
+    ///
+    /// # Returns
+    /// The tag of the header, which also identifies the loop.
+    pub fn get_header(&self) -> BasicBlockTag {
+        self.header
+    }
+
+    /// Retrieves the blocks branching back to the header.
+    ///
+    /// # Returns
+    /// The tags of the latches, in function order.
+    pub fn get_latches(&self) -> &[BasicBlockTag] {
+        &self.latches
+    }
+
+    /// Retrieves every block of the loop, including the blocks of nested loops.
+    ///
+    /// # Returns
+    /// The tags of the blocks, in function order.
+    pub fn get_blocks(&self) -> &[BasicBlockTag] {
+        &self.blocks
+    }
+
+    /// Retrieves the blocks of the loop with a successor outside it.
+    ///
+    /// # Returns
+    /// The tags of the exiting blocks, in function order.
+    pub fn get_exiting_blocks(&self) -> &[BasicBlockTag] {
+        &self.exiting_blocks
+    }
+
+    /// Retrieves the blocks outside the loop that control leaves it to.
+    ///
+    /// # Returns
+    /// The tags of the exit blocks, in the order they are first reached.
+    pub fn get_exits(&self) -> &[BasicBlockTag] {
+        &self.exits
+    }
+
+    /// Retrieves the preheader of the loop: the single block outside it that enters the header, if
+    /// that block has no other successor.
+    ///
+    /// # Returns
+    /// An `Option` containing the preheader, or `None` if the loop has none.
+    pub fn get_preheader(&self) -> Option<BasicBlockTag> {
+        self.preheader
+    }
+
+    /// Retrieves the header of the innermost loop containing this one.
+    ///
+    /// # Returns
+    /// An `Option` containing the parent loop's header, or `None` for top-level loops.
+    pub fn get_parent(&self) -> Option<BasicBlockTag> {
+        self.parent
+    }
+
+    /// Retrieves the headers of the loops directly nested in this one.
+    ///
+    /// # Returns
+    /// The headers of the child loops, in function order.
+    pub fn get_children(&self) -> &[BasicBlockTag] {
+        &self.children
+    }
+
+    /// Retrieves the nesting depth of the loop.
+    ///
+    /// # Returns
+    /// 1 for top-level loops, and one more than the parent's depth for nested loops.
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Determines whether a block belongs to the loop or one of its nested loops.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// True if the block is part of the loop, false otherwise.
+    pub fn contains(&self, block: BasicBlockTag) -> bool {
+        self.blocks.contains(&block)
+    }
+}
+
+/// The loops of a function.
+#[derive(Debug, Clone)]
+pub struct LoopInfo {
+    loops: Vec<Loop>,
+    back_edges: Vec<(BasicBlockTag, BasicBlockTag)>,
+    irreducible_edges: Vec<(BasicBlockTag, BasicBlockTag)>,
+}
+
+impl LoopInfo {
+    /// Finds the natural loops of a function.
+    ///
+    /// # Parameters
+    /// * `cfg` - The control-flow graph of the function.
+    ///
+    /// # Returns
+    /// A new instance of `LoopInfo`.
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let dominators = DominatorTree::new(cfg);
+        let order = cfg.get_reverse_postorder();
+        let position: HashMap<BasicBlockTag, usize> = order.iter().enumerate().map(|(i, block)| (*block, i)).collect();
+
+        let mut back_edges = Vec::new();
+        let mut irreducible_edges = Vec::new();
+        for (from, to) in cfg.get_edges() {
+            if !dominators.contains(from) {
+                continue;
+            }
+            if dominators.dominates(to, from) {
+                back_edges.push((from, to));
+            } else if position[&to] <= position[&from] {
+                // A retreating edge into a block that does not dominate its source enters a cycle
+                // through more than one block.
+                irreducible_edges.push((from, to));
+            }
+        }
+
+        let mut loops: Vec<Loop> = Vec::new();
+        for header in cfg.get_blocks().iter().copied() {
+            let latches: Vec<BasicBlockTag> = cfg.get_blocks().iter().copied()
+                .filter(|block| back_edges.contains(&(*block, header)))
+                .collect();
+            if latches.is_empty() {
+                continue;
+            }
+            loops.push(natural_loop(cfg, &dominators, header, latches));
+        }
+
+        // A loop's parent is the smallest other loop containing its header.
+        let parents: Vec<Option<BasicBlockTag>> = loops.iter()
+            .map(|inner| {
+                loops.iter()
+                    .filter(|outer| outer.header != inner.header && outer.contains(inner.header))
+                    .min_by_key(|outer| outer.blocks.len())
+                    .map(|outer| outer.header)
+            })
+            .collect();
+        for (inner, parent) in loops.iter_mut().zip(&parents) {
+            inner.parent = *parent;
+        }
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                let child = loops[index].header;
+                if let Some(outer) = loops.iter_mut().find(|outer| outer.header == *parent) {
+                    outer.children.push(child);
+                }
+            }
+        }
+        for index in 0..loops.len() {
+            let mut depth = 1;
+            let mut parent = loops[index].parent;
+            while let Some(header) = parent {
+                depth += 1;
+                parent = loops.iter().find(|outer| outer.header == header).and_then(|outer| outer.parent);
+            }
+            loops[index].depth = depth;
+        }
+
+        Self { loops, back_edges, irreducible_edges }
+    }
+
+    /// Retrieves every loop of the function.
+    ///
+    /// # Returns
+    /// The loops, ordered by the position of their headers in the function.
+    pub fn get_loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    /// Retrieves the loops that are not nested in another loop.
+    ///
+    /// # Returns
+    /// The top-level loops, ordered by the position of their headers in the function.
+    pub fn get_top_level_loops(&self) -> Vec<&Loop> {
+        self.loops.iter().filter(|l| l.parent.is_none()).collect()
+    }
+
+    /// Retrieves the loop with a given header.
+    ///
+    /// # Parameters
+    /// * `header` - The tag of the loop header.
+    ///
+    /// # Returns
+    /// An `Option` containing the loop, or `None` if the block is not a loop header.
+    pub fn get_loop(&self, header: BasicBlockTag) -> Option<&Loop> {
+        self.loops.iter().find(|l| l.header == header)
+    }
+
+    /// Retrieves the innermost loop containing a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// An `Option` containing the innermost loop, or `None` if the block is not in any loop.
+    pub fn get_innermost_loop(&self, block: BasicBlockTag) -> Option<&Loop> {
+        self.loops.iter().filter(|l| l.contains(block)).max_by_key(|l| l.depth)
+    }
+
+    /// Retrieves the number of loops containing a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// The loop nesting depth of the block, 0 if it is not in any loop.
+    pub fn get_loop_depth(&self, block: BasicBlockTag) -> usize {
+        self.get_innermost_loop(block).map(Loop::get_depth).unwrap_or(0)
+    }
+
+    /// Determines whether a block is the header of a loop.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// True if the block is the target of a back edge, false otherwise.
+    pub fn is_loop_header(&self, block: BasicBlockTag) -> bool {
+        self.get_loop(block).is_some()
+    }
+
+    /// Retrieves every back edge of the function.
+    ///
+    /// # Returns
+    /// The back edges as `(latch, header)` pairs, in function order of the latches.
+    pub fn get_back_edges(&self) -> &[(BasicBlockTag, BasicBlockTag)] {
+        &self.back_edges
+    }
+
+    /// Retrieves the edges entering a cycle that has no single header.
+    ///
+    /// Such cycles are not natural loops and are not reported by `get_loops`.
+    ///
+    /// # Returns
+    /// The retreating edges whose target does not dominate their source, as `(from, to)` pairs.
+    pub fn get_irreducible_edges(&self) -> &[(BasicBlockTag, BasicBlockTag)] {
+        &self.irreducible_edges
+    }
+
+    /// Determines whether every cycle of the function is a natural loop.
+    ///
+    /// # Returns
+    /// True if the control flow is reducible, false otherwise.
+    pub fn is_reducible(&self) -> bool {
+        self.irreducible_edges.is_empty()
+    }
+}
+
+/// Collects the natural loop of a header.
+///
+/// # Parameters
+/// * `cfg` - The control-flow graph of the function.
+/// * `dominators` - The dominator tree of the function.
+/// * `header` - The loop header.
+/// * `latches` - The sources of the back edges into the header.
+///
+/// # Returns
+/// The loop, without nesting information.
+fn natural_loop(cfg: &ControlFlowGraph, dominators: &DominatorTree, header: BasicBlockTag, latches: Vec<BasicBlockTag>) -> Loop {
+    let mut members = vec![header];
+    let mut worklist: Vec<BasicBlockTag> = latches.clone();
+    while let Some(block) = worklist.pop() {
+        // Unreachable blocks branching into the loop are not part of it.
+        if members.contains(&block) || !dominators.dominates(header, block) {
+            continue;
+        }
+        members.push(block);
+        worklist.extend(cfg.get_predecessors(block).iter().copied());
+    }
+
+    let blocks: Vec<BasicBlockTag> = cfg.get_blocks().iter().copied().filter(|block| members.contains(block)).collect();
+    let mut exiting_blocks = Vec::new();
+    let mut exits = Vec::new();
+    for block in &blocks {
+        for successor in cfg.get_successors(*block) {
+            if blocks.contains(successor) {
+                continue;
+            }
+            if !exiting_blocks.contains(block) {
+                exiting_blocks.push(*block);
+            }
+            if !exits.contains(successor) {
+                exits.push(*successor);
+            }
+        }
+    }
+
+    let outside_predecessors: Vec<BasicBlockTag> = cfg.get_predecessors(header).iter().copied()
+        .filter(|block| !blocks.contains(block))
+        .collect();
+    let preheader = match outside_predecessors.as_slice() {
+        [single] if cfg.get_successors(*single) == [header] => Some(*single),
+        _ => None,
+    };
+
+    Loop { header, latches, blocks, exiting_blocks, exits, preheader, parent: None, children: Vec::new(), depth: 1 }
+}
@@ -0,0 +1,335 @@
+//! This module provides size and complexity metrics for modules and their functions.
+//!
+//! Statistics are counted once when they are built, so they can be compared across compiler
+//! versions to track regressions in the size of generated IR.
+
+extern crate llvm_sys as llvm;
+use std::{collections::BTreeMap, fmt};
+use llvm::{core, prelude::LLVMValueRef, LLVMOpcode};
+use ir::{core::{IRManager, ModuleTag}, inspect::value_name};
+use crate::{callgraph::module_functions, cfg::{block_instructions, function_blocks, ControlFlowGraph}, loops::LoopInfo};
+
+/// Size and complexity metrics of a function with a body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStatistics {
+    name: String,
+    blocks: usize,
+    instructions: usize,
+    opcodes: BTreeMap<String, usize>,
+    allocas: usize,
+    calls: usize,
+    max_loop_depth: usize,
+    cyclomatic_complexity: usize,
+}
+
+impl FunctionStatistics {
+    /// Retrieves the name of the function.
+    ///
+    /// # Returns
+    /// The function name.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves the number of basic blocks.
+    ///
+    /// # Returns
+    /// The block count, including unreachable blocks.
+    pub fn get_block_count(&self) -> usize {
+        self.blocks
+    }
+
+    /// Retrieves the number of instructions.
+    ///
+    /// # Returns
+    /// The instruction count.
+    pub fn get_instruction_count(&self) -> usize {
+        self.instructions
+    }
+
+    /// Retrieves the number of instructions with each opcode.
+    ///
+    /// # Returns
+    /// The counts, keyed by the opcode as it is written in textual IR, such as `add` or `icmp`.
+    pub fn get_opcode_counts(&self) -> &BTreeMap<String, usize> {
+        &self.opcodes
+    }
+
+    /// Retrieves the number of instructions with an opcode.
+    ///
+    /// # Parameters
+    /// * `opcode` - The opcode as it is written in textual IR.
+    ///
+    /// # Returns
+    /// The count, 0 if the function has no such instruction.
+    pub fn get_opcode_count(&self, opcode: &str) -> usize {
+        self.opcodes.get(opcode).copied().unwrap_or(0)
+    }
+
+    /// Retrieves the number of `alloca` instructions.
+    ///
+    /// # Returns
+    /// The alloca count.
+    pub fn get_alloca_count(&self) -> usize {
+        self.allocas
+    }
+
+    /// Retrieves the number of call sites, counting `call`, `invoke` and `callbr` instructions.
+    ///
+    /// # Returns
+    /// The call count.
+    pub fn get_call_count(&self) -> usize {
+        self.calls
+    }
+
+    /// Retrieves the depth of the most deeply nested loop.
+    ///
+    /// # Returns
+    /// The maximum loop depth, 0 if the function has no loops.
+    pub fn get_max_loop_depth(&self) -> usize {
+        self.max_loop_depth
+    }
+
+    /// Retrieves the cyclomatic complexity, the number of linearly independent paths through the
+    /// function: `edges - blocks + 2` over the blocks reachable from the entry.
+    ///
+    /// # Returns
+    /// The cyclomatic complexity, 1 for straight-line code.
+    pub fn get_cyclomatic_complexity(&self) -> usize {
+        self.cyclomatic_complexity
+    }
+}
+
+impl fmt::Display for FunctionStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} blocks, {} instructions, {} allocas, {} calls, max loop depth {}, cyclomatic complexity {}",
+            self.name, self.blocks, self.instructions, self.allocas, self.calls, self.max_loop_depth, self.cyclomatic_complexity
+        )
+    }
+}
+
+/// Size and complexity metrics of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleStatistics {
+    functions: Vec<FunctionStatistics>,
+    declarations: usize,
+    globals: usize,
+}
+
+impl ModuleStatistics {
+    /// Collects the statistics of a module.
+    ///
+    /// Functions not yet stored in the IRManager are stored while their control-flow graphs are
+    /// built.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the module.
+    /// * `module_tag` - The tag of the module to measure.
+    ///
+    /// # Returns
+    /// Returns `Ok(ModuleStatistics)` on success, or `Err(String)` if the module could not be found or
+    /// one of its blocks has no terminator.
+    pub fn new(manager: &mut IRManager, module_tag: ModuleTag) -> Result<Self, String> {
+        let module_ptr = manager.get_module_ptr(module_tag).ok_or_else(|| format!("Module {:?} not found.", module_tag))?;
+
+        let mut functions = Vec::new();
+        let mut declarations = 0;
+        for function in module_functions(module_ptr) {
+            if unsafe { core::LLVMIsDeclaration(function) } != 0 {
+                declarations += 1;
+            } else {
+                functions.push(function_statistics(manager, function)?);
+            }
+        }
+
+        let mut globals = 0;
+        unsafe {
+            let mut global = core::LLVMGetFirstGlobal(module_ptr);
+            while !global.is_null() {
+                globals += 1;
+                global = core::LLVMGetNextGlobal(global);
+            }
+        }
+
+        Ok(Self { functions, declarations, globals })
+    }
+
+    /// Retrieves the statistics of every function with a body.
+    ///
+    /// # Returns
+    /// The function statistics, in module order.
+    pub fn get_functions(&self) -> &[FunctionStatistics] {
+        &self.functions
+    }
+
+    /// Retrieves the statistics of a function.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the function.
+    ///
+    /// # Returns
+    /// An `Option` containing the statistics, or `None` if the module has no function with a body
+    /// of that name.
+    pub fn get_function(&self, name: &str) -> Option<&FunctionStatistics> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Retrieves the number of functions with a body.
+    ///
+    /// # Returns
+    /// The function count, excluding declarations.
+    pub fn get_function_count(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Retrieves the number of functions that are only declared.
+    ///
+    /// # Returns
+    /// The declaration count.
+    pub fn get_declaration_count(&self) -> usize {
+        self.declarations
+    }
+
+    /// Retrieves the number of global variables.
+    ///
+    /// # Returns
+    /// The global count, including string constants.
+    pub fn get_global_count(&self) -> usize {
+        self.globals
+    }
+
+    /// Retrieves the number of basic blocks across all functions.
+    ///
+    /// # Returns
+    /// The total block count.
+    pub fn get_block_count(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_block_count).sum()
+    }
+
+    /// Retrieves the number of instructions across all functions.
+    ///
+    /// # Returns
+    /// The total instruction count.
+    pub fn get_instruction_count(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_instruction_count).sum()
+    }
+
+    /// Retrieves the number of instructions with each opcode across all functions.
+    ///
+    /// # Returns
+    /// The counts, keyed by the opcode as it is written in textual IR.
+    pub fn get_opcode_counts(&self) -> BTreeMap<String, usize> {
+        let mut opcodes = BTreeMap::new();
+        for (opcode, count) in self.functions.iter().flat_map(|function| function.opcodes.iter()) {
+            *opcodes.entry(opcode.clone()).or_insert(0) += count;
+        }
+        opcodes
+    }
+
+    /// Retrieves the number of `alloca` instructions across all functions.
+    ///
+    /// # Returns
+    /// The total alloca count.
+    pub fn get_alloca_count(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_alloca_count).sum()
+    }
+
+    /// Retrieves the number of call sites across all functions.
+    ///
+    /// # Returns
+    /// The total call count.
+    pub fn get_call_count(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_call_count).sum()
+    }
+
+    /// Retrieves the depth of the most deeply nested loop in the module.
+    ///
+    /// # Returns
+    /// The maximum loop depth, 0 if the module has no loops.
+    pub fn get_max_loop_depth(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_max_loop_depth).max().unwrap_or(0)
+    }
+
+    /// Retrieves the highest cyclomatic complexity of the module's functions.
+    ///
+    /// # Returns
+    /// The maximum cyclomatic complexity, 0 if the module has no functions with a body.
+    pub fn get_max_cyclomatic_complexity(&self) -> usize {
+        self.functions.iter().map(FunctionStatistics::get_cyclomatic_complexity).max().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for ModuleStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} functions, {} declarations, {} globals, {} blocks, {} instructions",
+            self.functions.len(), self.declarations, self.globals, self.get_block_count(), self.get_instruction_count()
+        )?;
+        for function in &self.functions {
+            writeln!(f, "  {}", function)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the statistics of a function with a body.
+///
+/// # Parameters
+/// * `manager` - The IRManager owning the function.
+/// * `function` - The function.
+///
+/// # Returns
+/// Returns `Ok(FunctionStatistics)` on success, or `Err(String)` if the control-flow graph of the
+/// function could not be built.
+fn function_statistics(manager: &mut IRManager, function: LLVMValueRef) -> Result<FunctionStatistics, String> {
+    let mut statistics = FunctionStatistics {
+        name: unsafe { value_name(function) },
+        blocks: 0,
+        instructions: 0,
+        opcodes: BTreeMap::new(),
+        allocas: 0,
+        calls: 0,
+        max_loop_depth: 0,
+        cyclomatic_complexity: 0,
+    };
+
+    for block in function_blocks(function) {
+        statistics.blocks += 1;
+        for instruction in block_instructions(block) {
+            let opcode = unsafe { core::LLVMGetInstructionOpcode(instruction) };
+            statistics.instructions += 1;
+            *statistics.opcodes.entry(opcode_name(opcode)).or_insert(0) += 1;
+            match opcode {
+                LLVMOpcode::LLVMAlloca => statistics.allocas += 1,
+                LLVMOpcode::LLVMCall | LLVMOpcode::LLVMInvoke | LLVMOpcode::LLVMCallBr => statistics.calls += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let function_tag = manager.get_or_store_value(function).ok_or("Failed to store value.")?;
+    let cfg = ControlFlowGraph::new(manager, function_tag)?;
+    let reachable = cfg.get_reverse_postorder();
+    let edges = reachable.iter().map(|block| cfg.get_successors(*block).len()).sum::<usize>();
+    statistics.cyclomatic_complexity = (edges + 2).saturating_sub(reachable.len());
+    statistics.max_loop_depth = LoopInfo::new(&cfg).get_loops().iter().map(|l| l.get_depth()).max().unwrap_or(0);
+
+    Ok(statistics)
+}
+
+/// Names an opcode as it is written in textual IR.
+///
+/// # Parameters
+/// * `opcode` - The opcode.
+///
+/// # Returns
+/// The lowercase mnemonic of the opcode, such as `add`, `getelementptr` or `phi`.
+fn opcode_name(opcode: LLVMOpcode) -> String {
+    match opcode {
+        LLVMOpcode::LLVMAtomicCmpXchg => "cmpxchg".to_string(),
+        _ => format!("{:?}", opcode).trim_start_matches("LLVM").to_lowercase(),
+    }
+}
@@ -37,6 +37,7 @@ impl Validator {
     /// # Returns
     /// True if the module is valid, false otherwise.
     pub fn is_valid_module(&self) -> bool {
+        let _span = logging::spans::verification("module");
         let mut error_message = std::ptr::null_mut();
 
         let action = analysis::LLVMVerifierFailureAction::LLVMPrintMessageAction;
@@ -74,6 +75,7 @@ impl Validator {
     /// # Returns
     /// True if the function is valid, false otherwise.
     pub fn is_valid_function(&self, function: Arc<RwLock<SafeLLVMPointer>>) -> bool {
+        let _span = logging::spans::verification("function");
         let action = analysis::LLVMVerifierFailureAction::LLVMPrintMessageAction;
 
         let function_rw_lock = function.read().expect("Failed to get function");
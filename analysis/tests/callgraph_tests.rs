@@ -0,0 +1,69 @@
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::{IRManager, ModuleTag};
+use analysis::callgraph::CallGraph;
+
+/// Builds a module with the calls
+///
+/// ```text
+/// main   -> helper, puts, even
+/// helper -> helper
+/// even   -> odd
+/// odd    -> even
+/// unused -> abort
+/// ```
+///
+/// where `puts` and `abort` are only declared.
+fn build_recursive_module(pools: &mut IRManager) -> ModuleTag {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("recursion", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    let [main, helper, puts, even, odd, unused, abort] = [DEFAULT_FUNCTION_NAME, "helper", "puts", "even", "odd", "unused", "abort"]
+        .map(|name| pools.add_function_to_module(module_tag, name, function_type).expect("Failed to add function"));
+
+    for (function_tag, callees) in [(main, vec![helper, puts, even]), (helper, vec![helper]), (even, vec![odd]), (odd, vec![even]), (unused, vec![abort])] {
+        let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+        pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+        for callee in callees {
+            pools.build_call(builder_tag, function_type, callee, &[], "").expect("Failed to build call");
+        }
+        pools.void_return(builder_tag).expect("Failed to build return");
+    }
+
+    module_tag
+}
+
+#[test]
+fn test_recursion_detection() {
+    let mut pools = IRManager::new();
+    let module_tag = build_recursive_module(&mut pools);
+    let call_graph = CallGraph::new(&pools, module_tag).expect("Failed to build call graph");
+
+    assert_eq!(call_graph.get_recursive_functions(), vec!["helper", "even", "odd"]);
+    assert!(call_graph.is_recursive("helper"), "Functions calling themselves are recursive");
+    assert!(!call_graph.is_recursive(DEFAULT_FUNCTION_NAME));
+    assert_eq!(call_graph.get_component("odd"), Some(&["even".to_string(), "odd".to_string()][..]));
+    assert_eq!(call_graph.get_component("missing"), None);
+
+    let components = call_graph.get_strongly_connected_components();
+    assert_eq!(components.len(), 6, "Every function belongs to one component: {:?}", components);
+    let position = |function: &str| components.iter().position(|component| component.iter().any(|name| name == function)).expect("Function should have a component");
+    assert!(position("even") < position(DEFAULT_FUNCTION_NAME), "Callees come before their callers: {:?}", components);
+    assert!(position("helper") < position(DEFAULT_FUNCTION_NAME), "Callees come before their callers: {:?}", components);
+}
+
+#[test]
+fn test_external_and_unreachable_functions() {
+    let mut pools = IRManager::new();
+    let module_tag = build_recursive_module(&mut pools);
+    let call_graph = CallGraph::new(&pools, module_tag).expect("Failed to build call graph");
+
+    assert_eq!(call_graph.get_external_callees(), vec!["puts", "abort"]);
+    assert_eq!(call_graph.get_reachable_functions(DEFAULT_FUNCTION_NAME), vec![DEFAULT_FUNCTION_NAME, "helper", "puts", "even", "odd"]);
+    assert_eq!(call_graph.get_unreachable_functions(DEFAULT_FUNCTION_NAME), vec!["unused"], "Declarations are not reported as unreachable");
+    assert_eq!(call_graph.get_reachable_functions("even"), vec!["even", "odd"]);
+    assert!(call_graph.get_reachable_functions("missing").is_empty());
+    assert_eq!(call_graph.get_unreachable_functions("missing"), vec![DEFAULT_FUNCTION_NAME, "helper", "even", "odd", "unused"]);
+}
@@ -0,0 +1,124 @@
+use ir::core::{BasicBlockTag, IRManager, ValueTag};
+use analysis::cfg::{ControlFlowGraph, TerminatorKind};
+
+/// The blocks of the function built by `build_branching_function`.
+struct Blocks {
+    entry: BasicBlockTag,
+    then_block: BasicBlockTag,
+    else_block: BasicBlockTag,
+    dead: BasicBlockTag,
+    merge: BasicBlockTag,
+}
+
+/// Builds `void branching(i64 x)`:
+///
+/// ```text
+/// entry: br true, then, else
+/// then:  br merge
+/// else:  switch x, merge [1: dead, 2: merge]
+/// dead:  unreachable
+/// merge: ret void
+/// ```
+fn build_branching_function(pools: &mut IRManager) -> (ValueTag, Blocks) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("cfg", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(void_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "branching", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    let then_block = pools.create_basic_block(context_tag, function_tag, "then").expect("Failed to create block");
+    let else_block = pools.create_basic_block(context_tag, function_tag, "else").expect("Failed to create block");
+    let dead = pools.create_basic_block(context_tag, function_tag, "dead").expect("Failed to create block");
+    let merge = pools.create_basic_block(context_tag, function_tag, "merge").expect("Failed to create block");
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+    pools.create_cond_br(builder_tag, condition, then_block, else_block).expect("Failed to build conditional branch");
+
+    pools.position_builder_at_end(builder_tag, then_block).expect("Failed to position builder");
+    pools.create_br(builder_tag, merge).expect("Failed to build branch");
+
+    pools.position_builder_at_end(builder_tag, else_block).expect("Failed to position builder");
+    let param = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one = pools.create_integer(context_tag, 1).expect("Failed to create integer");
+    let two = pools.create_integer(context_tag, 2).expect("Failed to create integer");
+    pools.create_switch(builder_tag, param, merge, &[(one, dead), (two, merge)]).expect("Failed to build switch");
+
+    pools.position_builder_at_end(builder_tag, dead).expect("Failed to position builder");
+    pools.create_unreachable(builder_tag).expect("Failed to build unreachable");
+
+    pools.position_builder_at_end(builder_tag, merge).expect("Failed to position builder");
+    pools.void_return(builder_tag).expect("Failed to build return");
+
+    (function_tag, Blocks { entry, then_block, else_block, dead, merge })
+}
+
+#[test]
+fn test_cfg_nodes_and_terminators() {
+    let mut pools = IRManager::new();
+    let (function_tag, blocks) = build_branching_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+
+    assert_eq!(cfg.get_function(), function_tag);
+    assert_eq!(cfg.get_entry(), blocks.entry);
+    assert_eq!(cfg.get_blocks(), &[blocks.entry, blocks.then_block, blocks.else_block, blocks.dead, blocks.merge]);
+    assert_eq!(cfg.len(), 5);
+    assert!(!cfg.is_empty());
+
+    assert_eq!(cfg.get_terminator(blocks.entry), Some(TerminatorKind::ConditionalBranch));
+    assert_eq!(cfg.get_terminator(blocks.then_block), Some(TerminatorKind::Branch));
+    assert_eq!(cfg.get_terminator(blocks.else_block), Some(TerminatorKind::Switch));
+    assert_eq!(cfg.get_terminator(blocks.dead), Some(TerminatorKind::Unreachable));
+    assert_eq!(cfg.get_terminator(blocks.merge), Some(TerminatorKind::Return));
+}
+
+#[test]
+fn test_cfg_edges() {
+    let mut pools = IRManager::new();
+    let (function_tag, blocks) = build_branching_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+
+    assert_eq!(cfg.get_successors(blocks.entry), &[blocks.then_block, blocks.else_block]);
+    assert_eq!(cfg.get_successors(blocks.then_block), &[blocks.merge]);
+    assert_eq!(cfg.get_successors(blocks.else_block), &[blocks.merge, blocks.dead], "Switch targets should be listed once, default first");
+    assert!(cfg.get_successors(blocks.dead).is_empty());
+    assert!(cfg.get_successors(blocks.merge).is_empty());
+
+    assert!(cfg.get_predecessors(blocks.entry).is_empty());
+    assert_eq!(cfg.get_predecessors(blocks.merge), &[blocks.then_block, blocks.else_block]);
+    assert_eq!(cfg.get_predecessors(blocks.dead), &[blocks.else_block]);
+
+    assert_eq!(cfg.get_exit_blocks(), vec![blocks.dead, blocks.merge]);
+    assert_eq!(cfg.get_edges().len(), 5);
+}
+
+#[test]
+fn test_cfg_rejects_declarations() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("cfg", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let declaration = pools.add_function_to_module(module_tag, "external", function_type).expect("Failed to add function");
+    let constant = pools.create_integer(context_tag, 7).expect("Failed to create integer");
+
+    assert!(ControlFlowGraph::new(&mut pools, declaration).is_err(), "Declarations have no CFG");
+    assert!(ControlFlowGraph::new(&mut pools, constant).is_err(), "Constants have no CFG");
+}
+
+#[test]
+fn test_cfg_rejects_unterminated_blocks() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("cfg", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "unfinished", function_type).expect("Failed to add function");
+    pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+
+    let error = ControlFlowGraph::new(&mut pools, function_tag).expect_err("Blocks without terminators should be rejected");
+    assert!(error.contains("no terminator"), "Unexpected error: {}", error);
+}
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+use ir::core::{IRManager, ValueTag};
+use analysis::{cfg::ControlFlowGraph, dataflow::{liveness, reaching_definitions, solve, FunctionBody, Liveness}};
+
+/// Collects tags into a set.
+fn set(tags: &[ValueTag]) -> HashSet<ValueTag> {
+    tags.iter().copied().collect()
+}
+
+#[test]
+fn test_liveness_of_loop() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("liveness", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "count", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one = pools.create_integer(context_tag, 1).expect("Failed to create integer");
+
+    let [entry, header, body, exit] = ["entry", "header", "body", "exit"]
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"));
+
+    // entry:  %start = add %n, 1
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let start = pools.build_add(builder_tag, n, one, "start").expect("Failed to build add");
+    pools.create_br(builder_tag, header).expect("Failed to build branch");
+
+    // header: %i = phi [%start, %entry], [%next, %body]
+    pools.position_builder_at_end(builder_tag, header).expect("Failed to position builder");
+    let i = pools.build_phi(builder_tag, int_type, "i").expect("Failed to build phi");
+    let condition = pools.build_icmp_lt(builder_tag, i, n, "condition").expect("Failed to build comparison");
+    pools.create_cond_br(builder_tag, condition, body, exit).expect("Failed to build branch");
+
+    // body:   %next = add %i, %n
+    pools.position_builder_at_end(builder_tag, body).expect("Failed to position builder");
+    let next = pools.build_add(builder_tag, i, n, "next").expect("Failed to build add");
+    pools.create_br(builder_tag, header).expect("Failed to build branch");
+
+    // exit:   ret %i
+    pools.position_builder_at_end(builder_tag, exit).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, i).expect("Failed to build return");
+
+    pools.add_incoming(i, &[(start, entry), (next, body)]).expect("Failed to add incoming values");
+
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let live = liveness(&mut pools, &cfg).expect("Failed to compute liveness");
+
+    assert_eq!(live.get_block_entry(entry), Some(&set(&[n])));
+    assert_eq!(live.get_block_exit(entry), Some(&set(&[n, start])), "`start` flows into the phi from `entry`");
+    assert_eq!(live.get_block_entry(header), Some(&set(&[n])), "Phi operands are not live on entry to the phi's block");
+    assert_eq!(live.get_block_exit(header), Some(&set(&[n, i])));
+    assert_eq!(live.get_block_entry(body), Some(&set(&[n, i])));
+    assert_eq!(live.get_block_exit(body), Some(&set(&[n, next])));
+    assert_eq!(live.get_block_entry(exit), Some(&set(&[i])));
+    assert_eq!(live.get_block_exit(exit), Some(&set(&[])));
+
+    assert_eq!(live.get_instruction_entry(condition), Some(&set(&[n, i])));
+    assert_eq!(live.get_instruction_exit(condition), Some(&set(&[n, i, condition])));
+    assert_eq!(live.get_instruction_entry(next), Some(&set(&[n, i])));
+    assert_eq!(live.get_instruction_exit(next), Some(&set(&[n, next])), "`i` dies at its last use in the body");
+
+    let body_snapshot = FunctionBody::new(&mut pools, &cfg).expect("Failed to snapshot function");
+    let phi = body_snapshot.get_instruction(i).expect("The phi should be part of the function");
+    assert!(phi.is_phi());
+    assert_eq!(phi.get_incoming(), &[(start, entry), (next, body)]);
+    assert_eq!(body_snapshot.get_instruction(next).expect("`next` should be part of the function").get_operands(), &[i, n], "Constants are not variables");
+    let solved = solve(&body_snapshot, &Liveness);
+    assert_eq!(solved.get_block_entry(body), live.get_block_entry(body));
+    assert!(solved.get_iterations() >= cfg.len());
+}
+
+#[test]
+fn test_reaching_definitions_of_stores() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("reaching", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "assign", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one = pools.create_integer(context_tag, 1).expect("Failed to create integer");
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+
+    let [entry, then_block, join] = ["entry", "then", "join"]
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"));
+
+    // entry: %x = alloca i64; store %n, %x
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let slot = pools.init_var(builder_tag, "x", int_type, Some(n)).expect("Failed to build alloca");
+    let first_store = pools.get_last_instruction(entry).expect("Failed to get store");
+    pools.create_cond_br(builder_tag, condition, then_block, join).expect("Failed to build branch");
+
+    // then:  store 1, %x
+    pools.position_builder_at_end(builder_tag, then_block).expect("Failed to position builder");
+    pools.reassign_var(builder_tag, slot, one).expect("Failed to build store");
+    let second_store = pools.get_last_instruction(then_block).expect("Failed to get store");
+    pools.create_br(builder_tag, join).expect("Failed to build branch");
+
+    // join:  %value = load i64, %x
+    pools.position_builder_at_end(builder_tag, join).expect("Failed to position builder");
+    let load = pools.get_var(builder_tag, int_type, slot, "").expect("Failed to build load");
+    pools.void_return(builder_tag).expect("Failed to build return");
+
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let reaching = reaching_definitions(&mut pools, &cfg).expect("Failed to compute reaching definitions");
+
+    assert_eq!(reaching.get_block_entry(entry), Some(&set(&[])));
+    assert_eq!(reaching.get_block_exit(entry), Some(&set(&[slot, first_store])));
+    assert_eq!(reaching.get_block_exit(then_block), Some(&set(&[slot, second_store])), "The second store overwrites the first");
+    assert_eq!(reaching.get_block_entry(join), Some(&set(&[slot, first_store, second_store])), "Both stores reach the join");
+    assert_eq!(reaching.get_instruction_entry(load), Some(&set(&[slot, first_store, second_store])));
+    assert_eq!(reaching.get_instruction_exit(load), Some(&set(&[slot, first_store, second_store, load])));
+}
@@ -0,0 +1,74 @@
+use ir::core::{IRManager, ModuleTag};
+use analysis::diff::{module_diff, DiffLine};
+
+/// Builds a module with `i64 add_one(i64)`, which adds `addend` to its parameter, and a `helper`
+/// if requested. Block and instruction names are taken from the arguments so that modules can
+/// differ only in naming.
+fn build_module(pools: &mut IRManager, block_name: &str, result_name: &str, addend: i64, with_helper: bool) -> ModuleTag {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("diff", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "add_one", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    let entry = pools.create_basic_block(context_tag, function_tag, block_name).expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let parameter = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let constant = pools.create_integer(context_tag, addend).expect("Failed to create integer");
+    let result = pools.build_add(builder_tag, parameter, constant, result_name).expect("Failed to build add");
+    pools.nonvoid_return(builder_tag, result).expect("Failed to build return");
+
+    if with_helper {
+        pools.add_function_to_module(module_tag, "helper", function_type).expect("Failed to add function");
+    }
+    module_tag
+}
+
+#[test]
+fn test_renamed_module_is_identical() {
+    let mut pools = IRManager::new();
+    let reference = build_module(&mut pools, "entry", "sum", 1, false);
+    let renamed = build_module(&mut pools, "start", "", 1, false);
+
+    let diff = module_diff(&pools, reference, renamed).expect("Failed to diff modules");
+    assert!(diff.is_identical(), "Value names and numbering should be ignored: {}", diff);
+    assert_eq!(diff.to_string(), "modules are identical\n");
+
+    let block = &diff.get_function("add_one").expect("`add_one` is in both modules").get_blocks()[0];
+    assert_eq!(block.get_first_name(), Some("entry"));
+    assert_eq!(block.get_second_name(), Some("start"));
+    assert_eq!(block.get_lines(), &[
+        DiffLine::Same("%v0 = add i64 %arg0, 1".to_string()),
+        DiffLine::Same("ret i64 %v0".to_string()),
+    ]);
+}
+
+#[test]
+fn test_changed_module_diff() {
+    let mut pools = IRManager::new();
+    let reference = build_module(&mut pools, "entry", "sum", 1, false);
+    let changed = build_module(&mut pools, "entry", "sum", 2, true);
+
+    let diff = module_diff(&pools, reference, changed).expect("Failed to diff modules");
+    assert!(!diff.is_identical());
+    assert!(diff.get_only_in_first().is_empty());
+    assert_eq!(diff.get_only_in_second(), &["helper".to_string()]);
+
+    let function = diff.get_function("add_one").expect("`add_one` is in both modules");
+    assert!(!function.is_identical());
+    let (first_signature, second_signature) = function.get_signatures();
+    assert_eq!(first_signature, second_signature);
+    assert_eq!(function.get_blocks()[0].get_lines(), &[
+        DiffLine::Removed("%v0 = add i64 %arg0, 1".to_string()),
+        DiffLine::Added("%v0 = add i64 %arg0, 2".to_string()),
+        DiffLine::Same("ret i64 %v0".to_string()),
+    ]);
+
+    let text = diff.to_string();
+    assert!(text.contains("+ function 'helper' only in second module\n"), "Unexpected diff: {}", text);
+    assert!(text.contains("function 'add_one':\n  block 0 (entry / entry):\n    - %v0 = add i64 %arg0, 1\n    + %v0 = add i64 %arg0, 2\n      ret i64 %v0\n"), "Unexpected diff: {}", text);
+
+    let reversed = module_diff(&pools, changed, reference).expect("Failed to diff modules");
+    assert_eq!(reversed.get_only_in_first(), &["helper".to_string()]);
+}
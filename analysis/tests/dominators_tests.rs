@@ -0,0 +1,106 @@
+use ir::core::{BasicBlockTag, IRManager, ValueTag};
+use analysis::{cfg::ControlFlowGraph, dominators::DominatorTree};
+
+/// Builds `void looping()` with the control flow
+///
+/// ```text
+/// entry -> left, right
+/// left  -> join
+/// right -> join
+/// join  -> latch
+/// latch -> left, exit
+/// exit  -> ret
+/// ```
+fn build_looping_function(pools: &mut IRManager) -> (ValueTag, [BasicBlockTag; 6]) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("dominators", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "looping", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    let names = ["entry", "left", "right", "join", "latch", "exit"];
+    let blocks: Vec<BasicBlockTag> = names.iter()
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"))
+        .collect();
+    let [entry, left, right, join, latch, exit] = [blocks[0], blocks[1], blocks[2], blocks[3], blocks[4], blocks[5]];
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    pools.create_cond_br(builder_tag, condition, left, right).expect("Failed to build branch");
+    for (block, target) in [(left, join), (right, join), (join, latch)] {
+        pools.position_builder_at_end(builder_tag, block).expect("Failed to position builder");
+        pools.create_br(builder_tag, target).expect("Failed to build branch");
+    }
+    pools.position_builder_at_end(builder_tag, latch).expect("Failed to position builder");
+    pools.create_cond_br(builder_tag, condition, left, exit).expect("Failed to build branch");
+    pools.position_builder_at_end(builder_tag, exit).expect("Failed to position builder");
+    pools.void_return(builder_tag).expect("Failed to build return");
+
+    (function_tag, [entry, left, right, join, latch, exit])
+}
+
+#[test]
+fn test_immediate_dominators_and_tree() {
+    let mut pools = IRManager::new();
+    let (function_tag, [entry, left, right, join, latch, exit]) = build_looping_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let tree = DominatorTree::new(&cfg);
+
+    assert!(!tree.is_post_dominator_tree());
+    assert_eq!(tree.get_roots(), &[entry]);
+    assert_eq!(tree.get_immediate_dominator(entry), None);
+    assert_eq!(tree.get_immediate_dominator(left), Some(entry));
+    assert_eq!(tree.get_immediate_dominator(right), Some(entry));
+    assert_eq!(tree.get_immediate_dominator(join), Some(entry), "Neither branch dominates the join point");
+    assert_eq!(tree.get_immediate_dominator(latch), Some(join));
+    assert_eq!(tree.get_immediate_dominator(exit), Some(latch));
+    assert_eq!(tree.get_children(entry), &[left, right, join]);
+    assert_eq!(tree.get_dominators(exit), vec![exit, latch, join, entry]);
+
+    assert!(tree.dominates(entry, exit));
+    assert!(tree.dominates(join, join), "Blocks dominate themselves");
+    assert!(!tree.strictly_dominates(join, join));
+    assert!(!tree.dominates(left, join));
+    assert!(!tree.dominates(latch, join), "Back edges do not make the latch dominate the loop");
+}
+
+#[test]
+fn test_dominance_frontiers() {
+    let mut pools = IRManager::new();
+    let (function_tag, [entry, left, right, join, latch, exit]) = build_looping_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let tree = DominatorTree::new(&cfg);
+
+    assert!(tree.get_dominance_frontier(entry).is_empty());
+    assert_eq!(tree.get_dominance_frontier(left), &[join]);
+    assert_eq!(tree.get_dominance_frontier(right), &[join]);
+    assert_eq!(tree.get_dominance_frontier(join), &[left]);
+    assert_eq!(tree.get_dominance_frontier(latch), &[left]);
+    assert!(tree.get_dominance_frontier(exit).is_empty());
+
+    let phi_blocks = tree.get_iterated_dominance_frontier(&[right]);
+    assert_eq!(phi_blocks.len(), 2);
+    assert!(phi_blocks.contains(&join) && phi_blocks.contains(&left), "A variable assigned in `right` needs phis at the join and the loop header");
+}
+
+#[test]
+fn test_post_dominators() {
+    let mut pools = IRManager::new();
+    let (function_tag, [entry, left, right, join, latch, exit]) = build_looping_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let tree = DominatorTree::post_dominators(&cfg);
+
+    assert!(tree.is_post_dominator_tree());
+    assert_eq!(tree.get_roots(), &[exit]);
+    assert_eq!(tree.get_immediate_dominator(entry), Some(join));
+    assert_eq!(tree.get_immediate_dominator(left), Some(join));
+    assert_eq!(tree.get_immediate_dominator(right), Some(join));
+    assert_eq!(tree.get_immediate_dominator(join), Some(latch));
+    assert_eq!(tree.get_immediate_dominator(latch), Some(exit));
+    assert!(tree.dominates(exit, entry), "The only exit post-dominates every block");
+    assert!(!tree.dominates(left, entry));
+
+    assert_eq!(tree.get_dominance_frontier(left), &[latch, entry], "`left` is control dependent on both branches leading to it");
+    assert_eq!(tree.get_dominance_frontier(right), &[entry]);
+}
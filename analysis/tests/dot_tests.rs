@@ -0,0 +1,94 @@
+use ir::core::{BasicBlockTag, IRManager, ModuleTag, ValueTag};
+use analysis::{callgraph::CallGraph, cfg::ControlFlowGraph, dot::{call_graph_to_dot, cfg_to_dot, DotOptions}};
+
+/// Builds `i64 choose()`, which branches on a constant condition to return 1 or 2.
+fn build_choose_function(pools: &mut IRManager) -> (ValueTag, [BasicBlockTag; 3]) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("dot", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(int_type), &[], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "choose", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    let then_block = pools.create_basic_block(context_tag, function_tag, "then").expect("Failed to create block");
+    let else_block = pools.create_basic_block(context_tag, function_tag, "else").expect("Failed to create block");
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+    pools.create_cond_br(builder_tag, condition, then_block, else_block).expect("Failed to build conditional branch");
+
+    for (block, value) in [(then_block, 1), (else_block, 2)] {
+        pools.position_builder_at_end(builder_tag, block).expect("Failed to position builder");
+        let constant = pools.create_integer(context_tag, value).expect("Failed to create integer");
+        pools.nonvoid_return(builder_tag, constant).expect("Failed to build return");
+    }
+
+    (function_tag, [entry, then_block, else_block])
+}
+
+/// Builds a module where `main` calls `helper` and the declared `external`.
+fn build_calling_module(pools: &mut IRManager) -> ModuleTag {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("calls", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let main_tag = pools.add_function_to_module(module_tag, "main", function_type).expect("Failed to add function");
+    let helper_tag = pools.add_function_to_module(module_tag, "helper", function_type).expect("Failed to add function");
+    let external_tag = pools.add_function_to_module(module_tag, "external", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    for (function_tag, callees) in [(main_tag, vec![helper_tag, external_tag]), (helper_tag, vec![])] {
+        let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+        pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+        for callee in callees {
+            pools.build_call(builder_tag, function_type, callee, &[], "").expect("Failed to build call");
+        }
+        pools.void_return(builder_tag).expect("Failed to build return");
+    }
+
+    module_tag
+}
+
+#[test]
+fn test_cfg_to_dot() {
+    let mut pools = IRManager::new();
+    let (function_tag, [entry, then_block, else_block]) = build_choose_function(&mut pools);
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+
+    let dot = cfg_to_dot(&pools, &cfg, &DotOptions::new()).expect("Failed to render CFG");
+    assert!(dot.starts_with("digraph \"CFG for 'choose'\" {"), "Unexpected header: {}", dot);
+    assert!(dot.contains("block0 [label=\"entry:\\l  br i1 true, label %then, label %else\\l\"];"), "Blocks should list their instructions: {}", dot);
+    assert!(dot.contains("block1 [label=\"then:\\l  ret i64 1\\l\"];"), "Blocks should list their instructions: {}", dot);
+    assert!(dot.contains("block0 -> block1 [label=\"true\"];"), "Conditional edges should be labelled: {}", dot);
+    assert!(dot.contains("block0 -> block2 [label=\"false\"];"), "Conditional edges should be labelled: {}", dot);
+    assert!(dot.trim_end().ends_with('}'));
+
+    let options = DotOptions::new().with_instructions(false).highlight_node(then_block).highlight_edge(entry, else_block).with_highlight_color("blue");
+    let dot = cfg_to_dot(&pools, &cfg, &options).expect("Failed to render CFG");
+    assert!(dot.contains("block0 [label=\"entry\"];"), "Labels should omit instructions: {}", dot);
+    assert!(dot.contains("block1 [label=\"then\", color=\"blue\", fontcolor=\"blue\", penwidth=2];"), "Highlighted blocks should be colored: {}", dot);
+    assert!(dot.contains("block0 -> block2 [label=\"false\", color=\"blue\", penwidth=2];"), "Highlighted edges should be colored: {}", dot);
+    assert!(dot.contains("block0 -> block1 [label=\"true\"];"), "Other edges should not be colored: {}", dot);
+}
+
+#[test]
+fn test_call_graph_to_dot() {
+    let mut pools = IRManager::new();
+    let module_tag = build_calling_module(&mut pools);
+    let call_graph = CallGraph::new(&pools, module_tag).expect("Failed to build call graph");
+
+    assert_eq!(call_graph.get_functions(), &["main", "helper", "external"]);
+    assert_eq!(call_graph.get_callees("main"), &["helper", "external"]);
+    assert_eq!(call_graph.get_callers("external"), &["main"]);
+    assert!(call_graph.is_declaration("external"));
+    assert!(!call_graph.is_declaration("helper"));
+
+    let options = DotOptions::new().highlight_node("helper").highlight_edge("main", "external");
+    let dot = call_graph_to_dot(&call_graph, &options);
+    assert!(dot.contains("\"main\" [label=\"main\"];"), "Functions should be nodes: {}", dot);
+    assert!(dot.contains("\"external\" [label=\"external\", style=dashed];"), "Declarations should be dashed: {}", dot);
+    assert!(dot.contains("\"helper\" [label=\"helper\", color=\"red\", fontcolor=\"red\", penwidth=2];"), "Highlighted functions should be colored: {}", dot);
+    assert!(dot.contains("\"main\" -> \"helper\";"), "Calls should be edges: {}", dot);
+    assert!(dot.contains("\"main\" -> \"external\" [color=\"red\", penwidth=2];"), "Highlighted calls should be colored: {}", dot);
+}
@@ -0,0 +1,85 @@
+use ir::core::{BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag};
+use analysis::lint::{lint_function, lint_module, LintKind};
+
+/// Creates a module with `i64 name(i64)`.
+fn setup(pools: &mut IRManager, name: &str) -> (ContextTag, ModuleTag, TypeTag, ValueTag, BuilderTag) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("lint", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, name, function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    (context_tag, module_tag, int_type, function_tag, builder_tag)
+}
+
+#[test]
+fn test_clean_function() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "clean");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let slot = pools.init_var(builder_tag, "slot", int_type, Some(n)).expect("Failed to build alloca");
+    let value = pools.get_var(builder_tag, int_type, slot, "value").expect("Failed to build load");
+    pools.nonvoid_return(builder_tag, value).expect("Failed to build return");
+
+    assert_eq!(lint_function(&pools, function_tag).expect("Failed to lint function"), vec![]);
+    assert_eq!(lint_module(&pools, module_tag).expect("Failed to lint module"), vec![]);
+}
+
+#[test]
+fn test_terminator_and_reachability_lints() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, _, function_tag, builder_tag) = setup(&mut pools, "broken");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let [entry, exit, orphan] = ["entry", "exit", "orphan"]
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"));
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    pools.create_br(builder_tag, exit).expect("Failed to build branch");
+    pools.build_add(builder_tag, n, n, "late").expect("Failed to build add");
+
+    pools.position_builder_at_end(builder_tag, exit).expect("Failed to position builder");
+    let doubled = pools.build_add(builder_tag, n, n, "doubled").expect("Failed to build add");
+
+    pools.position_builder_at_end(builder_tag, orphan).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, doubled).expect("Failed to build return");
+
+    let lints = lint_function(&pools, function_tag).expect("Failed to lint function");
+    let found: Vec<(LintKind, &str)> = lints.iter().map(|lint| (lint.get_kind(), lint.get_block())).collect();
+    assert_eq!(found, vec![
+        (LintKind::InstructionAfterTerminator, "entry"),
+        (LintKind::UnusedValue, "entry"),
+        (LintKind::MissingTerminator, "exit"),
+        (LintKind::UnreachableBlock, "orphan"),
+    ], "Unexpected lints: {:#?}", lints);
+
+    assert!(lints.iter().all(|lint| lint.get_function() == "broken"));
+    let after_terminator = lints[0].to_string();
+    assert!(after_terminator.starts_with("[instruction-after-terminator] in function 'broken', block 'entry': "), "Unexpected text: {}", after_terminator);
+    assert!(after_terminator.contains("`br label %exit`") && after_terminator.contains("%late = add i64 %0, %0"), "The text should quote the instructions: {}", after_terminator);
+    assert!(lints[2].get_message().contains("%doubled = add i64 %0, %0"), "The text should quote the last instruction: {}", lints[2]);
+    assert_eq!(lint_module(&pools, module_tag).expect("Failed to lint module"), lints);
+}
+
+#[test]
+fn test_alloca_lints() {
+    let mut pools = IRManager::new();
+    let (context_tag, _, int_type, function_tag, builder_tag) = setup(&mut pools, "slots");
+    let [entry, body] = ["entry", "body"]
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"));
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    pools.create_br(builder_tag, body).expect("Failed to build branch");
+
+    pools.position_builder_at_end(builder_tag, body).expect("Failed to position builder");
+    let slot = pools.init_var(builder_tag, "slot", int_type, None).expect("Failed to build alloca");
+    let value = pools.get_var(builder_tag, int_type, slot, "value").expect("Failed to build load");
+    pools.nonvoid_return(builder_tag, value).expect("Failed to build return");
+
+    let lints = lint_function(&pools, function_tag).expect("Failed to lint function");
+    let kinds: Vec<LintKind> = lints.iter().map(|lint| lint.get_kind()).collect();
+    assert_eq!(kinds, vec![LintKind::AllocaOutsideEntry, LintKind::LoadFromUninitializedAlloca], "Unexpected lints: {:#?}", lints);
+    assert!(lints.iter().all(|lint| lint.get_block() == "body"));
+    assert!(lints[1].get_message().contains("%slot = alloca i64"), "The text should quote the alloca: {}", lints[1]);
+}
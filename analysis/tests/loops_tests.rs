@@ -0,0 +1,106 @@
+use ir::core::{BasicBlockTag, ContextTag, IRManager, ValueTag};
+use analysis::{cfg::ControlFlowGraph, loops::LoopInfo};
+
+/// Creates `void name()` with one basic block per name in `block_names`.
+fn build_function(pools: &mut IRManager, name: &str, block_names: &[&str]) -> (ContextTag, ValueTag, Vec<BasicBlockTag>) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("loops", context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, name, function_type).expect("Failed to add function");
+    let blocks = block_names.iter()
+        .map(|block_name| pools.create_basic_block(context_tag, function_tag, block_name).expect("Failed to create block"))
+        .collect();
+    (context_tag, function_tag, blocks)
+}
+
+/// Ends each block with a branch to its targets: `ret` for none, `br` for one and a conditional
+/// `br` for two.
+fn connect(pools: &mut IRManager, context_tag: ContextTag, edges: &[(BasicBlockTag, &[BasicBlockTag])]) {
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+    for (block, targets) in edges {
+        pools.position_builder_at_end(builder_tag, *block).expect("Failed to position builder");
+        match targets {
+            [] => pools.void_return(builder_tag),
+            [target] => pools.create_br(builder_tag, *target),
+            [then_target, else_target] => pools.create_cond_br(builder_tag, condition, *then_target, *else_target),
+            _ => panic!("Blocks branch to at most two targets"),
+        }.expect("Failed to build terminator");
+    }
+}
+
+#[test]
+fn test_nested_loops() {
+    let mut pools = IRManager::new();
+    let (context_tag, function_tag, blocks) = build_function(&mut pools, "nested", &["entry", "outer", "inner", "inner_body", "outer_latch", "exit"]);
+    let [entry, outer, inner, inner_body, outer_latch, exit] = [blocks[0], blocks[1], blocks[2], blocks[3], blocks[4], blocks[5]];
+    connect(&mut pools, context_tag, &[
+        (entry, &[outer]),
+        (outer, &[inner, exit]),
+        (inner, &[inner_body, outer_latch]),
+        (inner_body, &[inner]),
+        (outer_latch, &[outer]),
+        (exit, &[]),
+    ]);
+
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let loops = LoopInfo::new(&cfg);
+
+    assert_eq!(loops.get_back_edges(), &[(inner_body, inner), (outer_latch, outer)]);
+    assert!(loops.is_reducible());
+    assert_eq!(loops.get_loops().len(), 2);
+    assert_eq!(loops.get_top_level_loops().len(), 1);
+
+    let outer_loop = loops.get_loop(outer).expect("`outer` should head a loop");
+    assert_eq!(outer_loop.get_blocks(), &[outer, inner, inner_body, outer_latch]);
+    assert_eq!(outer_loop.get_latches(), &[outer_latch]);
+    assert_eq!(outer_loop.get_exiting_blocks(), &[outer]);
+    assert_eq!(outer_loop.get_exits(), &[exit]);
+    assert_eq!(outer_loop.get_preheader(), Some(entry));
+    assert_eq!(outer_loop.get_children(), &[inner]);
+    assert_eq!(outer_loop.get_parent(), None);
+    assert_eq!(outer_loop.get_depth(), 1);
+
+    let inner_loop = loops.get_loop(inner).expect("`inner` should head a loop");
+    assert_eq!(inner_loop.get_blocks(), &[inner, inner_body]);
+    assert_eq!(inner_loop.get_latches(), &[inner_body]);
+    assert_eq!(inner_loop.get_exits(), &[outer_latch]);
+    assert_eq!(inner_loop.get_preheader(), None, "`outer` also branches to the exit, so it is not a preheader");
+    assert_eq!(inner_loop.get_parent(), Some(outer));
+    assert_eq!(inner_loop.get_depth(), 2);
+
+    assert_eq!(loops.get_loop_depth(entry), 0);
+    assert_eq!(loops.get_loop_depth(outer_latch), 1);
+    assert_eq!(loops.get_loop_depth(inner_body), 2);
+    assert_eq!(loops.get_innermost_loop(inner_body).map(|l| l.get_header()), Some(inner));
+    assert!(loops.is_loop_header(inner));
+    assert!(!loops.is_loop_header(inner_body));
+}
+
+#[test]
+fn test_loop_free_function() {
+    let mut pools = IRManager::new();
+    let (context_tag, function_tag, blocks) = build_function(&mut pools, "straight", &["entry", "exit"]);
+    connect(&mut pools, context_tag, &[(blocks[0], &[blocks[1]]), (blocks[1], &[])]);
+
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let loops = LoopInfo::new(&cfg);
+    assert!(loops.get_loops().is_empty());
+    assert!(loops.get_back_edges().is_empty());
+    assert!(loops.is_reducible());
+}
+
+#[test]
+fn test_irreducible_cycle() {
+    let mut pools = IRManager::new();
+    let (context_tag, function_tag, blocks) = build_function(&mut pools, "irreducible", &["entry", "a", "b", "exit"]);
+    let [entry, a, b, exit] = [blocks[0], blocks[1], blocks[2], blocks[3]];
+    connect(&mut pools, context_tag, &[(entry, &[a, b]), (a, &[b, exit]), (b, &[a]), (exit, &[])]);
+
+    let cfg = ControlFlowGraph::new(&mut pools, function_tag).expect("Failed to build CFG");
+    let loops = LoopInfo::new(&cfg);
+    assert!(loops.get_loops().is_empty(), "A cycle entered at two blocks is not a natural loop");
+    assert!(!loops.is_reducible());
+    assert_eq!(loops.get_irreducible_edges(), &[(b, a)]);
+}
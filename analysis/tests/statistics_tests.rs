@@ -0,0 +1,98 @@
+use ir::core::{IRManager, ModuleTag};
+use analysis::statistics::ModuleStatistics;
+
+/// Builds a module with a global `counter`, a declared `puts`, an empty `helper`, and a `main`
+/// with the control flow
+///
+/// ```text
+/// entry:  %slot = alloca i64; store 0, %slot; br header
+/// header: br true, body, exit
+/// body:   call helper(); br header
+/// exit:   ret void
+/// ```
+fn build_module(pools: &mut IRManager) -> ModuleTag {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("statistics", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+
+    pools.init_global_var(module_tag, "counter", int_type, None).expect("Failed to add global");
+
+    let main = pools.add_function_to_module(module_tag, "main", function_type).expect("Failed to add function");
+    let helper = pools.add_function_to_module(module_tag, "helper", function_type).expect("Failed to add function");
+    pools.add_function_to_module(module_tag, "puts", function_type).expect("Failed to add function");
+
+    let [entry, header, body, exit] = ["entry", "header", "body", "exit"]
+        .map(|name| pools.create_basic_block(context_tag, main, name).expect("Failed to create block"));
+    let zero = pools.create_integer(context_tag, 0).expect("Failed to create integer");
+    let condition = pools.create_boolean(context_tag, true).expect("Failed to create boolean");
+
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    pools.init_var(builder_tag, "slot", int_type, Some(zero)).expect("Failed to build alloca");
+    pools.create_br(builder_tag, header).expect("Failed to build branch");
+
+    pools.position_builder_at_end(builder_tag, header).expect("Failed to position builder");
+    pools.create_cond_br(builder_tag, condition, body, exit).expect("Failed to build branch");
+
+    pools.position_builder_at_end(builder_tag, body).expect("Failed to position builder");
+    pools.build_call(builder_tag, function_type, helper, &[], "").expect("Failed to build call");
+    pools.create_br(builder_tag, header).expect("Failed to build branch");
+
+    pools.position_builder_at_end(builder_tag, exit).expect("Failed to position builder");
+    pools.void_return(builder_tag).expect("Failed to build return");
+
+    let helper_entry = pools.create_basic_block(context_tag, helper, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, helper_entry).expect("Failed to position builder");
+    pools.void_return(builder_tag).expect("Failed to build return");
+
+    module_tag
+}
+
+#[test]
+fn test_function_statistics() {
+    let mut pools = IRManager::new();
+    let module_tag = build_module(&mut pools);
+    let statistics = ModuleStatistics::new(&mut pools, module_tag).expect("Failed to collect statistics");
+
+    let main = statistics.get_function("main").expect("`main` should have statistics");
+    assert_eq!(main.get_block_count(), 4);
+    assert_eq!(main.get_instruction_count(), 7);
+    assert_eq!(main.get_opcode_count("br"), 3);
+    assert_eq!(main.get_opcode_count("alloca"), 1);
+    assert_eq!(main.get_opcode_count("load"), 0);
+    assert_eq!(main.get_opcode_counts().values().sum::<usize>(), 7);
+    assert_eq!(main.get_alloca_count(), 1);
+    assert_eq!(main.get_call_count(), 1);
+    assert_eq!(main.get_max_loop_depth(), 1);
+    assert_eq!(main.get_cyclomatic_complexity(), 2, "Four edges between four blocks give two independent paths");
+
+    let helper = statistics.get_function("helper").expect("`helper` should have statistics");
+    assert_eq!(helper.get_instruction_count(), 1);
+    assert_eq!(helper.get_max_loop_depth(), 0);
+    assert_eq!(helper.get_cyclomatic_complexity(), 1);
+    assert!(statistics.get_function("puts").is_none(), "Declarations have no statistics");
+}
+
+#[test]
+fn test_module_statistics() {
+    let mut pools = IRManager::new();
+    let module_tag = build_module(&mut pools);
+    let statistics = ModuleStatistics::new(&mut pools, module_tag).expect("Failed to collect statistics");
+
+    assert_eq!(statistics.get_function_count(), 2);
+    assert_eq!(statistics.get_declaration_count(), 1);
+    assert_eq!(statistics.get_global_count(), 1);
+    assert_eq!(statistics.get_block_count(), 5);
+    assert_eq!(statistics.get_instruction_count(), 8);
+    assert_eq!(statistics.get_opcode_counts().get("ret"), Some(&2));
+    assert_eq!(statistics.get_alloca_count(), 1);
+    assert_eq!(statistics.get_call_count(), 1);
+    assert_eq!(statistics.get_max_loop_depth(), 1);
+    assert_eq!(statistics.get_max_cyclomatic_complexity(), 2);
+
+    let report = statistics.to_string();
+    assert!(report.starts_with("2 functions, 1 declarations, 1 globals, 5 blocks, 8 instructions\n"), "Unexpected report: {}", report);
+    assert!(report.contains("  main: 4 blocks, 7 instructions, 1 allocas, 1 calls, max loop depth 1, cyclomatic complexity 2\n"), "Unexpected report: {}", report);
+}
@@ -4,7 +4,7 @@
 //! encapsulating them within `Arc` and `RwLock` for concurrent access.
 
 extern crate llvm_sys as llvm;
-use llvm::{execution_engine::LLVMExecutionEngineRef, prelude::{LLVMBasicBlockRef, LLVMBuilderRef, LLVMContextRef, LLVMModuleRef, LLVMTypeRef, LLVMValueRef}};
+use llvm::{execution_engine::LLVMExecutionEngineRef, orc2::lljit::LLVMOrcLLJITRef, prelude::{LLVMBasicBlockRef, LLVMBuilderRef, LLVMContextRef, LLVMModuleRef, LLVMTypeRef, LLVMValueRef}};
 use std::{ffi::c_void, ptr::NonNull, sync::{Arc, RwLock}};
 
 /// Enum to represent various LLVM references for type management.
@@ -30,7 +30,10 @@ pub enum LLVMRef {
     Type(LLVMTypeRef), // https://llvm.org/doxygen/classllvm_1_1Type.html
     /// Represents an LLVM execution engine, which is capable
     /// of compiling and executing LLVM bitcode to native machine code.
-    ExecutionEngine(LLVMExecutionEngineRef) // https://llvm.org/doxygen/group__LLVMCExecutionEngine.html
+    ExecutionEngine(LLVMExecutionEngineRef), // https://llvm.org/doxygen/group__LLVMCExecutionEngine.html
+    /// Represents an ORC LLJIT instance, which links and executes precompiled
+    /// object files in addition to compiling LLVM IR on demand.
+    Lljit(LLVMOrcLLJITRef) // https://llvm.org/doxygen/classllvm_1_1orc_1_1LLJIT.html
 }
 
 /// Represents types of LLVM references for runtime conversion.
@@ -59,6 +62,9 @@ pub enum LLVMRefType {
     /// Manages the execution of compiled LLVM code. It is responsible for
     /// compiling LLVM IR to machine code and executing or providing access to that code.
     ExecutionEngine,
+    /// An ORC-based JIT that links object files into the running process and
+    /// resolves their symbols on lookup.
+    Lljit,
 }
 
 /// Helper methods for the LLVMRef enum to manage raw pointer conversions safely.
@@ -76,6 +82,7 @@ impl LLVMRef {
             LLVMRef::Builder(ptr) => ptr as *mut c_void,
             LLVMRef::Type(ptr) => ptr as *mut c_void,
             LLVMRef::ExecutionEngine(ptr) => ptr as *mut c_void,
+            LLVMRef::Lljit(ptr) => ptr as *mut c_void,
         }
     }
 
@@ -99,6 +106,7 @@ impl LLVMRef {
             LLVMRefType::Builder => LLVMRef::Builder(ptr as LLVMBuilderRef),
             LLVMRefType::Type => LLVMRef::Type(ptr as LLVMTypeRef),
             LLVMRefType::ExecutionEngine => LLVMRef::ExecutionEngine(ptr as LLVMExecutionEngineRef),
+            LLVMRefType::Lljit => LLVMRef::Lljit(ptr as LLVMOrcLLJITRef),
         }
     }
 }
@@ -0,0 +1,1378 @@
+//! This module provides an interpreter that executes the functions of an IRManager module one
+//! instruction at a time, for debugging generated IR.
+//!
+//! Unlike `jit::interpreter`, which hands the module to LLVM's own interpreter, memory is modeled
+//! on the Rust side. Every `alloca`, global and function is a separate allocation with its own
+//! bounds and per-byte initialization state, so out-of-bounds pointers, reads of uninitialized
+//! memory and accesses to the stack slots of returned functions are reported as undefined
+//! behavior where native execution would silently read or corrupt whatever memory is there.
+//!
+//! Execution advances one instruction at a time with `step`, or until a breakpoint is reached
+//! with `run`. In between, the SSA values and stack slots of the current frame can be inspected.
+//! The module must not be changed while it is being interpreted.
+
+extern crate llvm_sys as llvm;
+use std::{collections::{HashMap, HashSet}, fmt};
+use llvm::{core, prelude::{LLVMBasicBlockRef, LLVMTypeRef, LLVMValueRef}, target::{self, LLVMTargetDataRef}, LLVMIntPredicate, LLVMOpcode, LLVMRealPredicate, LLVMTypeKind};
+use ir::{core::{BasicBlockTag, IRManager, ModuleTag, ValueTag}, inspect::{block_name, print_type, print_value, value_name}};
+use crate::{memory::{AllocationKind, Memory, MemoryError, Pointer, MAX_ALLOCATION_SIZE}, value::{sign_extend, truncate, RuntimeValue}};
+
+/// The kinds of undefined behavior detected by the interpreter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UndefinedBehaviorKind {
+    /// A load or store through a null pointer, or a pointer into no allocation.
+    NullDereference,
+    /// A load or store to a stack slot of a function that has returned.
+    UseAfterReturn,
+    /// A load or store that reaches outside the allocation its pointer points into.
+    OutOfBoundsAccess,
+    /// A `getelementptr inbounds` whose result lies outside the allocation of its base pointer.
+    OutOfBoundsPointer,
+    /// A load of memory that has never been written.
+    UninitializedLoad,
+    /// An integer division or remainder by zero.
+    DivisionByZero,
+    /// A signed division or remainder of the minimum value by -1, whose result overflows.
+    DivisionOverflow,
+    /// A use of `undef` or `poison`, or of a shift by at least the bit width of its operand.
+    PoisonValue,
+    /// A call through a pointer that does not point to a function, or with the wrong number of
+    /// arguments.
+    InvalidCall,
+    /// Control reached an `unreachable` instruction.
+    ReachedUnreachable,
+}
+
+impl UndefinedBehaviorKind {
+    /// Retrieves a short identifier for the kind of undefined behavior.
+    ///
+    /// # Returns
+    /// The name of the kind in kebab case.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UndefinedBehaviorKind::NullDereference => "null-dereference",
+            UndefinedBehaviorKind::UseAfterReturn => "use-after-return",
+            UndefinedBehaviorKind::OutOfBoundsAccess => "out-of-bounds-access",
+            UndefinedBehaviorKind::OutOfBoundsPointer => "out-of-bounds-pointer",
+            UndefinedBehaviorKind::UninitializedLoad => "uninitialized-load",
+            UndefinedBehaviorKind::DivisionByZero => "division-by-zero",
+            UndefinedBehaviorKind::DivisionOverflow => "division-overflow",
+            UndefinedBehaviorKind::PoisonValue => "poison-value",
+            UndefinedBehaviorKind::InvalidCall => "invalid-call",
+            UndefinedBehaviorKind::ReachedUnreachable => "reached-unreachable",
+        }
+    }
+}
+
+/// Undefined behavior found while executing an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndefinedBehavior {
+    kind: UndefinedBehaviorKind,
+    function: String,
+    block: String,
+    instruction: String,
+    message: String,
+}
+
+impl UndefinedBehavior {
+    /// Retrieves the kind of undefined behavior.
+    ///
+    /// # Returns
+    /// The `UndefinedBehaviorKind`.
+    pub fn get_kind(&self) -> UndefinedBehaviorKind {
+        self.kind
+    }
+
+    /// Retrieves the name of the function the instruction belongs to.
+    ///
+    /// # Returns
+    /// The function name.
+    pub fn get_function(&self) -> &str {
+        &self.function
+    }
+
+    /// Retrieves the name of the block the instruction belongs to.
+    ///
+    /// # Returns
+    /// The block name, or `<block N>` for the N-th block if it is unnamed.
+    pub fn get_block(&self) -> &str {
+        &self.block
+    }
+
+    /// Retrieves the instruction with undefined behavior.
+    ///
+    /// # Returns
+    /// The textual IR of the instruction.
+    pub fn get_instruction(&self) -> &str {
+        &self.instruction
+    }
+
+    /// Retrieves an explanation of the undefined behavior.
+    ///
+    /// # Returns
+    /// The explanation, naming the values and allocations involved.
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for UndefinedBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] in function '{}', block '{}' at `{}`: {}",
+            self.kind.as_str(), self.function, self.block, self.instruction, self.message
+        )
+    }
+}
+
+/// Why the interpreter paused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    /// An instruction was executed, and the interpreter is paused before the next one.
+    Stepped,
+    /// Control entered a block with a breakpoint, and none of its instructions have executed yet.
+    Breakpoint(BasicBlockTag),
+    /// The started function returned, with its return value unless it returns `void`.
+    Returned(Option<RuntimeValue>),
+    /// The next instruction has undefined behavior. Execution cannot continue, but the state
+    /// before the instruction can still be inspected.
+    UndefinedBehavior(UndefinedBehavior),
+}
+
+/// A position in an executing function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    function: ValueTag,
+    block: BasicBlockTag,
+    instruction: ValueTag,
+}
+
+impl Location {
+    /// Retrieves the executing function.
+    ///
+    /// # Returns
+    /// The tag of the function.
+    pub fn get_function(&self) -> ValueTag {
+        self.function
+    }
+
+    /// Retrieves the executing block.
+    ///
+    /// # Returns
+    /// The tag of the block.
+    pub fn get_block(&self) -> BasicBlockTag {
+        self.block
+    }
+
+    /// Retrieves the instruction to execute next, or the call being executed in callers.
+    ///
+    /// # Returns
+    /// The tag of the instruction.
+    pub fn get_instruction(&self) -> ValueTag {
+        self.instruction
+    }
+}
+
+/// A stack slot created by an `alloca` in the current frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackSlot {
+    alloca: ValueTag,
+    name: String,
+    pointer: Pointer,
+    size: usize,
+}
+
+impl StackSlot {
+    /// Retrieves the `alloca` that created the slot.
+    ///
+    /// # Returns
+    /// The tag of the `alloca` instruction.
+    pub fn get_alloca(&self) -> ValueTag {
+        self.alloca
+    }
+
+    /// Retrieves the name of the slot.
+    ///
+    /// # Returns
+    /// The name of the `alloca`, or its textual IR if it is unnamed.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves the address of the slot.
+    ///
+    /// # Returns
+    /// A `Pointer` to the start of the slot.
+    pub fn get_pointer(&self) -> Pointer {
+        self.pointer
+    }
+
+    /// Retrieves the size of the slot.
+    ///
+    /// # Returns
+    /// The size in bytes.
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+}
+
+/// The state of a function call.
+#[derive(Debug)]
+struct Frame {
+    function: LLVMValueRef,
+    block: LLVMBasicBlockRef,
+    instruction: LLVMValueRef,
+    values: HashMap<LLVMValueRef, RuntimeValue>,
+    allocas: Vec<(LLVMValueRef, Pointer)>,
+}
+
+/// Why an instruction could not be executed.
+enum Fault {
+    /// The instruction has undefined behavior.
+    Undefined(UndefinedBehaviorKind, String),
+    /// The instruction is malformed or not supported by the interpreter.
+    Error(String),
+}
+
+impl From<MemoryError> for Fault {
+    fn from(error: MemoryError) -> Self {
+        let kind = match error {
+            MemoryError::NullPointer => UndefinedBehaviorKind::NullDereference,
+            MemoryError::DeadAllocation { .. } => UndefinedBehaviorKind::UseAfterReturn,
+            MemoryError::OutOfBounds { .. } | MemoryError::AddressOverflow { .. } => UndefinedBehaviorKind::OutOfBoundsAccess,
+            MemoryError::Uninitialized { .. } => UndefinedBehaviorKind::UninitializedLoad,
+        };
+        Fault::Undefined(kind, error.to_string())
+    }
+}
+
+/// Executes the functions of a module instruction by instruction.
+pub struct StepInterpreter {
+    target_data: LLVMTargetDataRef,
+    memory: Memory,
+    globals: HashMap<LLVMValueRef, Pointer>,
+    functions: HashMap<usize, LLVMValueRef>,
+    value_tags: HashMap<LLVMValueRef, ValueTag>,
+    values: HashMap<ValueTag, LLVMValueRef>,
+    block_tags: HashMap<LLVMBasicBlockRef, BasicBlockTag>,
+    frames: Vec<Frame>,
+    breakpoints: HashSet<BasicBlockTag>,
+    at_block_entry: bool,
+    undefined_behavior: Option<UndefinedBehavior>,
+    steps: usize,
+}
+
+impl StepInterpreter {
+    /// Constructs a new `StepInterpreter` for a module, with its global variables initialized.
+    ///
+    /// Functions, arguments, blocks and instructions not yet stored in the IRManager are stored,
+    /// so every value the interpreter reports has a tag. Values are identified by the tags the
+    /// IRManager most recently assigned them when the interpreter is created.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the module.
+    /// * `module_tag` - The tag of the module to interpret.
+    ///
+    /// # Returns
+    /// Returns `Ok(StepInterpreter)` on success, or `Err(String)` if the module could not be found or a
+    /// global has an initializer the interpreter does not support.
+    pub fn new(manager: &mut IRManager, module_tag: ModuleTag) -> Result<Self, String> {
+        let module = manager.get_module_ptr(module_tag).ok_or_else(|| format!("Module {:?} not found.", module_tag))?;
+        let mut interpreter = Self {
+            target_data: unsafe { target::LLVMGetModuleDataLayout(module) },
+            memory: Memory::new(),
+            globals: HashMap::new(),
+            functions: HashMap::new(),
+            value_tags: HashMap::new(),
+            values: HashMap::new(),
+            block_tags: HashMap::new(),
+            frames: Vec::new(),
+            breakpoints: HashSet::new(),
+            at_block_entry: false,
+            undefined_behavior: None,
+            steps: 0,
+        };
+
+        unsafe {
+            let mut function = core::LLVMGetFirstFunction(module);
+            while !function.is_null() {
+                interpreter.tag_value(manager, function)?;
+                let pointer = interpreter.memory.allocate(&value_name(function), AllocationKind::Function, 0);
+                interpreter.globals.insert(function, pointer);
+                interpreter.functions.insert(pointer.get_allocation(), function);
+
+                for index in 0..core::LLVMCountParams(function) {
+                    interpreter.tag_value(manager, core::LLVMGetParam(function, index))?;
+                }
+                let mut block = core::LLVMGetFirstBasicBlock(function);
+                while !block.is_null() {
+                    interpreter.tag_block(manager, block)?;
+                    let mut instruction = core::LLVMGetFirstInstruction(block);
+                    while !instruction.is_null() {
+                        interpreter.tag_value(manager, instruction)?;
+                        instruction = core::LLVMGetNextInstruction(instruction);
+                    }
+                    block = core::LLVMGetNextBasicBlock(block);
+                }
+                function = core::LLVMGetNextFunction(function);
+            }
+
+            let mut globals = Vec::new();
+            let mut global = core::LLVMGetFirstGlobal(module);
+            while !global.is_null() {
+                interpreter.tag_value(manager, global)?;
+                let size = interpreter.size_of(core::LLVMGlobalGetValueType(global));
+                if size > MAX_ALLOCATION_SIZE {
+                    return Err(format!("Global '{}' of {} bytes exceeds the interpreter's limit of {} bytes.", value_name(global), size, MAX_ALLOCATION_SIZE));
+                }
+                let pointer = interpreter.memory.allocate(&value_name(global), AllocationKind::Global, size);
+                interpreter.globals.insert(global, pointer);
+                globals.push((global, pointer));
+                global = core::LLVMGetNextGlobal(global);
+            }
+
+            for (global, pointer) in globals {
+                let initializer = core::LLVMGetInitializer(global);
+                if !initializer.is_null() {
+                    interpreter.initialize(pointer, initializer).map_err(|fault| match fault {
+                        Fault::Undefined(_, message) | Fault::Error(message) => {
+                            format!("Failed to initialize global '{}': {}", value_name(global), message)
+                        }
+                    })?;
+                }
+            }
+        }
+
+        Ok(interpreter)
+    }
+
+    /// Starts executing a function, discarding any execution in progress. The interpreter pauses
+    /// before the first instruction of the function.
+    ///
+    /// Global variables keep the values written by earlier executions.
+    ///
+    /// # Parameters
+    /// * `function` - The tag of the function to execute.
+    /// * `args` - The arguments, one per parameter, each matching the parameter's type.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the tag does not refer to a function with a
+    /// body of this module, or the arguments do not match its parameters.
+    pub fn start(&mut self, function: ValueTag, args: &[RuntimeValue]) -> Result<(), String> {
+        let function_ptr = *self.values.get(&function).ok_or_else(|| format!("Value {:?} is not part of the interpreted module.", function))?;
+        unsafe {
+            if core::LLVMIsAFunction(function_ptr).is_null() {
+                return Err(format!("Value {:?} is not a function.", function));
+            }
+            if core::LLVMCountBasicBlocks(function_ptr) == 0 {
+                return Err(format!("Function '{}' is a declaration without a body.", value_name(function_ptr)));
+            }
+
+            let param_count = core::LLVMCountParams(function_ptr) as usize;
+            if args.len() != param_count {
+                return Err(format!("Function '{}' takes {} arguments, but {} were given.", value_name(function_ptr), param_count, args.len()));
+            }
+            for (index, arg) in args.iter().enumerate() {
+                let param_type = core::LLVMTypeOf(core::LLVMGetParam(function_ptr, index as u32));
+                if !has_type(arg, param_type) {
+                    return Err(format!(
+                        "Argument {} of function '{}' must be of type {}, but `{}` was given.",
+                        index, value_name(function_ptr), print_type(param_type), arg
+                    ));
+                }
+            }
+        }
+
+        self.reset();
+        self.enter(function_ptr, args.to_vec()).map_err(|fault| match fault {
+            Fault::Undefined(_, message) | Fault::Error(message) => message,
+        })
+    }
+
+    /// Abandons the execution in progress, releasing the stack slots of every frame.
+    pub fn reset(&mut self) {
+        while let Some(frame) = self.frames.pop() {
+            for (_, pointer) in frame.allocas {
+                self.memory.release(pointer);
+            }
+        }
+        self.at_block_entry = false;
+        self.undefined_behavior = None;
+    }
+
+    /// Executes the next instruction.
+    ///
+    /// A branch also evaluates the `phi` nodes of its target, so the interpreter never pauses on a
+    /// `phi`. A call pauses before the first instruction of the callee.
+    ///
+    /// # Returns
+    /// Returns `Ok(StopReason)` describing the state after the instruction, or `Err(String)` if no
+    /// function is being executed, execution stopped at undefined behavior, or the instruction is
+    /// malformed or not supported. Breakpoints are not reported by `step`.
+    pub fn step(&mut self) -> Result<StopReason, String> {
+        if let Some(undefined_behavior) = &self.undefined_behavior {
+            return Err(format!("Execution stopped at undefined behavior: {}", undefined_behavior));
+        }
+        let frame = self.frames.last().ok_or_else(|| "No function is being executed.".to_string())?;
+        let (function, block, instruction) = (frame.function, frame.block, frame.instruction);
+
+        self.at_block_entry = false;
+        match self.execute(instruction) {
+            Ok(reason) => {
+                self.steps += 1;
+                Ok(reason.unwrap_or(StopReason::Stepped))
+            }
+            Err(Fault::Undefined(kind, message)) => {
+                let undefined_behavior = UndefinedBehavior {
+                    kind,
+                    function: unsafe { value_name(function) },
+                    block: unsafe { block_name(block) },
+                    instruction: unsafe { print_value(instruction) },
+                    message,
+                };
+                self.undefined_behavior = Some(undefined_behavior.clone());
+                Ok(StopReason::UndefinedBehavior(undefined_behavior))
+            }
+            Err(Fault::Error(message)) => Err(message),
+        }
+    }
+
+    /// Executes instructions until control enters a block with a breakpoint, the started function
+    /// returns, or undefined behavior is found.
+    ///
+    /// If the interpreter is paused at the entry of a block with a breakpoint that has not been
+    /// reported yet, `run` reports it without executing anything. Note that `run` does not return
+    /// if the program loops forever.
+    ///
+    /// # Returns
+    /// Returns `Ok(StopReason)` describing why execution paused, or `Err(String)` under the same
+    /// conditions as `step`.
+    pub fn run(&mut self) -> Result<StopReason, String> {
+        loop {
+            if self.at_block_entry {
+                self.at_block_entry = false;
+                let block = self.frames.last().and_then(|frame| self.block_tags.get(&frame.block)).copied();
+                if let Some(block) = block.filter(|block| self.breakpoints.contains(block)) {
+                    return Ok(StopReason::Breakpoint(block));
+                }
+            }
+            match self.step()? {
+                StopReason::Stepped => continue,
+                reason => return Ok(reason),
+            }
+        }
+    }
+
+    /// Sets a breakpoint, so that `run` pauses whenever control enters a block.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the block is not part of the interpreted
+    /// module.
+    pub fn add_breakpoint(&mut self, block: BasicBlockTag) -> Result<(), String> {
+        if !self.block_tags.values().any(|tag| *tag == block) {
+            return Err(format!("Basic block {:?} is not part of the interpreted module.", block));
+        }
+        self.breakpoints.insert(block);
+        Ok(())
+    }
+
+    /// Removes a breakpoint.
+    ///
+    /// # Parameters
+    /// * `block` - The tag of the block.
+    ///
+    /// # Returns
+    /// `true` if the block had a breakpoint.
+    pub fn remove_breakpoint(&mut self, block: BasicBlockTag) -> bool {
+        self.breakpoints.remove(&block)
+    }
+
+    /// Retrieves the blocks with breakpoints.
+    ///
+    /// # Returns
+    /// The tags of the blocks, in no particular order.
+    pub fn get_breakpoints(&self) -> Vec<BasicBlockTag> {
+        self.breakpoints.iter().copied().collect()
+    }
+
+    /// Determines whether a function is being executed.
+    ///
+    /// # Returns
+    /// `true` between `start` and the return of the started function, including after undefined
+    /// behavior is found.
+    pub fn is_running(&self) -> bool {
+        !self.frames.is_empty()
+    }
+
+    /// Retrieves the position of the interpreter in the current function.
+    ///
+    /// # Returns
+    /// An `Option` containing the location of the next instruction, or `None` if no function is
+    /// being executed.
+    pub fn get_location(&self) -> Option<Location> {
+        self.frames.last().and_then(|frame| self.location(frame))
+    }
+
+    /// Retrieves the positions of all active calls.
+    ///
+    /// # Returns
+    /// One location per frame, starting with the started function. Callers are positioned at
+    /// the call they are executing.
+    pub fn get_call_stack(&self) -> Vec<Location> {
+        self.frames.iter().filter_map(|frame| self.location(frame)).collect()
+    }
+
+    /// Retrieves the value of an argument, instruction or global in the current frame.
+    ///
+    /// # Parameters
+    /// * `value` - The tag of the value.
+    ///
+    /// # Returns
+    /// An `Option` containing the most recently computed value, the address of a global or
+    /// function, or `None` if the value has not been computed in the current frame.
+    pub fn get_value(&self, value: ValueTag) -> Option<RuntimeValue> {
+        let value_ptr = self.values.get(&value)?;
+        if let Some(pointer) = self.globals.get(value_ptr) {
+            return Some(RuntimeValue::Pointer(*pointer));
+        }
+        self.frames.last()?.values.get(value_ptr).copied()
+    }
+
+    /// Retrieves the stack slots created so far by the current frame.
+    ///
+    /// # Returns
+    /// The slots in the order their `alloca`s executed. Their contents can be read through
+    /// `get_memory`.
+    pub fn get_stack_slots(&self) -> Vec<StackSlot> {
+        let Some(frame) = self.frames.last() else {
+            return Vec::new();
+        };
+        frame.allocas.iter().filter_map(|(alloca, pointer)| {
+            let allocation = self.memory.get_allocation(*pointer)?;
+            Some(StackSlot {
+                alloca: *self.value_tags.get(alloca)?,
+                name: allocation.get_name().to_string(),
+                pointer: *pointer,
+                size: allocation.get_size(),
+            })
+        }).collect()
+    }
+
+    /// Retrieves the memory of the interpreted program.
+    ///
+    /// # Returns
+    /// The `Memory` holding the stack slots, globals and functions.
+    pub fn get_memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// Retrieves the address of a global variable or function.
+    ///
+    /// # Parameters
+    /// * `global` - The tag of the global or function.
+    ///
+    /// # Returns
+    /// An `Option` containing the address, or `None` if the tag does not refer to a global of the
+    /// interpreted module.
+    pub fn get_global(&self, global: ValueTag) -> Option<Pointer> {
+        self.values.get(&global).and_then(|global_ptr| self.globals.get(global_ptr)).copied()
+    }
+
+    /// Retrieves the undefined behavior execution stopped at.
+    ///
+    /// # Returns
+    /// An `Option` containing the undefined behavior, or `None` if none has been found since the
+    /// last `start`.
+    pub fn get_undefined_behavior(&self) -> Option<&UndefinedBehavior> {
+        self.undefined_behavior.as_ref()
+    }
+
+    /// Retrieves the number of instructions executed since the interpreter was created.
+    ///
+    /// # Returns
+    /// The step count.
+    pub fn get_step_count(&self) -> usize {
+        self.steps
+    }
+
+    /// Executes an instruction, moving to the next one.
+    ///
+    /// # Parameters
+    /// * `instruction` - The next instruction of the current frame.
+    ///
+    /// # Returns
+    /// Returns `Ok(Some(StopReason::Returned))` if the started function returned, `Ok(None)` if
+    /// execution can continue, or `Err(Fault)` if the instruction cannot be executed. The state is
+    /// left unchanged on error.
+    fn execute(&mut self, instruction: LLVMValueRef) -> Result<Option<StopReason>, Fault> {
+        unsafe {
+            if core::LLVMIsATerminatorInst(instruction).is_null() && core::LLVMGetNextInstruction(instruction).is_null() {
+                let block = core::LLVMGetInstructionParent(instruction);
+                return Err(Fault::Error(format!("Basic block '{}' has no terminator.", block_name(block))));
+            }
+        }
+
+        match unsafe { core::LLVMGetInstructionOpcode(instruction) } {
+            LLVMOpcode::LLVMBr => {
+                let successor = if unsafe { core::LLVMIsConditional(instruction) } != 0 {
+                    let (_, condition) = self.int_operand(instruction, 0)?;
+                    unsafe { core::LLVMGetSuccessor(instruction, if condition != 0 { 0 } else { 1 }) }
+                } else {
+                    unsafe { core::LLVMGetSuccessor(instruction, 0) }
+                };
+                self.jump(successor)?;
+            }
+            LLVMOpcode::LLVMSwitch => {
+                let (_, condition) = self.int_operand(instruction, 0)?;
+                let mut successor = unsafe { core::LLVMGetSuccessor(instruction, 0) };
+                for index in 1..unsafe { core::LLVMGetNumSuccessors(instruction) } {
+                    let (_, case) = self.int_operand(instruction, index * 2)?;
+                    if case == condition {
+                        successor = unsafe { core::LLVMGetSuccessor(instruction, index) };
+                        break;
+                    }
+                }
+                self.jump(successor)?;
+            }
+            LLVMOpcode::LLVMRet => {
+                let value = if unsafe { core::LLVMGetNumOperands(instruction) } > 0 {
+                    Some(self.operand(instruction, 0)?)
+                } else {
+                    None
+                };
+                if let Some(frame) = self.frames.pop() {
+                    for (_, pointer) in frame.allocas {
+                        self.memory.release(pointer);
+                    }
+                }
+                match self.frames.last_mut() {
+                    Some(caller) => {
+                        if let Some(value) = value {
+                            caller.values.insert(caller.instruction, value);
+                        }
+                        caller.instruction = unsafe { core::LLVMGetNextInstruction(caller.instruction) };
+                    }
+                    None => return Ok(Some(StopReason::Returned(value))),
+                }
+            }
+            LLVMOpcode::LLVMUnreachable => {
+                return Err(Fault::Undefined(UndefinedBehaviorKind::ReachedUnreachable, "control reached an `unreachable` instruction".to_string()));
+            }
+            LLVMOpcode::LLVMCall => {
+                let callee = self.callee(instruction)?;
+                let arg_count = unsafe { core::LLVMGetNumArgOperands(instruction) };
+                let args = (0..arg_count).map(|index| self.operand(instruction, index)).collect::<Result<Vec<_>, _>>()?;
+                unsafe {
+                    if core::LLVMCountBasicBlocks(callee) == 0 {
+                        return Err(Fault::Error(format!("Calls to external function '{}' are not supported.", value_name(callee))));
+                    }
+                    if core::LLVMCountParams(callee) as usize != args.len() {
+                        return Err(Fault::Undefined(
+                            UndefinedBehaviorKind::InvalidCall,
+                            format!("function '{}' takes {} arguments, but is called with {}", value_name(callee), core::LLVMCountParams(callee), args.len()),
+                        ));
+                    }
+                }
+                self.enter(callee, args)?;
+            }
+            LLVMOpcode::LLVMPHI => {
+                return Err(Fault::Error(format!("`{}` is not at the start of its block.", unsafe { print_value(instruction) })));
+            }
+            opcode => {
+                let value = self.compute(instruction, opcode)?;
+                if let Some(frame) = self.frames.last_mut() {
+                    if let Some(value) = value {
+                        frame.values.insert(instruction, value);
+                    }
+                    frame.instruction = unsafe { core::LLVMGetNextInstruction(instruction) };
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Computes the result of an instruction or constant expression that does not transfer
+    /// control.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `opcode` - Its opcode.
+    ///
+    /// # Returns
+    /// Returns `Ok(Some(RuntimeValue))` containing the result, `Ok(None)` for instructions without
+    /// a result, or `Err(Fault)` if the value cannot be computed.
+    fn compute(&mut self, value: LLVMValueRef, opcode: LLVMOpcode) -> Result<Option<RuntimeValue>, Fault> {
+        let type_ref = unsafe { core::LLVMTypeOf(value) };
+        let result = match opcode {
+            LLVMOpcode::LLVMAdd | LLVMOpcode::LLVMSub | LLVMOpcode::LLVMMul | LLVMOpcode::LLVMUDiv | LLVMOpcode::LLVMSDiv
+            | LLVMOpcode::LLVMURem | LLVMOpcode::LLVMSRem | LLVMOpcode::LLVMShl | LLVMOpcode::LLVMLShr | LLVMOpcode::LLVMAShr
+            | LLVMOpcode::LLVMAnd | LLVMOpcode::LLVMOr | LLVMOpcode::LLVMXor => {
+                let (bits, left) = self.int_operand(value, 0)?;
+                let (_, right) = self.int_operand(value, 1)?;
+                RuntimeValue::Int { bits, value: integer_operation(opcode, bits, left, right)? }
+            }
+            LLVMOpcode::LLVMFAdd | LLVMOpcode::LLVMFSub | LLVMOpcode::LLVMFMul | LLVMOpcode::LLVMFDiv | LLVMOpcode::LLVMFRem => {
+                let left = self.float_operand(value, 0)?;
+                let right = self.float_operand(value, 1)?;
+                let result = match opcode {
+                    LLVMOpcode::LLVMFAdd => left + right,
+                    LLVMOpcode::LLVMFSub => left - right,
+                    LLVMOpcode::LLVMFMul => left * right,
+                    LLVMOpcode::LLVMFDiv => left / right,
+                    _ => left % right,
+                };
+                float_value(type_ref, result)?
+            }
+            LLVMOpcode::LLVMFNeg => float_value(type_ref, -self.float_operand(value, 0)?)?,
+            LLVMOpcode::LLVMICmp => {
+                let (bits, left) = self.comparable_operand(value, 0)?;
+                let (_, right) = self.comparable_operand(value, 1)?;
+                let (signed_left, signed_right) = (sign_extend(bits, left), sign_extend(bits, right));
+                RuntimeValue::from_bool(match unsafe { core::LLVMGetICmpPredicate(value) } {
+                    LLVMIntPredicate::LLVMIntEQ => left == right,
+                    LLVMIntPredicate::LLVMIntNE => left != right,
+                    LLVMIntPredicate::LLVMIntUGT => left > right,
+                    LLVMIntPredicate::LLVMIntUGE => left >= right,
+                    LLVMIntPredicate::LLVMIntULT => left < right,
+                    LLVMIntPredicate::LLVMIntULE => left <= right,
+                    LLVMIntPredicate::LLVMIntSGT => signed_left > signed_right,
+                    LLVMIntPredicate::LLVMIntSGE => signed_left >= signed_right,
+                    LLVMIntPredicate::LLVMIntSLT => signed_left < signed_right,
+                    LLVMIntPredicate::LLVMIntSLE => signed_left <= signed_right,
+                })
+            }
+            LLVMOpcode::LLVMFCmp => {
+                let left = self.float_operand(value, 0)?;
+                let right = self.float_operand(value, 1)?;
+                let unordered = left.is_nan() || right.is_nan();
+                RuntimeValue::from_bool(match unsafe { core::LLVMGetFCmpPredicate(value) } {
+                    LLVMRealPredicate::LLVMRealPredicateFalse => false,
+                    LLVMRealPredicate::LLVMRealOEQ => !unordered && left == right,
+                    LLVMRealPredicate::LLVMRealOGT => !unordered && left > right,
+                    LLVMRealPredicate::LLVMRealOGE => !unordered && left >= right,
+                    LLVMRealPredicate::LLVMRealOLT => !unordered && left < right,
+                    LLVMRealPredicate::LLVMRealOLE => !unordered && left <= right,
+                    LLVMRealPredicate::LLVMRealONE => !unordered && left != right,
+                    LLVMRealPredicate::LLVMRealORD => !unordered,
+                    LLVMRealPredicate::LLVMRealUNO => unordered,
+                    LLVMRealPredicate::LLVMRealUEQ => unordered || left == right,
+                    LLVMRealPredicate::LLVMRealUGT => unordered || left > right,
+                    LLVMRealPredicate::LLVMRealUGE => unordered || left >= right,
+                    LLVMRealPredicate::LLVMRealULT => unordered || left < right,
+                    LLVMRealPredicate::LLVMRealULE => unordered || left <= right,
+                    LLVMRealPredicate::LLVMRealUNE => unordered || left != right,
+                    LLVMRealPredicate::LLVMRealPredicateTrue => true,
+                })
+            }
+            LLVMOpcode::LLVMTrunc | LLVMOpcode::LLVMZExt => {
+                let (_, operand) = self.int_operand(value, 0)?;
+                let bits = int_width(type_ref)?;
+                RuntimeValue::Int { bits, value: truncate(bits, operand) }
+            }
+            LLVMOpcode::LLVMSExt => {
+                let (operand_bits, operand) = self.int_operand(value, 0)?;
+                RuntimeValue::from_int(int_width(type_ref)?, sign_extend(operand_bits, operand))
+            }
+            LLVMOpcode::LLVMPtrToInt => {
+                let pointer = self.pointer_operand(value, 0)?;
+                let bits = int_width(type_ref)?;
+                RuntimeValue::Int { bits, value: truncate(bits, pointer.to_address()?) }
+            }
+            LLVMOpcode::LLVMIntToPtr => {
+                let (_, address) = self.int_operand(value, 0)?;
+                RuntimeValue::Pointer(Pointer::from_address(address))
+            }
+            LLVMOpcode::LLVMBitCast => {
+                let operand = self.operand(value, 0)?;
+                from_bytes(type_ref, &operand.to_bytes()?)?
+            }
+            LLVMOpcode::LLVMAddrSpaceCast | LLVMOpcode::LLVMFreeze => self.operand(value, 0)?,
+            LLVMOpcode::LLVMSIToFP => {
+                let (bits, operand) = self.int_operand(value, 0)?;
+                float_value(type_ref, sign_extend(bits, operand) as f64)?
+            }
+            LLVMOpcode::LLVMUIToFP => {
+                let (_, operand) = self.int_operand(value, 0)?;
+                float_value(type_ref, operand as f64)?
+            }
+            LLVMOpcode::LLVMFPToSI => RuntimeValue::from_int(int_width(type_ref)?, self.float_operand(value, 0)? as i64),
+            LLVMOpcode::LLVMFPToUI => {
+                let bits = int_width(type_ref)?;
+                RuntimeValue::Int { bits, value: truncate(bits, self.float_operand(value, 0)? as u64) }
+            }
+            LLVMOpcode::LLVMFPTrunc | LLVMOpcode::LLVMFPExt => float_value(type_ref, self.float_operand(value, 0)?)?,
+            LLVMOpcode::LLVMSelect => {
+                let (_, condition) = self.int_operand(value, 0)?;
+                self.operand(value, if condition != 0 { 1 } else { 2 })?
+            }
+            LLVMOpcode::LLVMGetElementPtr => RuntimeValue::Pointer(self.element_pointer(value)?),
+            LLVMOpcode::LLVMAlloca => {
+                let (bits, count) = self.int_operand(value, 0)?;
+                let count = sign_extend(bits, count);
+                let element_size = self.size_of(unsafe { core::LLVMGetAllocatedType(value) });
+                let size = usize::try_from(count).ok()
+                    .and_then(|count| element_size.checked_mul(count))
+                    .filter(|size| *size <= MAX_ALLOCATION_SIZE)
+                    .ok_or_else(|| Fault::Error(format!(
+                        "`{}` allocates {} elements of {} bytes, which is negative or exceeds the interpreter's limit of {} bytes.",
+                        unsafe { print_value(value) }, count, element_size, MAX_ALLOCATION_SIZE,
+                    )))?;
+                let name = match unsafe { value_name(value) } {
+                    name if name.is_empty() => unsafe { print_value(value) },
+                    name => name,
+                };
+                let pointer = self.memory.allocate(&name, AllocationKind::Stack, size);
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.allocas.push((value, pointer));
+                }
+                RuntimeValue::Pointer(pointer)
+            }
+            LLVMOpcode::LLVMLoad => {
+                let pointer = self.pointer_operand(value, 0)?;
+                let size = unsafe { target::LLVMStoreSizeOfType(self.target_data, type_ref) } as usize;
+                let bytes = self.memory.read(pointer, size)?;
+                from_bytes(type_ref, &bytes)?
+            }
+            LLVMOpcode::LLVMStore => {
+                let stored = self.operand(value, 0)?;
+                let pointer = self.pointer_operand(value, 1)?;
+                self.memory.write(pointer, &stored.to_bytes()?)?;
+                return Ok(None);
+            }
+            _ => return Err(Fault::Error(format!("Unsupported instruction `{}`.", unsafe { print_value(value) }))),
+        };
+        Ok(Some(result))
+    }
+
+    /// Computes the address produced by a `getelementptr`.
+    ///
+    /// # Parameters
+    /// * `value` - The `getelementptr` instruction or constant expression.
+    ///
+    /// # Returns
+    /// Returns `Ok(Pointer)` containing the address, or `Err(Fault)` if an operand cannot be
+    /// evaluated or an `inbounds` address leaves its allocation.
+    fn element_pointer(&mut self, value: LLVMValueRef) -> Result<Pointer, Fault> {
+        let base = self.pointer_operand(value, 0)?;
+        let mut type_ref = unsafe { core::LLVMGetGEPSourceElementType(value) };
+        let mut offset: i64 = 0;
+
+        for index in 1..unsafe { core::LLVMGetNumOperands(value) } as u32 {
+            let (bits, raw_position) = self.int_operand(value, index)?;
+            let position = sign_extend(bits, raw_position);
+            if index > 1 {
+                match unsafe { core::LLVMGetTypeKind(type_ref) } {
+                    LLVMTypeKind::LLVMStructTypeKind => unsafe {
+                        offset = offset.wrapping_add(target::LLVMOffsetOfElement(self.target_data, type_ref, position as u32) as i64);
+                        type_ref = core::LLVMStructGetTypeAtIndex(type_ref, position as u32);
+                        continue;
+                    },
+                    LLVMTypeKind::LLVMArrayTypeKind | LLVMTypeKind::LLVMVectorTypeKind => {
+                        type_ref = unsafe { core::LLVMGetElementType(type_ref) };
+                    }
+                    _ => return Err(Fault::Error(format!("Cannot index into type {} in `{}`.", unsafe { print_type(type_ref) }, unsafe { print_value(value) }))),
+                }
+            }
+            offset = offset.wrapping_add(position.wrapping_mul(self.size_of(type_ref) as i64));
+        }
+
+        let result = base.offset_by(offset);
+        let in_bounds = unsafe { core::LLVMIsInBounds(value) } != 0;
+        if in_bounds && offset != 0 && !(self.memory.is_in_bounds(base) && self.memory.is_in_bounds(result)) {
+            let message = match self.memory.get_allocation(base) {
+                Some(allocation) => format!(
+                    "`getelementptr inbounds` moves offset {} of '{}' by {} bytes to offset {}, outside its {} bytes",
+                    base.get_offset(), allocation.get_name(), offset, result.get_offset(), allocation.get_size()
+                ),
+                None => format!("`getelementptr inbounds` moves a null pointer by {} bytes", offset),
+            };
+            return Err(Fault::Undefined(UndefinedBehaviorKind::OutOfBoundsPointer, message));
+        }
+        Ok(result)
+    }
+
+    /// Transfers control from the current block to another block of the same function, assigning
+    /// its `phi` nodes.
+    ///
+    /// # Parameters
+    /// * `target` - The block to enter.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(Fault)` if a `phi` node cannot be evaluated or the
+    /// block has no terminator.
+    fn jump(&mut self, target: LLVMBasicBlockRef) -> Result<(), Fault> {
+        let Some(source) = self.frames.last().map(|frame| frame.block) else {
+            return Err(Fault::Error("No function is being executed.".to_string()));
+        };
+
+        let mut assignments = Vec::new();
+        let mut instruction = unsafe { core::LLVMGetFirstInstruction(target) };
+        while !instruction.is_null() && !unsafe { core::LLVMIsAPHINode(instruction) }.is_null() {
+            let incoming = (0..unsafe { core::LLVMCountIncoming(instruction) })
+                .find(|index| unsafe { core::LLVMGetIncomingBlock(instruction, *index) } == source)
+                .ok_or_else(|| Fault::Error(format!("`{}` has no incoming value for block '{}'.", unsafe { print_value(instruction) }, unsafe { block_name(source) })))?;
+            assignments.push((instruction, self.evaluate(unsafe { core::LLVMGetIncomingValue(instruction, incoming) })?));
+            instruction = unsafe { core::LLVMGetNextInstruction(instruction) };
+        }
+        if instruction.is_null() {
+            return Err(Fault::Error(format!("Basic block '{}' has no terminator.", unsafe { block_name(target) })));
+        }
+
+        if let Some(frame) = self.frames.last_mut() {
+            frame.values.extend(assignments);
+            frame.block = target;
+            frame.instruction = instruction;
+        }
+        self.at_block_entry = true;
+        Ok(())
+    }
+
+    /// Pushes a frame for a call, positioned before the first instruction of the function.
+    ///
+    /// # Parameters
+    /// * `function` - The function with a body to call.
+    /// * `args` - The arguments, one per parameter.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(Fault)` if the entry block is empty.
+    fn enter(&mut self, function: LLVMValueRef, args: Vec<RuntimeValue>) -> Result<(), Fault> {
+        let entry = unsafe { core::LLVMGetEntryBasicBlock(function) };
+        let instruction = unsafe { core::LLVMGetFirstInstruction(entry) };
+        if instruction.is_null() {
+            return Err(Fault::Error(format!("Basic block '{}' has no terminator.", unsafe { block_name(entry) })));
+        }
+
+        let values = args.into_iter().enumerate()
+            .map(|(index, arg)| (unsafe { core::LLVMGetParam(function, index as u32) }, arg))
+            .collect();
+        self.frames.push(Frame { function, block: entry, instruction, values, allocas: Vec::new() });
+        self.at_block_entry = true;
+        Ok(())
+    }
+
+    /// Resolves the function called by a `call` instruction.
+    ///
+    /// # Parameters
+    /// * `instruction` - The `call` instruction.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMValueRef)` containing the callee, or `Err(Fault)` if an indirect call's
+    /// pointer does not point to a function.
+    fn callee(&mut self, instruction: LLVMValueRef) -> Result<LLVMValueRef, Fault> {
+        let called = unsafe { core::LLVMGetCalledValue(instruction) };
+        if !unsafe { core::LLVMIsAFunction(called) }.is_null() {
+            return Ok(called);
+        }
+        let pointer = self.evaluate(called)?.as_pointer()
+            .ok_or_else(|| Fault::Error(format!("The callee of `{}` is not a pointer.", unsafe { print_value(instruction) })))?;
+        self.functions.get(&pointer.get_allocation()).copied().filter(|_| pointer.get_offset() == 0).ok_or_else(|| {
+            Fault::Undefined(UndefinedBehaviorKind::InvalidCall, format!("call through `ptr {}`, which does not point to a function", self.describe_pointer(pointer)))
+        })
+    }
+
+    /// Evaluates an operand of an instruction or constant expression.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `index` - The index of the operand.
+    ///
+    /// # Returns
+    /// Returns `Ok(RuntimeValue)` containing the operand's value, or `Err(Fault)` if it cannot be
+    /// evaluated.
+    fn operand(&mut self, value: LLVMValueRef, index: u32) -> Result<RuntimeValue, Fault> {
+        self.evaluate(unsafe { core::LLVMGetOperand(value, index) })
+    }
+
+    /// Evaluates an integer operand.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `index` - The index of the operand.
+    ///
+    /// # Returns
+    /// Returns `Ok((u32, u64))` containing the bit width and zero-extended value, or `Err(Fault)` if
+    /// the operand is not an integer or cannot be evaluated.
+    fn int_operand(&mut self, value: LLVMValueRef, index: u32) -> Result<(u32, u64), Fault> {
+        match self.operand(value, index)? {
+            RuntimeValue::Int { bits, value } => Ok((bits, value)),
+            other => Err(Fault::Error(format!("Operand {} of `{}` is `{}`, not an integer.", index, unsafe { print_value(value) }, other))),
+        }
+    }
+
+    /// Evaluates a floating-point operand.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `index` - The index of the operand.
+    ///
+    /// # Returns
+    /// Returns `Ok(f64)` containing the value, or `Err(Fault)` if the operand is not a
+    /// floating-point number or cannot be evaluated.
+    fn float_operand(&mut self, value: LLVMValueRef, index: u32) -> Result<f64, Fault> {
+        let operand = self.operand(value, index)?;
+        operand.as_f64().ok_or_else(|| Fault::Error(format!("Operand {} of `{}` is `{}`, not a floating-point number.", index, unsafe { print_value(value) }, operand)))
+    }
+
+    /// Evaluates a pointer operand.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `index` - The index of the operand.
+    ///
+    /// # Returns
+    /// Returns `Ok(Pointer)` containing the pointer, or `Err(Fault)` if the operand is not a
+    /// pointer or cannot be evaluated.
+    fn pointer_operand(&mut self, value: LLVMValueRef, index: u32) -> Result<Pointer, Fault> {
+        let operand = self.operand(value, index)?;
+        operand.as_pointer().ok_or_else(|| Fault::Error(format!("Operand {} of `{}` is `{}`, not a pointer.", index, unsafe { print_value(value) }, operand)))
+    }
+
+    /// Evaluates an operand of an `icmp`, treating pointers as their 64-bit addresses.
+    ///
+    /// # Parameters
+    /// * `value` - The instruction or constant expression.
+    /// * `index` - The index of the operand.
+    ///
+    /// # Returns
+    /// Returns `Ok((u32, u64))` containing the bit width and zero-extended value, or `Err(Fault)` if
+    /// the operand is neither an integer nor a pointer or cannot be evaluated.
+    fn comparable_operand(&mut self, value: LLVMValueRef, index: u32) -> Result<(u32, u64), Fault> {
+        match self.operand(value, index)? {
+            RuntimeValue::Pointer(pointer) => Ok((64, pointer.to_address()?)),
+            _ => self.int_operand(value, index),
+        }
+    }
+
+    /// Evaluates a value in the current frame.
+    ///
+    /// # Parameters
+    /// * `value` - An argument, instruction, global or constant.
+    ///
+    /// # Returns
+    /// Returns `Ok(RuntimeValue)` containing the value, or `Err(Fault)` if it is `undef` or
+    /// `poison`, has not been computed yet, or is not supported.
+    fn evaluate(&mut self, value: LLVMValueRef) -> Result<RuntimeValue, Fault> {
+        if let Some(result) = self.frames.last().and_then(|frame| frame.values.get(&value)) {
+            return Ok(*result);
+        }
+        if let Some(pointer) = self.globals.get(&value) {
+            return Ok(RuntimeValue::Pointer(*pointer));
+        }
+
+        unsafe {
+            let type_ref = core::LLVMTypeOf(value);
+            if !core::LLVMIsAUndefValue(value).is_null() {
+                return Err(Fault::Undefined(UndefinedBehaviorKind::PoisonValue, format!("use of `{}`", print_value(value))));
+            }
+            if !core::LLVMIsAConstantInt(value).is_null() {
+                let bits = int_width(type_ref)?;
+                return Ok(RuntimeValue::Int { bits, value: truncate(bits, core::LLVMConstIntGetZExtValue(value)) });
+            }
+            if !core::LLVMIsAConstantFP(value).is_null() {
+                let mut loses_info = 0;
+                return float_value(type_ref, core::LLVMConstRealGetDouble(value, &mut loses_info));
+            }
+            if !core::LLVMIsAConstantPointerNull(value).is_null() {
+                return Ok(RuntimeValue::Pointer(Pointer::null()));
+            }
+            if !core::LLVMIsAConstantExpr(value).is_null() {
+                let opcode = core::LLVMGetConstOpcode(value);
+                return self.compute(value, opcode)?
+                    .ok_or_else(|| Fault::Error(format!("Unsupported constant `{}`.", print_value(value))));
+            }
+            if !core::LLVMIsAInstruction(value).is_null() || !core::LLVMIsAArgument(value).is_null() {
+                return Err(Fault::Error(format!("`{}` is used before it is computed.", print_value(value))));
+            }
+        }
+        Err(Fault::Error(format!("Unsupported operand `{}`.", unsafe { print_value(value) })))
+    }
+
+    /// Writes the initializer of a global variable to memory.
+    ///
+    /// # Parameters
+    /// * `pointer` - The address to write to.
+    /// * `constant` - The constant to write. Parts that are `undef` are left uninitialized.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(Fault)` if the constant is not supported.
+    fn initialize(&mut self, pointer: Pointer, constant: LLVMValueRef) -> Result<(), Fault> {
+        unsafe {
+            let type_ref = core::LLVMTypeOf(constant);
+            if !core::LLVMIsAUndefValue(constant).is_null() {
+                return Ok(());
+            }
+            if !core::LLVMIsAConstantAggregateZero(constant).is_null() {
+                let zeros = vec![0; self.size_of(type_ref)];
+                return Ok(self.memory.write(pointer, &zeros)?);
+            }
+
+            let element_offsets: Vec<i64> = match core::LLVMGetTypeKind(type_ref) {
+                LLVMTypeKind::LLVMArrayTypeKind | LLVMTypeKind::LLVMVectorTypeKind => {
+                    let stride = self.size_of(core::LLVMGetElementType(type_ref)) as i64;
+                    let length = match core::LLVMGetTypeKind(type_ref) {
+                        LLVMTypeKind::LLVMArrayTypeKind => core::LLVMGetArrayLength(type_ref),
+                        _ => core::LLVMGetVectorSize(type_ref),
+                    };
+                    (0..length as i64).map(|index| index * stride).collect()
+                }
+                LLVMTypeKind::LLVMStructTypeKind => (0..core::LLVMCountStructElementTypes(type_ref))
+                    .map(|index| target::LLVMOffsetOfElement(self.target_data, type_ref, index) as i64)
+                    .collect(),
+                _ => {
+                    let value = self.evaluate(constant)?;
+                    return Ok(self.memory.write(pointer, &value.to_bytes()?)?);
+                }
+            };
+
+            for (index, offset) in element_offsets.into_iter().enumerate() {
+                let element = core::LLVMGetAggregateElement(constant, index as u32);
+                if element.is_null() {
+                    return Err(Fault::Error(format!("Unsupported constant `{}`.", print_value(constant))));
+                }
+                self.initialize(pointer.offset_by(offset), element)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the number of bytes a value of a type occupies, including padding.
+    ///
+    /// # Parameters
+    /// * `type_ref` - The type.
+    ///
+    /// # Returns
+    /// The ABI size of the type under the module's data layout.
+    fn size_of(&self, type_ref: LLVMTypeRef) -> usize {
+        unsafe { target::LLVMABISizeOfType(self.target_data, type_ref) as usize }
+    }
+
+    /// Builds the location of a frame.
+    ///
+    /// # Parameters
+    /// * `frame` - The frame.
+    ///
+    /// # Returns
+    /// An `Option` containing the location, or `None` if the module was changed after the
+    /// interpreter was created.
+    fn location(&self, frame: &Frame) -> Option<Location> {
+        Some(Location {
+            function: *self.value_tags.get(&frame.function)?,
+            block: *self.block_tags.get(&frame.block)?,
+            instruction: *self.value_tags.get(&frame.instruction)?,
+        })
+    }
+
+    /// Renders a value for display, naming the allocation pointers point into.
+    ///
+    /// # Parameters
+    /// * `value` - The value.
+    ///
+    /// # Returns
+    /// The value as `i64 5`, `double 1.5` or `ptr slot+8`.
+    fn describe(&self, value: &RuntimeValue) -> String {
+        match value {
+            RuntimeValue::Pointer(pointer) => format!("ptr {}", self.describe_pointer(*pointer)),
+            other => other.to_string(),
+        }
+    }
+
+    /// Renders a pointer for display, naming the allocation it points into.
+    ///
+    /// # Parameters
+    /// * `pointer` - The pointer.
+    ///
+    /// # Returns
+    /// The pointer as `null`, `slot+8`, or its allocation index if it points into no allocation.
+    fn describe_pointer(&self, pointer: Pointer) -> String {
+        match self.memory.get_allocation(pointer) {
+            Some(allocation) => format!("{}{:+}", allocation.get_name(), pointer.get_offset()),
+            None => pointer.to_string(),
+        }
+    }
+
+    /// Retrieves the tag of a value, storing the value in the IRManager if it has none yet.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager to look the value up in.
+    /// * `value` - The value.
+    ///
+    /// # Returns
+    /// Returns `Ok(ValueTag)` on success, or `Err(String)` if the value could not be stored.
+    fn tag_value(&mut self, manager: &mut IRManager, value: LLVMValueRef) -> Result<ValueTag, String> {
+        let tag = manager.get_or_store_value(value).ok_or("Failed to store value.")?;
+        self.value_tags.insert(value, tag);
+        self.values.insert(tag, value);
+        Ok(tag)
+    }
+
+    /// Retrieves the tag of a basic block, storing the block in the IRManager if it has none yet.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager to look the block up in.
+    /// * `block` - The basic block.
+    ///
+    /// # Returns
+    /// Returns `Ok(BasicBlockTag)` on success, or `Err(String)` if the block could not be stored.
+    fn tag_block(&mut self, manager: &mut IRManager, block: LLVMBasicBlockRef) -> Result<BasicBlockTag, String> {
+        let tag = manager.get_or_store_basic_block(block).ok_or("Failed to store basic block.")?;
+        self.block_tags.insert(block, tag);
+        Ok(tag)
+    }
+}
+
+impl fmt::Display for StepInterpreter {
+    /// Renders the call stack, the current block with the next instruction marked by `=>` and the
+    /// values computed so far, and the contents of the current frame's stack slots, with `??` for
+    /// uninitialized bytes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(frame) = self.frames.last() else {
+            return writeln!(f, "not running");
+        };
+
+        for (depth, caller) in self.frames.iter().enumerate() {
+            writeln!(f, "#{} function '{}', block '{}'", depth, unsafe { value_name(caller.function) }, unsafe { block_name(caller.block) })?;
+        }
+
+        writeln!(f, "{}:", unsafe { block_name(frame.block) })?;
+        let mut instruction = unsafe { core::LLVMGetFirstInstruction(frame.block) };
+        while !instruction.is_null() {
+            let marker = if instruction == frame.instruction { "=>" } else { "  " };
+            match frame.values.get(&instruction) {
+                Some(value) => writeln!(f, "{} {} ; {}", marker, unsafe { print_value(instruction) }, self.describe(value))?,
+                None => writeln!(f, "{} {}", marker, unsafe { print_value(instruction) })?,
+            }
+            instruction = unsafe { core::LLVMGetNextInstruction(instruction) };
+        }
+
+        for slot in self.get_stack_slots() {
+            let bytes = self.memory.get_allocation(slot.pointer).map(|allocation| allocation.get_bytes()).unwrap_or_default();
+            let bytes: Vec<String> = bytes.iter().map(|byte| byte.map_or("??".to_string(), |byte| format!("{:02x}", byte))).collect();
+            writeln!(f, "  {} = [{}]", slot.name, bytes.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies an integer binary operator.
+///
+/// # Parameters
+/// * `opcode` - The operator.
+/// * `bits` - The bit width of the operands.
+/// * `left` - The left operand, zero-extended.
+/// * `right` - The right operand, zero-extended.
+///
+/// # Returns
+/// Returns `Ok(u64)` containing the result truncated to `bits`, or `Err(Fault)` if the operation
+/// has undefined behavior or yields poison.
+fn integer_operation(opcode: LLVMOpcode, bits: u32, left: u64, right: u64) -> Result<u64, Fault> {
+    let (signed_left, signed_right) = (sign_extend(bits, left), sign_extend(bits, right));
+    let result = match opcode {
+        LLVMOpcode::LLVMAdd => left.wrapping_add(right),
+        LLVMOpcode::LLVMSub => left.wrapping_sub(right),
+        LLVMOpcode::LLVMMul => left.wrapping_mul(right),
+        LLVMOpcode::LLVMUDiv | LLVMOpcode::LLVMSDiv | LLVMOpcode::LLVMURem | LLVMOpcode::LLVMSRem if right == 0 => {
+            return Err(Fault::Undefined(UndefinedBehaviorKind::DivisionByZero, format!("division of {} by zero", signed_left)));
+        }
+        LLVMOpcode::LLVMSDiv | LLVMOpcode::LLVMSRem if signed_right == -1 && signed_left == i64::MIN >> (64 - bits) => {
+            return Err(Fault::Undefined(UndefinedBehaviorKind::DivisionOverflow, format!("division of {} by -1 overflows i{}", signed_left, bits)));
+        }
+        LLVMOpcode::LLVMUDiv => left / right,
+        LLVMOpcode::LLVMSDiv => signed_left.wrapping_div(signed_right) as u64,
+        LLVMOpcode::LLVMURem => left % right,
+        LLVMOpcode::LLVMSRem => signed_left.wrapping_rem(signed_right) as u64,
+        LLVMOpcode::LLVMShl | LLVMOpcode::LLVMLShr | LLVMOpcode::LLVMAShr if right >= bits as u64 => {
+            return Err(Fault::Undefined(UndefinedBehaviorKind::PoisonValue, format!("shift by {} is not less than the bit width {}", right, bits)));
+        }
+        LLVMOpcode::LLVMShl => left << right,
+        LLVMOpcode::LLVMLShr => left >> right,
+        LLVMOpcode::LLVMAShr => (signed_left >> right) as u64,
+        LLVMOpcode::LLVMAnd => left & right,
+        LLVMOpcode::LLVMOr => left | right,
+        LLVMOpcode::LLVMXor => left ^ right,
+        _ => return Err(Fault::Error(format!("Unsupported integer operation {:?}.", opcode))),
+    };
+    Ok(truncate(bits, result))
+}
+
+/// Retrieves the bit width of an integer type.
+///
+/// # Parameters
+/// * `type_ref` - The type.
+///
+/// # Returns
+/// Returns `Ok(u32)` containing the bit width, or `Err(Fault)` if the type is not an integer type
+/// of at most 64 bits.
+fn int_width(type_ref: LLVMTypeRef) -> Result<u32, Fault> {
+    unsafe {
+        match core::LLVMGetTypeKind(type_ref) {
+            LLVMTypeKind::LLVMIntegerTypeKind if core::LLVMGetIntTypeWidth(type_ref) <= 64 => Ok(core::LLVMGetIntTypeWidth(type_ref)),
+            _ => Err(Fault::Error(format!("Unsupported type {}; only integers of up to 64 bits are supported.", print_type(type_ref)))),
+        }
+    }
+}
+
+/// Creates a floating-point value of a type.
+///
+/// # Parameters
+/// * `type_ref` - The type, `float` or `double`.
+/// * `value` - The value, rounded to single precision for `float`.
+///
+/// # Returns
+/// Returns `Ok(RuntimeValue)` containing the value, or `Err(Fault)` if the type is not supported.
+fn float_value(type_ref: LLVMTypeRef, value: f64) -> Result<RuntimeValue, Fault> {
+    match unsafe { core::LLVMGetTypeKind(type_ref) } {
+        LLVMTypeKind::LLVMFloatTypeKind => Ok(RuntimeValue::Float(value as f32)),
+        LLVMTypeKind::LLVMDoubleTypeKind => Ok(RuntimeValue::Double(value)),
+        _ => Err(Fault::Error(format!("Unsupported type {}; only float and double are supported.", unsafe { print_type(type_ref) }))),
+    }
+}
+
+/// Decodes a value of a type from its bytes in memory.
+///
+/// # Parameters
+/// * `type_ref` - The type.
+/// * `bytes` - The little-endian bytes, at least the store size of the type.
+///
+/// # Returns
+/// Returns `Ok(RuntimeValue)` containing the value, or `Err(Fault)` if the type is not supported.
+fn from_bytes(type_ref: LLVMTypeRef, bytes: &[u8]) -> Result<RuntimeValue, Fault> {
+    let mut buffer = [0u8; 8];
+    let length = bytes.len().min(8);
+    buffer[..length].copy_from_slice(&bytes[..length]);
+    let raw = u64::from_le_bytes(buffer);
+
+    match unsafe { core::LLVMGetTypeKind(type_ref) } {
+        LLVMTypeKind::LLVMIntegerTypeKind => {
+            let bits = int_width(type_ref)?;
+            Ok(RuntimeValue::Int { bits, value: truncate(bits, raw) })
+        }
+        LLVMTypeKind::LLVMFloatTypeKind => Ok(RuntimeValue::Float(f32::from_bits(raw as u32))),
+        LLVMTypeKind::LLVMDoubleTypeKind => Ok(RuntimeValue::Double(f64::from_bits(raw))),
+        LLVMTypeKind::LLVMPointerTypeKind => Ok(RuntimeValue::Pointer(Pointer::from_address(raw))),
+        _ => Err(Fault::Error(format!("Unsupported type {}; only integers, floats and pointers can be loaded and stored.", unsafe { print_type(type_ref) }))),
+    }
+}
+
+/// Determines whether a value can be passed for a parameter of a type.
+///
+/// # Parameters
+/// * `value` - The value.
+/// * `type_ref` - The parameter type.
+///
+/// # Returns
+/// `true` if the value has the kind and bit width of the type.
+fn has_type(value: &RuntimeValue, type_ref: LLVMTypeRef) -> bool {
+    unsafe {
+        match (value, core::LLVMGetTypeKind(type_ref)) {
+            (RuntimeValue::Int { bits, .. }, LLVMTypeKind::LLVMIntegerTypeKind) => *bits == core::LLVMGetIntTypeWidth(type_ref),
+            (RuntimeValue::Float(_), LLVMTypeKind::LLVMFloatTypeKind) => true,
+            (RuntimeValue::Double(_), LLVMTypeKind::LLVMDoubleTypeKind) => true,
+            (RuntimeValue::Pointer(_), LLVMTypeKind::LLVMPointerTypeKind) => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! IR Interpreter.
+
+/// Step-by-step execution of functions with breakpoints, inspection of SSA values and stack
+/// slots, and detection of undefined behavior.
+pub mod core;
+
+/// The memory model of interpreted programs, with bounds, lifetimes and initialization tracked
+/// per allocation.
+pub mod memory;
+
+/// Values computed by interpreted instructions.
+pub mod value;
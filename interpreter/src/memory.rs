@@ -0,0 +1,381 @@
+//! This module provides the memory model of the interpreter.
+//!
+//! Memory is a list of separate allocations, one per `alloca`, global variable and function, each
+//! with its own bytes and a flag per byte recording whether it has been written. Pointers name
+//! the allocation they point into and an offset within it, so every access can be checked
+//! against the bounds, lifetime and initialization of the memory it touches.
+
+use std::fmt;
+
+/// The largest allocation the interpreter creates, in bytes.
+///
+/// Addresses only have 32 bits for the offset, and every byte is backed by host memory, so larger
+/// `alloca`s and globals are rejected instead of exhausting the host.
+pub const MAX_ALLOCATION_SIZE: usize = 1 << 28;
+
+/// A pointer into the interpreter's memory.
+///
+/// A pointer converts to and from a 64-bit address, with the allocation in the upper 32 bits and
+/// the offset in the lower 32 bits, so that `ptrtoint` and `inttoptr` round-trip. The null pointer
+/// has address 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pointer {
+    allocation: usize,
+    offset: i64,
+}
+
+impl Pointer {
+    /// Constructs a pointer into an allocation.
+    ///
+    /// # Parameters
+    /// * `allocation` - The index of the allocation, 0 for the null pointer.
+    /// * `offset` - The offset in bytes from the start of the allocation.
+    ///
+    /// # Returns
+    /// A new `Pointer`.
+    pub fn new(allocation: usize, offset: i64) -> Self {
+        Self { allocation, offset }
+    }
+
+    /// Constructs the null pointer.
+    ///
+    /// # Returns
+    /// A `Pointer` into no allocation.
+    pub fn null() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Reconstructs a pointer from its address.
+    ///
+    /// # Parameters
+    /// * `address` - An address returned by `to_address`.
+    ///
+    /// # Returns
+    /// The `Pointer` with that address.
+    pub fn from_address(address: u64) -> Self {
+        Self::new((address >> 32) as usize, address as u32 as i32 as i64)
+    }
+
+    /// Converts the pointer to its address.
+    ///
+    /// Addresses hold the allocation in the upper 32 bits and the offset in the lower 32 bits, so
+    /// pointers whose offset does not fit in an `i32` have no address.
+    ///
+    /// # Returns
+    /// Returns `Ok(u64)` containing the 64-bit address of the pointer, or
+    /// `Err(MemoryError::AddressOverflow)` if the offset cannot be represented.
+    pub fn to_address(&self) -> Result<u64, MemoryError> {
+        let offset = i32::try_from(self.offset).map_err(|_| MemoryError::AddressOverflow { offset: self.offset })?;
+        Ok(((self.allocation as u64) << 32) | (offset as u32 as u64))
+    }
+
+    /// Retrieves the allocation the pointer points into.
+    ///
+    /// # Returns
+    /// The index of the allocation, 0 for the null pointer and pointers derived from it.
+    pub fn get_allocation(&self) -> usize {
+        self.allocation
+    }
+
+    /// Retrieves the offset of the pointer within its allocation.
+    ///
+    /// # Returns
+    /// The offset in bytes, which may be negative or past the end for out-of-bounds pointers.
+    pub fn get_offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Determines whether this is the null pointer.
+    ///
+    /// # Returns
+    /// `true` if the pointer has address 0.
+    pub fn is_null(&self) -> bool {
+        self.allocation == 0 && self.offset == 0
+    }
+
+    /// Moves the pointer within its allocation.
+    ///
+    /// # Parameters
+    /// * `bytes` - The number of bytes to move by, negative to move backwards.
+    ///
+    /// # Returns
+    /// A new `Pointer` into the same allocation.
+    pub fn offset_by(&self, bytes: i64) -> Self {
+        Self::new(self.allocation, self.offset.wrapping_add(bytes))
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_null() {
+            write!(f, "null")
+        } else {
+            write!(f, "<allocation {}>{:+}", self.allocation, self.offset)
+        }
+    }
+}
+
+/// What an allocation holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationKind {
+    /// A stack slot created by an `alloca`, released when its function returns.
+    Stack,
+    /// A global variable, alive for the whole run.
+    Global,
+    /// A function, which has an address but no bytes.
+    Function,
+}
+
+/// A block of memory with its own bounds and lifetime.
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    name: String,
+    kind: AllocationKind,
+    bytes: Vec<u8>,
+    initialized: Vec<bool>,
+    live: bool,
+}
+
+impl Allocation {
+    /// Retrieves the name of the value the allocation was created for.
+    ///
+    /// # Returns
+    /// The name of the `alloca`, global or function.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves what the allocation holds.
+    ///
+    /// # Returns
+    /// The `AllocationKind` of the allocation.
+    pub fn get_kind(&self) -> AllocationKind {
+        self.kind
+    }
+
+    /// Retrieves the size of the allocation.
+    ///
+    /// # Returns
+    /// The size in bytes.
+    pub fn get_size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Determines whether the allocation may still be accessed.
+    ///
+    /// # Returns
+    /// `false` once the function owning a stack allocation has returned.
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Retrieves the contents of the allocation.
+    ///
+    /// # Returns
+    /// One entry per byte, `None` for bytes that have never been written.
+    pub fn get_bytes(&self) -> Vec<Option<u8>> {
+        self.bytes.iter().zip(&self.initialized).map(|(byte, initialized)| initialized.then_some(*byte)).collect()
+    }
+}
+
+/// An invalid memory access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryError {
+    /// The pointer is null or does not point into any allocation.
+    NullPointer,
+    /// The allocation is a stack slot of a function that has returned.
+    DeadAllocation {
+        /// The name of the allocation.
+        name: String,
+    },
+    /// Some of the accessed bytes lie outside the allocation.
+    OutOfBounds {
+        /// The name of the allocation.
+        name: String,
+        /// The offset of the first accessed byte.
+        offset: i64,
+        /// The number of accessed bytes.
+        size: usize,
+        /// The size of the allocation.
+        allocation_size: usize,
+    },
+    /// The pointer is too far outside its allocation to be converted to an address.
+    AddressOverflow {
+        /// The offset of the pointer.
+        offset: i64,
+    },
+    /// Some of the read bytes have never been written.
+    Uninitialized {
+        /// The name of the allocation.
+        name: String,
+        /// The offset of the first uninitialized byte.
+        offset: i64,
+    },
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryError::NullPointer => write!(f, "access through a null pointer"),
+            MemoryError::DeadAllocation { name } => write!(f, "access to '{}' after its function returned", name),
+            MemoryError::OutOfBounds { name, offset, size, allocation_size } => write!(
+                f,
+                "access of {} bytes at offset {} is outside '{}', which has {} bytes",
+                size, offset, name, allocation_size
+            ),
+            MemoryError::AddressOverflow { offset } => write!(f, "pointer offset {} does not fit in an address", offset),
+            MemoryError::Uninitialized { name, offset } => write!(f, "read of uninitialized byte {} of '{}'", offset, name),
+        }
+    }
+}
+
+/// The memory of an interpreted program.
+#[derive(Debug, Clone)]
+pub struct Memory {
+    allocations: Vec<Allocation>,
+}
+
+impl Memory {
+    /// Constructs an empty memory.
+    ///
+    /// # Returns
+    /// A new `Memory` with no allocations.
+    pub fn new() -> Self {
+        let null = Allocation {
+            name: "null".to_string(),
+            kind: AllocationKind::Global,
+            bytes: Vec::new(),
+            initialized: Vec::new(),
+            live: false,
+        };
+        Self { allocations: vec![null] }
+    }
+
+    /// Creates an allocation with uninitialized contents.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the value the allocation is created for.
+    /// * `kind` - What the allocation holds.
+    /// * `size` - The size in bytes.
+    ///
+    /// # Returns
+    /// A `Pointer` to the start of the new allocation.
+    pub fn allocate(&mut self, name: &str, kind: AllocationKind, size: usize) -> Pointer {
+        self.allocations.push(Allocation {
+            name: name.to_string(),
+            kind,
+            bytes: vec![0; size],
+            initialized: vec![false; size],
+            live: true,
+        });
+        Pointer::new(self.allocations.len() - 1, 0)
+    }
+
+    /// Releases a stack allocation, so that later accesses through pointers to it are reported.
+    ///
+    /// # Parameters
+    /// * `pointer` - A pointer into the allocation.
+    pub fn release(&mut self, pointer: Pointer) {
+        if let Some(allocation) = self.allocations.get_mut(pointer.allocation).filter(|_| pointer.allocation != 0) {
+            allocation.live = false;
+        }
+    }
+
+    /// Retrieves the allocation a pointer points into.
+    ///
+    /// # Parameters
+    /// * `pointer` - The pointer.
+    ///
+    /// # Returns
+    /// An `Option` containing the allocation, or `None` for null pointers and pointers that were
+    /// never returned by `allocate`.
+    pub fn get_allocation(&self, pointer: Pointer) -> Option<&Allocation> {
+        match pointer.allocation {
+            0 => None,
+            index => self.allocations.get(index),
+        }
+    }
+
+    /// Determines whether a pointer points into a live allocation or just past its end, the
+    /// positions an `inbounds` address computation may produce.
+    ///
+    /// # Parameters
+    /// * `pointer` - The pointer.
+    ///
+    /// # Returns
+    /// `true` if the pointer is within the bounds of its allocation.
+    pub fn is_in_bounds(&self, pointer: Pointer) -> bool {
+        self.get_allocation(pointer)
+            .is_some_and(|allocation| allocation.live && pointer.offset >= 0 && pointer.offset as usize <= allocation.get_size())
+    }
+
+    /// Reads bytes that must all have been written.
+    ///
+    /// # Parameters
+    /// * `pointer` - A pointer to the first byte.
+    /// * `size` - The number of bytes to read.
+    ///
+    /// # Returns
+    /// Returns `Ok(Vec<u8>)` containing the bytes, or `Err(MemoryError)` if the access is invalid or
+    /// reads uninitialized memory.
+    pub fn read(&self, pointer: Pointer, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let allocation = self.check_access(pointer, size)?;
+        let range = pointer.offset as usize..pointer.offset as usize + size;
+        if let Some(uninitialized) = allocation.initialized[range.clone()].iter().position(|initialized| !initialized) {
+            return Err(MemoryError::Uninitialized {
+                name: allocation.name.clone(),
+                offset: pointer.offset + uninitialized as i64,
+            });
+        }
+        Ok(allocation.bytes[range].to_vec())
+    }
+
+    /// Writes bytes and marks them initialized.
+    ///
+    /// # Parameters
+    /// * `pointer` - A pointer to the first byte.
+    /// * `bytes` - The bytes to write.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(MemoryError)` if the access is invalid.
+    pub fn write(&mut self, pointer: Pointer, bytes: &[u8]) -> Result<(), MemoryError> {
+        self.check_access(pointer, bytes.len())?;
+        let allocation = &mut self.allocations[pointer.allocation];
+        let range = pointer.offset as usize..pointer.offset as usize + bytes.len();
+        allocation.bytes[range.clone()].copy_from_slice(bytes);
+        allocation.initialized[range].fill(true);
+        Ok(())
+    }
+
+    /// Checks that an access stays within a live allocation.
+    ///
+    /// # Parameters
+    /// * `pointer` - A pointer to the first accessed byte.
+    /// * `size` - The number of accessed bytes.
+    ///
+    /// # Returns
+    /// Returns `Ok(&Allocation)` containing the accessed allocation, or `Err(MemoryError)` if the
+    /// access is invalid.
+    fn check_access(&self, pointer: Pointer, size: usize) -> Result<&Allocation, MemoryError> {
+        let allocation = self.get_allocation(pointer).ok_or(MemoryError::NullPointer)?;
+        if !allocation.live {
+            return Err(MemoryError::DeadAllocation { name: allocation.name.clone() });
+        }
+        let end = usize::try_from(pointer.offset).ok().and_then(|offset| offset.checked_add(size));
+        if end.is_none_or(|end| end > allocation.get_size()) {
+            return Err(MemoryError::OutOfBounds {
+                name: allocation.name.clone(),
+                offset: pointer.offset,
+                size,
+                allocation_size: allocation.get_size(),
+            });
+        }
+        Ok(allocation)
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,159 @@
+//! This module provides the values computed by the interpreter.
+//!
+//! SSA values are held as `RuntimeValue`s rather than LLVM constants, so they can be inspected and
+//! passed in from Rust without creating IR. Integers up to 64 bits wide are supported.
+
+use std::fmt;
+use crate::memory::{MemoryError, Pointer};
+
+/// A value computed by an interpreted instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuntimeValue {
+    /// An integer of the given bit width, stored zero-extended.
+    Int {
+        /// The bit width of the integer, between 1 and 64.
+        bits: u32,
+        /// The value, with the bits above `bits` cleared.
+        value: u64,
+    },
+    /// A single-precision floating-point number.
+    Float(f32),
+    /// A double-precision floating-point number.
+    Double(f64),
+    /// A pointer into the interpreter's memory.
+    Pointer(Pointer),
+}
+
+impl RuntimeValue {
+    /// Creates an integer value.
+    ///
+    /// # Parameters
+    /// * `bits` - The bit width of the integer.
+    /// * `value` - The value, truncated to `bits` bits.
+    ///
+    /// # Returns
+    /// A new `RuntimeValue::Int`.
+    pub fn from_int(bits: u32, value: i64) -> Self {
+        RuntimeValue::Int { bits, value: truncate(bits, value as u64) }
+    }
+
+    /// Creates an `i1` value.
+    ///
+    /// # Parameters
+    /// * `value` - The boolean.
+    ///
+    /// # Returns
+    /// A new `RuntimeValue::Int` of width 1.
+    pub fn from_bool(value: bool) -> Self {
+        RuntimeValue::Int { bits: 1, value: value as u64 }
+    }
+
+    /// Reads the value as an unsigned integer.
+    ///
+    /// # Returns
+    /// An `Option` containing the zero-extended integer, or `None` if this is not an integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            RuntimeValue::Int { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Reads the value as a signed integer.
+    ///
+    /// # Returns
+    /// An `Option` containing the sign-extended integer, or `None` if this is not an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            RuntimeValue::Int { bits, value } => Some(sign_extend(*bits, *value)),
+            _ => None,
+        }
+    }
+
+    /// Reads the value as a boolean.
+    ///
+    /// # Returns
+    /// An `Option` containing `true` for non-zero integers, or `None` if this is not an integer.
+    pub fn as_bool(&self) -> Option<bool> {
+        self.as_u64().map(|value| value != 0)
+    }
+
+    /// Reads the value as a floating-point number.
+    ///
+    /// # Returns
+    /// An `Option` containing the value widened to `f64`, or `None` if this is not a
+    /// floating-point number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            RuntimeValue::Float(value) => Some(*value as f64),
+            RuntimeValue::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Reads the value as a pointer.
+    ///
+    /// # Returns
+    /// An `Option` containing the pointer, or `None` if this is not a pointer.
+    pub fn as_pointer(&self) -> Option<Pointer> {
+        match self {
+            RuntimeValue::Pointer(pointer) => Some(*pointer),
+            _ => None,
+        }
+    }
+
+    /// Encodes the value as it is laid out in memory, in little-endian byte order.
+    ///
+    /// # Returns
+    /// Returns `Ok(Vec<u8>)` containing the bytes of the value: as many as needed for the bit width
+    /// of integers, 4 for floats and 8 for doubles and pointers. Returns `Err(MemoryError)` if the
+    /// value is a pointer without an address.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MemoryError> {
+        Ok(match self {
+            RuntimeValue::Int { bits, value } => value.to_le_bytes()[..bits.div_ceil(8) as usize].to_vec(),
+            RuntimeValue::Float(value) => value.to_le_bytes().to_vec(),
+            RuntimeValue::Double(value) => value.to_le_bytes().to_vec(),
+            RuntimeValue::Pointer(pointer) => pointer.to_address()?.to_le_bytes().to_vec(),
+        })
+    }
+}
+
+impl fmt::Display for RuntimeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeValue::Int { bits: 1, value } => write!(f, "i1 {}", *value != 0),
+            RuntimeValue::Int { bits, .. } => write!(f, "i{} {}", bits, self.as_i64().unwrap_or_default()),
+            RuntimeValue::Float(value) => write!(f, "float {}", value),
+            RuntimeValue::Double(value) => write!(f, "double {}", value),
+            RuntimeValue::Pointer(pointer) => write!(f, "ptr {}", pointer),
+        }
+    }
+}
+
+/// Clears the bits of an integer above its bit width.
+///
+/// # Parameters
+/// * `bits` - The bit width.
+/// * `value` - The integer.
+///
+/// # Returns
+/// The low `bits` bits of `value`.
+pub(crate) fn truncate(bits: u32, value: u64) -> u64 {
+    if bits >= 64 { value } else { value & ((1u64 << bits) - 1) }
+}
+
+/// Sign-extends an integer from its bit width to 64 bits.
+///
+/// # Parameters
+/// * `bits` - The bit width.
+/// * `value` - The integer, zero-extended.
+///
+/// # Returns
+/// The integer as a signed 64-bit value.
+pub(crate) fn sign_extend(bits: u32, value: u64) -> i64 {
+    if bits == 0 || bits >= 64 {
+        value as i64
+    } else {
+        ((value << (64 - bits)) as i64) >> (64 - bits)
+    }
+}
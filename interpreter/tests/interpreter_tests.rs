@@ -0,0 +1,279 @@
+use ir::core::{BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag};
+use interpreter::{core::{StepInterpreter, StopReason, UndefinedBehaviorKind}, value::RuntimeValue};
+
+/// Creates a module with `i64 name(i64)`.
+fn setup(pools: &mut IRManager, name: &str) -> (ContextTag, ModuleTag, TypeTag, ValueTag, BuilderTag) {
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module("interpreter", context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, name, function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    (context_tag, module_tag, int_type, function_tag, builder_tag)
+}
+
+/// Builds `%name = getelementptr [inbounds] [4 x i64], ptr %base, i64 0, i64 index` at the
+/// builder's position.
+fn build_element_pointer(pools: &mut IRManager, context_tag: ContextTag, builder_tag: BuilderTag, array_type: TypeTag, base: ValueTag, index: i64, in_bounds: bool) -> ValueTag {
+    let zero = pools.create_integer(context_tag, 0).expect("Failed to create integer");
+    let index = pools.create_integer(context_tag, index).expect("Failed to create integer");
+    pools.build_gep(builder_tag, array_type, base, &[zero, index], in_bounds, "element").expect("Failed to build getelementptr")
+}
+
+#[test]
+fn test_stepping_and_inspection() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "add_one");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one = pools.create_integer(context_tag, 1).expect("Failed to create integer");
+    let slot = pools.init_var(builder_tag, "slot", int_type, Some(n)).expect("Failed to initialize variable");
+    let value = pools.get_var(builder_tag, int_type, slot, "value").expect("Failed to load variable");
+    let sum = pools.build_add(builder_tag, value, one, "sum").expect("Failed to build add");
+    pools.nonvoid_return(builder_tag, sum).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    assert!(interpreter.step().is_err(), "Nothing is executing before `start`");
+    assert!(interpreter.start(function_tag, &[]).is_err(), "The argument count is checked");
+    assert!(interpreter.start(function_tag, &[RuntimeValue::from_int(32, 41)]).is_err(), "The argument type is checked");
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 41)]).expect("Failed to start function");
+
+    let location = interpreter.get_location().expect("The function should be executing");
+    assert_eq!(location.get_function(), function_tag);
+    assert_eq!(location.get_block(), entry);
+    assert_eq!(location.get_instruction(), slot);
+    assert_eq!(interpreter.get_value(n), Some(RuntimeValue::from_int(64, 41)));
+
+    assert_eq!(interpreter.step(), Ok(StopReason::Stepped));
+    let slots = interpreter.get_stack_slots();
+    assert_eq!(slots.len(), 1);
+    assert_eq!((slots[0].get_alloca(), slots[0].get_name(), slots[0].get_size()), (slot, "slot", 8));
+    let allocation = interpreter.get_memory().get_allocation(slots[0].get_pointer()).expect("The slot should be allocated");
+    assert_eq!(allocation.get_bytes(), vec![None; 8], "A new slot is uninitialized");
+
+    assert_eq!(interpreter.step(), Ok(StopReason::Stepped));
+    let contents = interpreter.get_memory().read(slots[0].get_pointer(), 8).expect("The slot should be initialized");
+    assert_eq!(contents, 41u64.to_le_bytes().to_vec());
+
+    assert_eq!(interpreter.step(), Ok(StopReason::Stepped));
+    assert_eq!(interpreter.get_value(value), Some(RuntimeValue::from_int(64, 41)));
+    assert_eq!(interpreter.get_value(sum), None, "`sum` has not been computed yet");
+    let state = interpreter.to_string();
+    assert!(state.contains("#0 function 'add_one', block 'entry'\n"), "Unexpected state: {}", state);
+    assert!(state.contains("=> %sum = add i64 %value, 1\n"), "The next instruction should be marked: {}", state);
+    assert!(state.contains("  slot = [29 00 00 00 00 00 00 00]\n"), "The slot contents should be shown: {}", state);
+
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 42)))));
+    assert!(!interpreter.is_running());
+    assert_eq!(interpreter.get_step_count(), 5);
+    assert!(interpreter.get_memory().get_allocation(slots[0].get_pointer()).is_some_and(|allocation| !allocation.is_live()), "Returning releases the slot");
+}
+
+#[test]
+fn test_breakpoints_and_calls() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "abs");
+    let double_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let double = pools.add_function_to_module(module_tag, "double", double_type).expect("Failed to add function");
+
+    let double_entry = pools.create_basic_block(context_tag, double, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, double_entry).expect("Failed to position builder");
+    let x = pools.get_param(double, 0).expect("Failed to get parameter");
+    let doubled = pools.build_add(builder_tag, x, x, "doubled").expect("Failed to build add");
+    pools.nonvoid_return(builder_tag, doubled).expect("Failed to build return");
+
+    let [entry, negative, positive] = ["entry", "negative", "positive"]
+        .map(|name| pools.create_basic_block(context_tag, function_tag, name).expect("Failed to create block"));
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let zero = pools.create_integer(context_tag, 0).expect("Failed to create integer");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let is_negative = pools.build_icmp_lt(builder_tag, n, zero, "is_negative").expect("Failed to build icmp");
+    pools.create_cond_br(builder_tag, is_negative, negative, positive).expect("Failed to build branch");
+    pools.position_builder_at_end(builder_tag, negative).expect("Failed to position builder");
+    let negated = pools.build_sub(builder_tag, zero, n, "negated").expect("Failed to build sub");
+    pools.nonvoid_return(builder_tag, negated).expect("Failed to build return");
+    pools.position_builder_at_end(builder_tag, positive).expect("Failed to position builder");
+    let call = pools.build_call(builder_tag, double_type, double, &[n], "call").expect("Failed to build call");
+    pools.nonvoid_return(builder_tag, call).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    interpreter.add_breakpoint(negative).expect("Failed to add breakpoint");
+    interpreter.add_breakpoint(double_entry).expect("Failed to add breakpoint");
+    assert!(interpreter.remove_breakpoint(negative));
+    assert!(!interpreter.remove_breakpoint(negative));
+    interpreter.add_breakpoint(negative).expect("Failed to add breakpoint");
+    assert_eq!(interpreter.get_breakpoints().len(), 2);
+
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, -5)]).expect("Failed to start function");
+    assert_eq!(interpreter.run(), Ok(StopReason::Breakpoint(negative)));
+    assert_eq!(interpreter.get_value(is_negative), Some(RuntimeValue::from_bool(true)));
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 5)))));
+
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 7)]).expect("Failed to start function");
+    assert_eq!(interpreter.run(), Ok(StopReason::Breakpoint(double_entry)));
+    let call_stack = interpreter.get_call_stack();
+    assert_eq!(call_stack.len(), 2);
+    assert_eq!((call_stack[0].get_block(), call_stack[0].get_instruction()), (positive, call));
+    assert_eq!(call_stack[1].get_function(), double);
+    assert_eq!(interpreter.get_value(x), Some(RuntimeValue::from_int(64, 7)));
+    assert_eq!(interpreter.get_value(n), None, "Values of callers are not visible in the callee's frame");
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 14)))));
+}
+
+#[test]
+fn test_globals() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "read_counter");
+    let seven = pools.create_integer(context_tag, 7).expect("Failed to create integer");
+    let counter = pools.init_global_var(module_tag, "counter", int_type, Some(seven)).expect("Failed to add global");
+
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let value = pools.get_var(builder_tag, int_type, counter, "value").expect("Failed to load global");
+    let sum = pools.build_add(builder_tag, value, n, "sum").expect("Failed to build add");
+    pools.reassign_var(builder_tag, counter, sum).expect("Failed to store global");
+    pools.nonvoid_return(builder_tag, sum).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    let address = interpreter.get_global(counter).expect("The global should have an address");
+    assert_eq!(interpreter.get_memory().read(address, 8), Ok(7u64.to_le_bytes().to_vec()));
+
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 3)]).expect("Failed to start function");
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 10)))));
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 3)]).expect("Failed to start function");
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 13)))), "Globals keep their values between runs");
+}
+
+#[test]
+fn test_uninitialized_load() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "forgot_store");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let slot = pools.init_var(builder_tag, "slot", int_type, None).expect("Failed to initialize variable");
+    let value = pools.get_var(builder_tag, int_type, slot, "value").expect("Failed to load variable");
+    pools.nonvoid_return(builder_tag, value).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 0)]).expect("Failed to start function");
+    let StopReason::UndefinedBehavior(undefined_behavior) = interpreter.run().expect("Failed to run function") else {
+        panic!("The load should be reported");
+    };
+    assert_eq!(undefined_behavior.get_kind(), UndefinedBehaviorKind::UninitializedLoad);
+    let text = undefined_behavior.to_string();
+    assert!(text.starts_with("[uninitialized-load] in function 'forgot_store', block 'entry' at `%value = load i64, ptr %slot"), "Unexpected text: {}", text);
+    assert!(text.ends_with("`: read of uninitialized byte 0 of 'slot'"), "Unexpected text: {}", text);
+    assert_eq!(interpreter.get_undefined_behavior(), Some(&undefined_behavior));
+    assert_eq!(interpreter.get_location().map(|location| location.get_instruction()), Some(value), "The interpreter stays at the faulting instruction");
+    assert!(interpreter.is_running());
+    assert!(interpreter.step().is_err(), "Execution cannot continue after undefined behavior");
+}
+
+#[test]
+fn test_out_of_bounds_pointers() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "overflow");
+    let array_type = pools.array_type(int_type, 4).expect("Failed to create array type");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let array = pools.init_var(builder_tag, "array", array_type, None).expect("Failed to initialize variable");
+    let past_end = build_element_pointer(&mut pools, context_tag, builder_tag, array_type, array, 4, true);
+    let wild = build_element_pointer(&mut pools, context_tag, builder_tag, array_type, array, 5, false);
+    build_element_pointer(&mut pools, context_tag, builder_tag, array_type, array, 5, true);
+    pools.nonvoid_return(builder_tag, n).expect("Failed to build return");
+
+    let function_type = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let store_function = pools.add_function_to_module(module_tag, "store_past_end", function_type).expect("Failed to add function");
+    let store_entry = pools.create_basic_block(context_tag, store_function, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, store_entry).expect("Failed to position builder");
+    let m = pools.get_param(store_function, 0).expect("Failed to get parameter");
+    let store_array = pools.init_var(builder_tag, "array", array_type, None).expect("Failed to initialize variable");
+    let store_wild = build_element_pointer(&mut pools, context_tag, builder_tag, array_type, store_array, 5, false);
+    pools.reassign_var(builder_tag, store_wild, m).expect("Failed to build store");
+    pools.nonvoid_return(builder_tag, m).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 1)]).expect("Failed to start function");
+    let StopReason::UndefinedBehavior(element) = interpreter.run().expect("Failed to run function") else {
+        panic!("The out-of-bounds `getelementptr inbounds` should be reported");
+    };
+    assert_eq!(element.get_kind(), UndefinedBehaviorKind::OutOfBoundsPointer);
+    assert_eq!(element.get_message(), "`getelementptr inbounds` moves offset 0 of 'array' by 40 bytes to offset 40, outside its 32 bytes");
+
+    let array_pointer = interpreter.get_value(array).and_then(|array| array.as_pointer()).expect("The array should be allocated");
+    assert_eq!(interpreter.get_value(past_end), Some(RuntimeValue::Pointer(array_pointer.offset_by(32))), "Pointing one past the end is allowed");
+    assert_eq!(interpreter.get_value(wild), Some(RuntimeValue::Pointer(array_pointer.offset_by(40))), "Without `inbounds`, any address may be computed");
+
+    interpreter.start(store_function, &[RuntimeValue::from_int(64, 1)]).expect("Failed to start function");
+    let StopReason::UndefinedBehavior(store) = interpreter.run().expect("Failed to run function") else {
+        panic!("The store should be reported");
+    };
+    assert_eq!(store.get_kind(), UndefinedBehaviorKind::OutOfBoundsAccess);
+    assert_eq!(store.get_message(), "access of 8 bytes at offset 40 is outside 'array', which has 32 bytes");
+}
+
+#[test]
+fn test_arithmetic_and_lifetime_errors() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "divide");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let hundred = pools.create_integer(context_tag, 100).expect("Failed to create integer");
+    let quotient = pools.build_div(builder_tag, hundred, n, "quotient").expect("Failed to build div");
+    pools.nonvoid_return(builder_tag, quotient).expect("Failed to build return");
+
+    let pointer_type = pools.pointer_type(int_type).expect("Failed to create pointer type");
+    let escape_type = pools.create_function(Some(pointer_type), &[], false, context_tag).expect("Failed to create function type");
+    let escape = pools.add_function_to_module(module_tag, "escape", escape_type).expect("Failed to add function");
+    let escape_entry = pools.create_basic_block(context_tag, escape, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, escape_entry).expect("Failed to position builder");
+    let local = pools.init_var(builder_tag, "local", int_type, Some(hundred)).expect("Failed to initialize variable");
+    pools.nonvoid_return(builder_tag, local).expect("Failed to build return");
+
+    let caller_type = pools.create_function(Some(int_type), &[], false, context_tag).expect("Failed to create function type");
+    let caller = pools.add_function_to_module(module_tag, "dangling", caller_type).expect("Failed to add function");
+    let caller_entry = pools.create_basic_block(context_tag, caller, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, caller_entry).expect("Failed to position builder");
+    let escaped = pools.build_call(builder_tag, escape_type, escape, &[], "escaped").expect("Failed to build call");
+    let value = pools.get_var(builder_tag, int_type, escaped, "value").expect("Failed to load variable");
+    pools.nonvoid_return(builder_tag, value).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 4)]).expect("Failed to start function");
+    assert_eq!(interpreter.run(), Ok(StopReason::Returned(Some(RuntimeValue::from_int(64, 25)))));
+
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 0)]).expect("Failed to start function");
+    let StopReason::UndefinedBehavior(division) = interpreter.run().expect("Failed to run function") else {
+        panic!("The division should be reported");
+    };
+    assert_eq!(division.get_kind(), UndefinedBehaviorKind::DivisionByZero);
+
+    interpreter.start(caller, &[]).expect("Failed to start function");
+    let StopReason::UndefinedBehavior(dangling) = interpreter.run().expect("Failed to run function") else {
+        panic!("The load should be reported");
+    };
+    assert_eq!(dangling.get_kind(), UndefinedBehaviorKind::UseAfterReturn);
+    assert_eq!(dangling.get_function(), "dangling");
+    assert_eq!(dangling.get_message(), "access to 'local' after its function returned");
+}
+
+#[test]
+fn test_oversized_alloca() {
+    let mut pools = IRManager::new();
+    let (context_tag, module_tag, int_type, function_tag, builder_tag) = setup(&mut pools, "oversized");
+    let entry = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry).expect("Failed to position builder");
+    let n = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let count = pools.create_integer(context_tag, -1).expect("Failed to create integer");
+    pools.init_array_var(builder_tag, "slots", int_type, count).expect("Failed to build alloca");
+    pools.nonvoid_return(builder_tag, n).expect("Failed to build return");
+
+    let mut interpreter = StepInterpreter::new(&mut pools, module_tag).expect("Failed to create interpreter");
+    interpreter.start(function_tag, &[RuntimeValue::from_int(64, 1)]).expect("Failed to start function");
+    let error = interpreter.run().expect_err("The alloca of -1 elements should be rejected");
+    assert!(error.contains("allocates -1 elements of 8 bytes"), "Unexpected error: {}", error);
+}
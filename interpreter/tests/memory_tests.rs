@@ -0,0 +1,55 @@
+use interpreter::{memory::{AllocationKind, Memory, MemoryError, Pointer}, value::RuntimeValue};
+
+#[test]
+fn test_memory_accesses() {
+    let mut memory = Memory::new();
+    let slot = memory.allocate("slot", AllocationKind::Stack, 8);
+    assert_eq!(memory.get_allocation(slot).map(|allocation| (allocation.get_name(), allocation.get_kind(), allocation.get_size())), Some(("slot", AllocationKind::Stack, 8)));
+
+    assert_eq!(memory.read(slot, 8), Err(MemoryError::Uninitialized { name: "slot".to_string(), offset: 0 }));
+    memory.write(slot.offset_by(4), &[1, 2, 3, 4]).expect("Failed to write");
+    assert_eq!(memory.read(slot.offset_by(4), 4), Ok(vec![1, 2, 3, 4]));
+    assert_eq!(memory.read(slot.offset_by(2), 4), Err(MemoryError::Uninitialized { name: "slot".to_string(), offset: 2 }));
+    assert_eq!(memory.get_allocation(slot).map(|allocation| allocation.get_bytes()), Some(vec![None, None, None, None, Some(1), Some(2), Some(3), Some(4)]));
+
+    let overflow = memory.write(slot.offset_by(6), &[0; 4]).expect_err("The write should be out of bounds");
+    assert_eq!(overflow.to_string(), "access of 4 bytes at offset 6 is outside 'slot', which has 8 bytes");
+    assert!(matches!(memory.read(slot.offset_by(-1), 1), Err(MemoryError::OutOfBounds { .. })));
+    assert!(matches!(memory.read(slot.offset_by(4), usize::MAX), Err(MemoryError::OutOfBounds { .. })), "The end of the access overflows");
+    assert_eq!(memory.read(Pointer::null(), 1), Err(MemoryError::NullPointer));
+
+    assert!(memory.is_in_bounds(slot.offset_by(8)), "One past the end is in bounds");
+    assert!(!memory.is_in_bounds(slot.offset_by(9)));
+    assert!(!memory.is_in_bounds(slot.offset_by(-1)));
+
+    memory.release(slot);
+    assert!(!memory.is_in_bounds(slot));
+    assert_eq!(memory.read(slot.offset_by(4), 4), Err(MemoryError::DeadAllocation { name: "slot".to_string() }));
+}
+
+#[test]
+fn test_pointers_and_values() {
+    let pointer = Pointer::new(3, -8);
+    assert_eq!(pointer.to_address().map(Pointer::from_address), Ok(pointer), "Addresses round-trip, including negative offsets");
+    assert_eq!(Pointer::from_address(0), Pointer::null());
+    assert_eq!(pointer.offset_by(1 << 32).to_address(), Err(MemoryError::AddressOverflow { offset: (1 << 32) - 8 }), "Offsets beyond 32 bits have no address");
+    assert!(Pointer::null().is_null() && !pointer.is_null());
+    assert_eq!(pointer.offset_by(16).get_offset(), 8);
+    assert_eq!(pointer.to_string(), "<allocation 3>-8");
+
+    let byte = RuntimeValue::from_int(8, -1);
+    assert_eq!(byte, RuntimeValue::Int { bits: 8, value: 255 });
+    assert_eq!((byte.as_u64(), byte.as_i64()), (Some(255), Some(-1)));
+    assert_eq!(RuntimeValue::from_int(64, i64::MIN).as_i64(), Some(i64::MIN));
+    assert_eq!(RuntimeValue::from_int(33, -2).to_bytes(), Ok(vec![0xfe, 0xff, 0xff, 0xff, 0x01]));
+    assert_eq!(RuntimeValue::from_bool(true).to_bytes(), Ok(vec![1]));
+    assert_eq!(RuntimeValue::Pointer(pointer).to_bytes(), pointer.to_address().map(|address| address.to_le_bytes().to_vec()));
+    assert!(RuntimeValue::Pointer(pointer.offset_by(i64::MIN)).to_bytes().is_err());
+    assert_eq!(RuntimeValue::Float(1.5).as_f64(), Some(1.5));
+    assert_eq!(byte.as_pointer(), None);
+
+    assert_eq!(byte.to_string(), "i8 -1");
+    assert_eq!(RuntimeValue::from_bool(false).to_string(), "i1 false");
+    assert_eq!(RuntimeValue::Double(2.5).to_string(), "double 2.5");
+    assert_eq!(RuntimeValue::Pointer(Pointer::null()).to_string(), "ptr null");
+}
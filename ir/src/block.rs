@@ -26,6 +26,7 @@ impl IRManager {
         function_tag: ValueTag,
         name: &str
     ) -> Option<BasicBlockTag> {
+        let trace = self.begin_trace("create_basic_block", || format!("context_tag={context_tag:?}, function_tag={function_tag:?}, name={name:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let function_arc_rwlock = self.get_value(function_tag)?;
 
@@ -58,7 +59,7 @@ impl IRManager {
         };
     
         if !basic_block.is_null() {
-            self.store_basic_block(basic_block)
+            trace.finish(self.store_basic_block(basic_block), self)
         } else {
             None
         }
@@ -75,6 +76,7 @@ impl IRManager {
     /// # Returns
     /// Option<BasicBlockTag> - The tag of the newly created basic block, or None if creation fails.
     pub fn create_basic_block_after(&mut self, context_tag: ContextTag, function_tag: ValueTag, target_tag: BasicBlockTag, name: &str) -> Option<BasicBlockTag> {
+        let trace = self.begin_trace("create_basic_block_after", || format!("context_tag={context_tag:?}, function_tag={function_tag:?}, target_tag={target_tag:?}, name={name:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let function_arc_rwlock = self.get_value(function_tag)?;
         let bb_arc_rwlock = self.get_basic_block(target_tag)?;
@@ -124,7 +126,7 @@ impl IRManager {
             unsafe {
                 core::LLVMMoveBasicBlockAfter(basic_block, bb_ptr);
             }
-            return return_val
+            return trace.finish(return_val, self)
         } else {
             return None
         }
@@ -140,6 +142,7 @@ impl IRManager {
     /// # Returns
     /// Option<BasicBlockTag> - The tag of the inserted basic block, or None if insertion fails.
     pub fn insert_before_basic_block(&mut self, context_tag: ContextTag, before_target_tag: BasicBlockTag, name: &str) -> Option<BasicBlockTag> {
+        let trace = self.begin_trace("insert_before_basic_block", || format!("context_tag={context_tag:?}, before_target_tag={before_target_tag:?}, name={name:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let before_target_arc_rwlock = self.get_basic_block(before_target_tag)?;
 
@@ -174,7 +177,7 @@ impl IRManager {
         if basic_block.is_null() {
             None
         } else {
-            self.store_basic_block(basic_block)
+            trace.finish(self.store_basic_block(basic_block), self)
         }
     }
 
@@ -295,6 +298,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The resulting branch instruction tag, or None if the branch creation fails.
     pub fn create_cond_br(&mut self, builder_tag: BuilderTag, condition_tag: ValueTag, then_bb_tag: BasicBlockTag, else_bb_tag: BasicBlockTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_cond_br", || format!("builder_tag={builder_tag:?}, condition_tag={condition_tag:?}, then_bb_tag={then_bb_tag:?}, else_bb_tag={else_bb_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let condition_arc_rwlock = self.get_value(condition_tag)?;
         let then_bb_arc_rwlock = self.get_basic_block(then_bb_tag)?;
@@ -351,7 +355,7 @@ impl IRManager {
         if branch.is_null() {
             None
         } else {
-            self.store_value(branch)
+            trace.finish(self.store_value(branch), self)
         }
     }
 
@@ -364,6 +368,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created branch instruction, or None if the operation fails.
     pub fn create_br(&mut self, builder_tag: BuilderTag, target_bb_tag: BasicBlockTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_br", || format!("builder_tag={builder_tag:?}, target_bb_tag={target_bb_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let target_bb_arc_rwlock = self.get_basic_block(target_bb_tag)?;
 
@@ -396,7 +401,63 @@ impl IRManager {
         if branch.is_null() {
             None
         } else {
-            self.store_value(branch)
+            trace.finish(self.store_value(branch), self)
+        }
+    }
+
+    /// Creates a switch instruction that jumps to the block of the case matching a value.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - BuilderTag indicating the current position.
+    /// * `value_tag` - ValueTag of the integer to switch on.
+    /// * `default_bb_tag` - BasicBlockTag of the block to jump to if no case matches.
+    /// * `cases` - Pairs of a constant integer and the block to jump to if the value equals it.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the created switch instruction, or None if the operation fails.
+    pub fn create_switch(&mut self, builder_tag: BuilderTag, value_tag: ValueTag, default_bb_tag: BasicBlockTag, cases: &[(ValueTag, BasicBlockTag)]) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_switch", || format!("builder_tag={builder_tag:?}, value_tag={value_tag:?}, default_bb_tag={default_bb_tag:?}, cases={cases:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+        let value_ptr = self.get_value_ptr(value_tag)?;
+        let default_bb_ptr = self.get_basic_block_ptr(default_bb_tag)?;
+        let case_ptrs = cases.iter()
+            .map(|(case_tag, case_bb_tag)| Some((self.get_value_ptr(*case_tag)?, self.get_basic_block_ptr(*case_bb_tag)?)))
+            .collect::<Option<Vec<_>>>()?;
+
+        let switch = unsafe {
+            let switch = core::LLVMBuildSwitch(builder_ptr, value_ptr, default_bb_ptr, case_ptrs.len() as u32);
+            if !switch.is_null() {
+                for (case_ptr, case_bb_ptr) in case_ptrs {
+                    core::LLVMAddCase(switch, case_ptr, case_bb_ptr);
+                }
+            }
+            switch
+        };
+
+        if switch.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(switch), self)
+        }
+    }
+
+    /// Creates an unreachable instruction, marking the end of a block control never reaches.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - BuilderTag indicating the current position.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the created unreachable instruction, or None if the operation fails.
+    pub fn create_unreachable(&mut self, builder_tag: BuilderTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_unreachable", || format!("builder_tag={builder_tag:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+
+        let unreachable = unsafe { core::LLVMBuildUnreachable(builder_ptr) };
+
+        if unreachable.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(unreachable), self)
         }
     }
     
@@ -409,6 +470,7 @@ impl IRManager {
     /// # Returns
     /// Option<()> - None if the operation fails, or an empty Option if successful.
     pub fn position_builder_at_end(&mut self, builder_tag: BuilderTag, bb_tag: BasicBlockTag) -> Option<()> {
+        let trace = self.begin_trace("position_builder_at_end", || format!("builder_tag={builder_tag:?}, bb_tag={bb_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let bb_arc_rwlock = self.get_basic_block(bb_tag)?;
 
@@ -438,7 +500,7 @@ impl IRManager {
             core::LLVMPositionBuilderAtEnd(builder_ptr, bb_ptr);
         }
 
-        Some(())
+        trace.finish(Some(()), self)
     }
 
     /// Deletes a specified basic block from the function.
@@ -449,6 +511,7 @@ impl IRManager {
     /// # Returns
     /// Option<()> - None if the deletion fails, or an empty Option if successful.
     pub fn delete_basic_block(&mut self, bb_tag: BasicBlockTag) -> Option<()> {
+        let trace = self.begin_trace("delete_basic_block", || format!("bb_tag={bb_tag:?}"));
         let bb_arc_rwlock = self.get_basic_block(bb_tag)?;
 
         let bb_ptr: LLVMBasicBlockRef = {
@@ -466,7 +529,7 @@ impl IRManager {
             core::LLVMDeleteBasicBlock(bb_ptr);
         }
 
-        Some(())
+        trace.finish(Some(()), self)
     }
 
     /// Retrieves the first instruction within a target basic block.
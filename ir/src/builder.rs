@@ -8,7 +8,7 @@ extern crate llvm_sys as llvm;
 use std::ffi::CString;
 use llvm::{core, prelude::LLVMBuilderRef, LLVMIntPredicate};
 use common::pointer::{LLVMRef, LLVMRefType};
-use crate::core::{BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag};
+use crate::core::{BasicBlockTag, BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag};
 
 impl IRManager {
     /// Allocates a builder in a specified context and stores it in the resource pool.
@@ -19,6 +19,7 @@ impl IRManager {
     /// # Returns
     /// Option<BuilderTag> - The tag of the newly created builder or None if the builder cannot be created.
     pub fn create_builder(&mut self, context_tag: ContextTag) -> Option<BuilderTag> {
+        let trace = self.begin_trace("create_builder", || format!("context_tag={context_tag:?}"));
 
         let context_arc_rwlock = self.get_context(context_tag)?;
 
@@ -40,7 +41,7 @@ impl IRManager {
             return None;
         }
 
-        self.store_builder(builder_ptr)
+        trace.finish(self.store_builder(builder_ptr), self)
     }
 
     /// Adds a function to a module.
@@ -53,6 +54,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the newly added function or None if the function cannot be added.
     pub fn add_function_to_module(&mut self, module_tag: ModuleTag, function_name: &str, function_type_tag: TypeTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("add_function_to_module", || format!("module_tag={module_tag:?}, function_name={function_name:?}, function_type_tag={function_type_tag:?}"));
         let module_arc_rwlock = self.get_module(module_tag)?;
         let function_type_arc_rwlock = self.get_type(function_type_tag)?;
 
@@ -85,7 +87,7 @@ impl IRManager {
         if function.is_null() {
             None
         } else {
-            self.store_value(function)
+            trace.finish(self.store_value(function), self)
         }
     }
 
@@ -106,6 +108,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_and", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -143,7 +146,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -164,6 +167,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_or", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -201,7 +205,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -222,6 +226,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_xor", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -259,7 +264,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -280,6 +285,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_shl", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -317,7 +323,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -338,6 +344,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_shr", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -375,7 +382,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -396,6 +403,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_icmp_gt", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -433,7 +441,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -454,6 +462,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_icmp_lt", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -491,7 +500,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -512,6 +521,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_icmp_eq", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -549,7 +559,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -568,6 +578,7 @@ impl IRManager {
         operand_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_negation", || format!("builder_tag={builder_tag:?}, operand_tag={operand_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let operand_arc_rwlock = self.get_value(operand_tag)?;
 
@@ -596,7 +607,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -615,6 +626,7 @@ impl IRManager {
         operand_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_bitwise_not", || format!("builder_tag={builder_tag:?}, operand_tag={operand_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let operand_arc_rwlock = self.get_value(operand_tag)?;
 
@@ -643,7 +655,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -664,6 +676,7 @@ impl IRManager {
         operand_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_logical_not", || format!("builder_tag={builder_tag:?}, context_tag={context_tag:?}, operand_tag={operand_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let context_arc_rwlock = self.get_context(context_tag)?;
         let operand_arc_rwlock = self.get_value(operand_tag)?;
@@ -704,7 +717,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -725,6 +738,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_add", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -762,7 +776,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -783,6 +797,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_sub", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -820,7 +835,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -841,6 +856,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_mul", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -878,7 +894,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -899,6 +915,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_div", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -936,7 +953,7 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
 
@@ -957,6 +974,7 @@ impl IRManager {
         param_b_tag: ValueTag,
         name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_rem", || format!("builder_tag={builder_tag:?}, param_a_tag={param_a_tag:?}, param_b_tag={param_b_tag:?}, name={name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let param_a_arc_rwlock = self.get_value(param_a_tag)?;
         let param_b_arc_rwlock = self.get_value(param_b_tag)?;
@@ -994,7 +1012,91 @@ impl IRManager {
         if result.is_null() {
             None
         } else {
-            self.store_value(result)
+            trace.finish(self.store_value(result), self)
         }
     }
+
+    /// Builds a call to a function.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - Tag of the builder.
+    /// * `function_type_tag` - Tag of the type of the called function.
+    /// * `function_tag` - Tag of the function, or of a pointer to it.
+    /// * `args` - Tags of the arguments, in order.
+    /// * `name` - Name for the result, empty for calls to functions returning void.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the call instruction or None if the operation fails.
+    pub fn build_call(&mut self, builder_tag: BuilderTag, function_type_tag: TypeTag, function_tag: ValueTag, args: &[ValueTag], name: &str) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_call", || format!("builder_tag={builder_tag:?}, function_type_tag={function_type_tag:?}, function_tag={function_tag:?}, args={args:?}, name={name:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+        let function_type_ptr = self.get_type_ptr(function_type_tag)?;
+        let function_ptr = self.get_value_ptr(function_tag)?;
+        let mut arg_ptrs = args.iter().map(|arg| self.get_value_ptr(*arg)).collect::<Option<Vec<_>>>()?;
+
+        let c_name = CString::new(name).expect("Failed to create CString for name");
+
+        let call = unsafe {
+            core::LLVMBuildCall2(builder_ptr, function_type_ptr, function_ptr, arg_ptrs.as_mut_ptr(), arg_ptrs.len() as u32, c_name.as_ptr())
+        };
+
+        if call.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(call), self)
+        }
+    }
+
+    /// Builds a phi node that selects a value depending on the block control came from.
+    ///
+    /// The incoming values are added with `add_incoming`, since they are often defined after the phi node.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - Tag of the builder.
+    /// * `type_tag` - Tag of the type of the incoming values.
+    /// * `name` - Name for the result.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the phi node or None if the operation fails.
+    pub fn build_phi(&mut self, builder_tag: BuilderTag, type_tag: TypeTag, name: &str) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_phi", || format!("builder_tag={builder_tag:?}, type_tag={type_tag:?}, name={name:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+        let type_ptr = self.get_type_ptr(type_tag)?;
+
+        let c_name = CString::new(name).expect("Failed to create CString for name");
+
+        let phi = unsafe {
+            core::LLVMBuildPhi(builder_ptr, type_ptr, c_name.as_ptr())
+        };
+
+        if phi.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(phi), self)
+        }
+    }
+
+    /// Adds incoming values to a phi node.
+    ///
+    /// # Parameters
+    /// * `phi_tag` - Tag of the phi node.
+    /// * `incoming` - Pairs of a value and the predecessor block it comes from.
+    ///
+    /// # Returns
+    /// Option<()> - Some(()) if the values were added, or None if any tag is unknown.
+    pub fn add_incoming(&mut self, phi_tag: ValueTag, incoming: &[(ValueTag, BasicBlockTag)]) -> Option<()> {
+        let trace = self.begin_trace("add_incoming", || format!("phi_tag={phi_tag:?}, incoming={incoming:?}"));
+        let phi_ptr = self.get_value_ptr(phi_tag)?;
+        let (mut value_ptrs, mut block_ptrs): (Vec<_>, Vec<_>) = incoming.iter()
+            .map(|(value_tag, block_tag)| Some((self.get_value_ptr(*value_tag)?, self.get_basic_block_ptr(*block_tag)?)))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        unsafe {
+            core::LLVMAddIncoming(phi_ptr, value_ptrs.as_mut_ptr(), block_ptrs.as_mut_ptr(), value_ptrs.len() as u32);
+        }
+
+        trace.finish(Some(()), self)
+    }
 }
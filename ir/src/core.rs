@@ -6,7 +6,9 @@
 extern crate llvm_sys as llvm;
 use llvm::{core, prelude::{LLVMBasicBlockRef, LLVMBuilderRef, LLVMContextRef, LLVMModuleRef, LLVMTypeRef, LLVMValueRef}};
 use std::{collections::HashMap, ffi::CString, sync::{Arc, RwLock}};
+use slog::Logger;
 use common::pointer::{LLVMRef, LLVMRefType, SafeLLVMPointer};
+use crate::diagnostics::{install_diagnostic_handler, DiagnosticSink};
 
 /// Represents a definition for an LLVM enum type, mapping string names to integer values.
 /// This structure aids in managing enum representations within the LLVM IR.
@@ -123,11 +125,16 @@ pub struct IRManager {
     contexts: Option<HashMap<ContextTag, Arc<RwLock<SafeLLVMPointer>>>>,
     modules: Option<HashMap<ModuleTag, Arc<RwLock<SafeLLVMPointer>>>>,
     values: Option<HashMap<ValueTag, Arc<RwLock<SafeLLVMPointer>>>>,
+    value_tag_map: Option<HashMap<LLVMValueRef, ValueTag>>,
     basic_blocks: Option<HashMap<BasicBlockTag, Arc<RwLock<SafeLLVMPointer>>>>,
     basic_block_tag_map: Option<HashMap<LLVMBasicBlockRef, BasicBlockTag>>,
     builders: Option<HashMap<BuilderTag, Arc<RwLock<SafeLLVMPointer>>>>,
     types: Option<HashMap<TypeTag, Arc<RwLock<SafeLLVMPointer>>>>,
     enums: Option<HashMap<TypeTag, EnumDefinition>>,
+    trace_logger: Option<Logger>,
+    diagnostic_sinks: Option<HashMap<ContextTag, Arc<DiagnosticSink>>>,
+    diagnostic_logger: Option<Logger>,
+    collect_diagnostics: bool,
     next_tag: usize,
 }
 
@@ -141,11 +148,16 @@ impl IRManager {
             contexts: None,
             modules: None,
             values: None,
+            value_tag_map: None,
             basic_blocks: None,
             basic_block_tag_map: None,
             builders: None,
             types: None,
             enums: None,
+            trace_logger: None,
+            diagnostic_sinks: None,
+            diagnostic_logger: None,
+            collect_diagnostics: false,
             next_tag: 0,
         }
     }
@@ -155,6 +167,67 @@ impl IRManager {
         self.next_tag += 1;
     }
 
+    /// Sets the logger that create and build calls are traced to.
+    ///
+    /// # Arguments
+    /// * `logger` - The logger to trace to, or `None` to disable tracing.
+    pub(crate) fn set_trace_logger(&mut self, logger: Option<Logger>) {
+        self.trace_logger = logger;
+    }
+
+    /// Retrieves the logger that create and build calls are traced to.
+    ///
+    /// # Returns
+    /// An option containing the trace logger if tracing is enabled, `None` otherwise.
+    pub(crate) fn get_trace_logger(&self) -> Option<&Logger> {
+        self.trace_logger.as_ref()
+    }
+
+    /// Sets the logger and collection setting applied to the diagnostics of new contexts.
+    ///
+    /// # Arguments
+    /// * `logger` - The logger to write diagnostics to, or `None` to print them to stderr.
+    /// * `collect` - Whether diagnostics are collected.
+    pub(crate) fn set_diagnostic_settings(&mut self, logger: Option<Logger>, collect: bool) {
+        self.diagnostic_logger = logger;
+        self.collect_diagnostics = collect;
+    }
+
+    /// Retrieves the logger diagnostics are written to.
+    ///
+    /// # Returns
+    /// An option containing the diagnostic logger if one is set, `None` otherwise.
+    pub(crate) fn get_diagnostic_logger(&self) -> Option<&Logger> {
+        self.diagnostic_logger.as_ref()
+    }
+
+    /// Retrieves whether diagnostics are collected.
+    ///
+    /// # Returns
+    /// True if diagnostics are collected, false otherwise.
+    pub(crate) fn get_collect_diagnostics(&self) -> bool {
+        self.collect_diagnostics
+    }
+
+    /// Retrieves the diagnostic sink of a context.
+    ///
+    /// # Arguments
+    /// * `tag` - The tag of the context.
+    ///
+    /// # Returns
+    /// An option containing the sink, or `None` if the context has no diagnostic handler.
+    pub(crate) fn get_diagnostic_sink(&self, tag: ContextTag) -> Option<Arc<DiagnosticSink>> {
+        self.diagnostic_sinks.as_ref()?.get(&tag).cloned()
+    }
+
+    /// Retrieves the diagnostic sinks of every context.
+    ///
+    /// # Returns
+    /// The sinks of all contexts with a diagnostic handler.
+    pub(crate) fn get_diagnostic_sinks(&self) -> Vec<Arc<DiagnosticSink>> {
+        self.diagnostic_sinks.as_ref().map(|sinks| sinks.values().cloned().collect()).unwrap_or_default()
+    }
+
     /// Creates a new context and stores it in the resource pools.
     ///
     /// # Arguments
@@ -184,6 +257,21 @@ impl IRManager {
         self.contexts.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a context by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `ContextTag` used to identify the context.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMContextRef` of the context if found, `None` otherwise.
+    pub fn get_context_ptr(&self, tag: ContextTag) -> Option<LLVMContextRef> {
+        let context_arc_rwlock = self.get_context(tag)?;
+        let context_rwlock = context_arc_rwlock.read().ok()?;
+        context_rwlock.read(LLVMRefType::Context, |context_ref| {
+            if let LLVMRef::Context(ptr) = context_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Creates a new module and stores it in the resource pools.
     ///
     /// # Arguments
@@ -213,6 +301,31 @@ impl IRManager {
         self.modules.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a module by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `ModuleTag` used to identify the module.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMModuleRef` of the module if found, `None` otherwise.
+    pub fn get_module_ptr(&self, tag: ModuleTag) -> Option<LLVMModuleRef> {
+        Self::read_module_ptr(&self.get_module(tag)?)
+    }
+
+    /// Retrieves the raw pointer of a module handed out by `get_module`.
+    ///
+    /// # Arguments
+    /// * `module` - The shared module, for example one passed to an execution engine.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMModuleRef` of the module, `None` if the lock is poisoned or the pointer is not a module.
+    pub fn read_module_ptr(module: &Arc<RwLock<SafeLLVMPointer>>) -> Option<LLVMModuleRef> {
+        let module_rwlock = module.read().ok()?;
+        module_rwlock.read(LLVMRefType::Module, |module_ref| {
+            if let LLVMRef::Module(ptr) = module_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Creates a new value and stores it in the resource pools.
     ///
     /// # Arguments
@@ -228,9 +341,38 @@ impl IRManager {
         let value_map = self.values.get_or_insert_with(HashMap::new);
         value_map.insert(tag, Arc::new(RwLock::new(c_pointer)));
 
+        let value_tag_map = self.value_tag_map.get_or_insert_with(HashMap::new);
+        value_tag_map.insert(value, tag);
+
         Some(tag)
     }
 
+    /// Retrieves a value's tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `value` - A raw pointer to an LLVMValueRef.
+    ///
+    /// # Returns
+    /// An option containing the most recently created ValueTag for the value if it has been stored,
+    /// `None` otherwise.
+    pub fn get_value_tag(&self, value: LLVMValueRef) -> Option<ValueTag> {
+        self.value_tag_map.as_ref()?.get(&value).cloned()
+    }
+
+    /// Retrieves a value's tag from the resource pools, storing the value if it has no tag yet.
+    ///
+    /// # Arguments
+    /// * `value` - A raw pointer to an LLVMValueRef.
+    ///
+    /// # Returns
+    /// An option containing the ValueTag of the value, `None` if it had to be stored and storing failed.
+    pub fn get_or_store_value(&mut self, value: LLVMValueRef) -> Option<ValueTag> {
+        match self.get_value_tag(value) {
+            Some(tag) => Some(tag),
+            None => self.store_value(value),
+        }
+    }
+
     /// Retrieves a value by its tag from the resource pools.
     ///
     /// # Arguments
@@ -242,6 +384,21 @@ impl IRManager {
         self.values.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a value by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `ValueTag` used to identify the value.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMValueRef` of the value if found, `None` otherwise.
+    pub fn get_value_ptr(&self, tag: ValueTag) -> Option<LLVMValueRef> {
+        let value_arc_rwlock = self.get_value(tag)?;
+        let value_rwlock = value_arc_rwlock.read().ok()?;
+        value_rwlock.read(LLVMRefType::Value, |value_ref| {
+            if let LLVMRef::Value(ptr) = value_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Creates a new basic block and stores it in the resource pools.
     ///
     /// # Arguments
@@ -296,6 +453,21 @@ impl IRManager {
         self.retrieve_basic_block_tag(basic_block)
     }
 
+    /// Retrieves a basic block's tag from the resource pools, storing the block if it has no tag yet.
+    ///
+    /// # Arguments
+    /// * `basic_block` - A reference to an LLVMBasicBlockRef.
+    ///
+    /// # Returns
+    /// An option containing the BasicBlockTag of the basic block, `None` if it had to be stored and
+    /// storing failed.
+    pub fn get_or_store_basic_block(&mut self, basic_block: LLVMBasicBlockRef) -> Option<BasicBlockTag> {
+        match self.retrieve_basic_block_tag(basic_block) {
+            Some(tag) => Some(tag),
+            None => self.store_basic_block(basic_block),
+        }
+    }
+
     /// Retrieves a basic block by its tag from the resource pools.
     ///
     /// # Arguments
@@ -307,6 +479,21 @@ impl IRManager {
         self.basic_blocks.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a basic block by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `BasicBlockTag` used to identify the basic block.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMBasicBlockRef` of the basic block if found, `None` otherwise.
+    pub fn get_basic_block_ptr(&self, tag: BasicBlockTag) -> Option<LLVMBasicBlockRef> {
+        let basic_block_arc_rwlock = self.get_basic_block(tag)?;
+        let basic_block_rwlock = basic_block_arc_rwlock.read().ok()?;
+        basic_block_rwlock.read(LLVMRefType::BasicBlock, |basic_block_ref| {
+            if let LLVMRef::BasicBlock(ptr) = basic_block_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Creates a new builder and stores it in the resource pools.
     ///
     /// # Arguments
@@ -337,6 +524,21 @@ impl IRManager {
         self.builders.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a builder by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `BuilderTag` used to identify the builder.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMBuilderRef` of the builder if found, `None` otherwise.
+    pub fn get_builder_ptr(&self, tag: BuilderTag) -> Option<LLVMBuilderRef> {
+        let builder_arc_rwlock = self.get_builder(tag)?;
+        let builder_rwlock = builder_arc_rwlock.read().ok()?;
+        builder_rwlock.read(LLVMRefType::Builder, |builder_ref| {
+            if let LLVMRef::Builder(ptr) = builder_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Creates a new type and stores it in the resource pools.
     ///
     /// # Arguments
@@ -367,6 +569,21 @@ impl IRManager {
         self.types.as_ref()?.get(&tag).cloned()
     }
 
+    /// Retrieves the raw pointer of a type by its tag from the resource pools.
+    ///
+    /// # Arguments
+    /// * `tag` - The `TypeTag` used to identify the type.
+    ///
+    /// # Returns
+    /// An option containing the `LLVMTypeRef` of the type if found, `None` otherwise.
+    pub fn get_type_ptr(&self, tag: TypeTag) -> Option<LLVMTypeRef> {
+        let type_arc_rwlock = self.get_type(tag)?;
+        let type_rwlock = type_arc_rwlock.read().ok()?;
+        type_rwlock.read(LLVMRefType::Type, |type_ref| {
+            if let LLVMRef::Type(ptr) = type_ref { Some(*ptr) } else { None }
+        })
+    }
+
     /// Stores an enum definition associated with a type tag in the resource pools.
     ///
     /// # Arguments
@@ -390,16 +607,27 @@ impl IRManager {
 
     /// Allocates a new LLVM context and stores it in the resource pool, assigning a new tag.
     ///
+    /// A diagnostic handler is installed on the context, routing LLVM's diagnostics to the
+    /// diagnostic logger and collecting them if collection is enabled.
+    ///
     /// # Returns
     /// An option containing a `ContextTag` if a new context was successfully created and stored, `None` if creation failed.
     pub fn create_context(&mut self) -> Option<ContextTag> {
+        let trace = self.begin_trace("create_context", String::new);
         let raw_ptr: LLVMContextRef = unsafe { core::LLVMContextCreate() };
 
         if raw_ptr.is_null() {
             return None;
         }
 
-        self.store_context(raw_ptr)
+        let sink = Arc::new(DiagnosticSink::default());
+        sink.set_logger(self.diagnostic_logger.clone());
+        sink.set_collect(self.collect_diagnostics);
+        install_diagnostic_handler(raw_ptr, &sink);
+
+        let context_tag = self.store_context(raw_ptr)?;
+        self.diagnostic_sinks.get_or_insert_with(HashMap::new).insert(context_tag, sink);
+        trace.finish(Some(context_tag), self)
     }
 
     /// Allocates a new LLVM module in a specified context and stores it in the resource pool.
@@ -411,6 +639,8 @@ impl IRManager {
     /// # Returns
     /// An option containing a `ModuleTag` if a new module was successfully created and stored, `None` if creation failed.
     pub fn create_module(&mut self, module_name: &str, context_tag: ContextTag) -> Option<ModuleTag> {
+        let _span = logging::spans::module_generation(module_name);
+        let trace = self.begin_trace("create_module", || format!("module_name={module_name:?}, context_tag={context_tag:?}"));
         let c_module_name: CString = CString::new(module_name).expect("Failed to create CString from module name");
 
         let context_arc_rwlock = self.get_context(context_tag)?;
@@ -433,6 +663,6 @@ impl IRManager {
             return None;
         }
 
-        self.store_module(module_ptr)
+        trace.finish(self.store_module(module_ptr), self)
     }
 }
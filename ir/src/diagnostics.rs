@@ -0,0 +1,253 @@
+//! Routing of LLVM diagnostics into the logging crate.
+//!
+//! Every context created by `IRManager::create_context` has a diagnostic handler installed. LLVM
+//! reports warnings and errors from the linker, code generation, optimization remarks and inline
+//! assembly through this handler instead of printing them to stderr. Each diagnostic is logged at
+//! the slog level matching its severity, and can optionally be collected for retrieval by context
+//! tag. The module verifier is not covered: it returns its findings to its caller, and
+//! `analysis::validator::Validator` prints them to stderr.
+
+extern crate llvm_sys as llvm;
+use std::{ffi::{c_void, CStr}, fmt, sync::{Arc, Mutex}};
+use llvm::{core, prelude::{LLVMContextRef, LLVMDiagnosticInfoRef}, LLVMDiagnosticSeverity};
+use slog::{Level, Logger};
+use crate::core::{ContextTag, IRManager};
+
+/// The severity of an LLVM diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// An error. The operation that reported it has failed.
+    Error,
+    /// A warning about a likely problem.
+    Warning,
+    /// An optimization remark.
+    Remark,
+    /// Additional information attached to a preceding diagnostic.
+    Note,
+}
+
+impl DiagnosticSeverity {
+    /// Maps the severity to the slog level diagnostics of this severity are logged at.
+    ///
+    /// # Returns
+    /// `Error` and `Warning` map to the levels of the same name, `Note` to `Info` and `Remark` to `Debug`.
+    pub fn to_level(self) -> Level {
+        match self {
+            DiagnosticSeverity::Error => Level::Error,
+            DiagnosticSeverity::Warning => Level::Warning,
+            DiagnosticSeverity::Note => Level::Info,
+            DiagnosticSeverity::Remark => Level::Debug,
+        }
+    }
+
+    /// Retrieves the prefix LLVM prints before diagnostics of this severity.
+    ///
+    /// # Returns
+    /// A string such as `error` or `warning`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Remark => "remark",
+            DiagnosticSeverity::Note => "note",
+        }
+    }
+}
+
+impl From<LLVMDiagnosticSeverity> for DiagnosticSeverity {
+    fn from(severity: LLVMDiagnosticSeverity) -> Self {
+        match severity {
+            LLVMDiagnosticSeverity::LLVMDSError => DiagnosticSeverity::Error,
+            LLVMDiagnosticSeverity::LLVMDSWarning => DiagnosticSeverity::Warning,
+            LLVMDiagnosticSeverity::LLVMDSRemark => DiagnosticSeverity::Remark,
+            LLVMDiagnosticSeverity::LLVMDSNote => DiagnosticSeverity::Note,
+        }
+    }
+}
+
+/// A diagnostic reported by LLVM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: DiagnosticSeverity,
+    /// The description LLVM gave for the diagnostic.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity.as_str(), self.message)
+    }
+}
+
+/// The destination of the diagnostics of one context.
+///
+/// A reference to the sink is handed to LLVM as the diagnostic context, so the sink lives for as
+/// long as the context, which is never disposed.
+#[derive(Default)]
+pub(crate) struct DiagnosticSink {
+    logger: Mutex<Option<Logger>>,
+    collect: Mutex<bool>,
+    diagnostics: Mutex<Vec<Diagnostic>>,
+}
+
+impl DiagnosticSink {
+    /// Logs a diagnostic and collects it if collection is enabled.
+    ///
+    /// Without a logger, errors and warnings are printed to stderr as LLVM would print them.
+    ///
+    /// # Parameters
+    /// * `diagnostic` - The diagnostic reported by LLVM.
+    fn report(&self, diagnostic: Diagnostic) {
+        let logger = self.logger.lock().ok().and_then(|logger| logger.clone());
+        match logger {
+            Some(logger) => match diagnostic.severity.to_level() {
+                Level::Error => logging::core::log_error(&logger, &diagnostic.message),
+                Level::Warning => logging::core::log_warning(&logger, &diagnostic.message),
+                Level::Info => logging::core::log_info(&logger, &diagnostic.message),
+                _ => slog::debug!(logger, "{}", diagnostic.message),
+            },
+            None => {
+                if matches!(diagnostic.severity, DiagnosticSeverity::Error | DiagnosticSeverity::Warning) {
+                    eprintln!("{}", diagnostic);
+                }
+            }
+        }
+
+        if self.collect.lock().map(|collect| *collect).unwrap_or(false) {
+            if let Ok(mut diagnostics) = self.diagnostics.lock() {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    /// Sets the logger diagnostics are written to.
+    ///
+    /// # Parameters
+    /// * `logger` - The logger, or `None` to print errors and warnings to stderr.
+    pub(crate) fn set_logger(&self, logger: Option<Logger>) {
+        if let Ok(mut current) = self.logger.lock() {
+            *current = logger;
+        }
+    }
+
+    /// Enables or disables collection of diagnostics.
+    ///
+    /// # Parameters
+    /// * `enabled` - Whether diagnostics are stored for later retrieval.
+    pub(crate) fn set_collect(&self, enabled: bool) {
+        if let Ok(mut collect) = self.collect.lock() {
+            *collect = enabled;
+        }
+    }
+}
+
+/// Receives diagnostics from LLVM and forwards them to the context's sink.
+///
+/// # Parameters
+/// * `info` - The diagnostic being reported.
+/// * `sink` - The `DiagnosticSink` registered with the context.
+extern "C" fn handle_diagnostic(info: LLVMDiagnosticInfoRef, sink: *mut c_void) {
+    if info.is_null() || sink.is_null() {
+        return;
+    }
+
+    let diagnostic = unsafe {
+        let raw_description = core::LLVMGetDiagInfoDescription(info);
+        let message = CStr::from_ptr(raw_description).to_string_lossy().into_owned();
+        core::LLVMDisposeMessage(raw_description);
+        Diagnostic { severity: core::LLVMGetDiagInfoSeverity(info).into(), message }
+    };
+
+    let sink = unsafe { &*(sink as *const DiagnosticSink) };
+    sink.report(diagnostic);
+}
+
+/// Installs the diagnostic handler on a context.
+///
+/// The handler holds its own reference to the sink, which is never released, as the context is
+/// never disposed.
+///
+/// # Parameters
+/// * `context` - The context to install the handler on.
+/// * `sink` - The sink receiving the context's diagnostics.
+pub(crate) fn install_diagnostic_handler(context: LLVMContextRef, sink: &Arc<DiagnosticSink>) {
+    let sink_ptr = Arc::into_raw(Arc::clone(sink)) as *mut c_void;
+    unsafe { core::LLVMContextSetDiagnosticHandler(context, Some(handle_diagnostic), sink_ptr) };
+}
+
+impl IRManager {
+    /// Writes the diagnostics of every context, including contexts created later, to a logger.
+    ///
+    /// Errors and warnings are logged at the levels of the same name, notes as info records and
+    /// remarks as debug records.
+    ///
+    /// # Arguments
+    /// * `logger` - The logger to write diagnostics to.
+    pub fn set_diagnostic_logger(&mut self, logger: Logger) {
+        self.update_diagnostic_settings(Some(logger), self.is_collecting_diagnostics());
+    }
+
+    /// Stops logging diagnostics. Errors and warnings are printed to stderr instead.
+    pub fn clear_diagnostic_logger(&mut self) {
+        self.update_diagnostic_settings(None, self.is_collecting_diagnostics());
+    }
+
+    /// Enables or disables collection of the diagnostics of every context.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether diagnostics are stored for retrieval with `get_diagnostics`.
+    pub fn collect_diagnostics(&mut self, enabled: bool) {
+        let logger = self.get_diagnostic_logger().cloned();
+        self.update_diagnostic_settings(logger, enabled);
+    }
+
+    /// Applies diagnostic settings to every existing context and remembers them for new contexts.
+    ///
+    /// # Arguments
+    /// * `logger` - The logger to write diagnostics to, if any.
+    /// * `collect` - Whether diagnostics are collected.
+    fn update_diagnostic_settings(&mut self, logger: Option<Logger>, collect: bool) {
+        for sink in self.get_diagnostic_sinks() {
+            sink.set_logger(logger.clone());
+            sink.set_collect(collect);
+        }
+        self.set_diagnostic_settings(logger, collect);
+    }
+
+    /// Determines whether diagnostics are being collected.
+    ///
+    /// # Returns
+    /// True if diagnostics are collected, false otherwise.
+    pub fn is_collecting_diagnostics(&self) -> bool {
+        self.get_collect_diagnostics()
+    }
+
+    /// Retrieves the diagnostics collected for a context.
+    ///
+    /// # Arguments
+    /// * `tag` - The tag of the context.
+    ///
+    /// # Returns
+    /// An option containing the collected diagnostics in the order they were reported, or `None`
+    /// if the context does not exist or was not created by `create_context`.
+    pub fn get_diagnostics(&self, tag: ContextTag) -> Option<Vec<Diagnostic>> {
+        let sink = self.get_diagnostic_sink(tag)?;
+        let diagnostics = sink.diagnostics.lock().ok()?;
+        Some(diagnostics.clone())
+    }
+
+    /// Retrieves and discards the diagnostics collected for a context.
+    ///
+    /// # Arguments
+    /// * `tag` - The tag of the context.
+    ///
+    /// # Returns
+    /// An option containing the collected diagnostics in the order they were reported, or `None`
+    /// if the context does not exist or was not created by `create_context`.
+    pub fn take_diagnostics(&self, tag: ContextTag) -> Option<Vec<Diagnostic>> {
+        let sink = self.get_diagnostic_sink(tag)?;
+        let mut diagnostics = sink.diagnostics.lock().ok()?;
+        Some(std::mem::take(&mut *diagnostics))
+    }
+}
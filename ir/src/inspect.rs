@@ -0,0 +1,87 @@
+//! Naming and printing of raw LLVM objects.
+//!
+//! Values, blocks and types are reported as they appear in textual IR. These functions take raw
+//! pointers obtained from an IRManager, for example through `get_value_ptr`, or by walking a module
+//! with the LLVM C API.
+
+extern crate llvm_sys as llvm;
+use std::ffi::CStr;
+use llvm::{core, prelude::{LLVMBasicBlockRef, LLVMTypeRef, LLVMValueRef}};
+
+/// Retrieves the name of a value.
+///
+/// # Parameters
+/// * `value` - The value.
+///
+/// # Safety
+/// `value` must be a valid value whose context has not been disposed.
+///
+/// # Returns
+/// The name of the value, or an empty string for unnamed values.
+pub unsafe fn value_name(value: LLVMValueRef) -> String {
+    let mut length = 0;
+    let name_ptr = core::LLVMGetValueName2(value, &mut length);
+    if name_ptr.is_null() {
+        return String::new();
+    }
+    let bytes = std::slice::from_raw_parts(name_ptr as *const u8, length);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Names a basic block for display.
+///
+/// # Parameters
+/// * `block` - The basic block.
+///
+/// # Safety
+/// `block` must be a valid basic block whose context has not been disposed.
+///
+/// # Returns
+/// The name of the block, or `<block N>` for the N-th block of its function if it is unnamed.
+pub unsafe fn block_name(block: LLVMBasicBlockRef) -> String {
+    let name = CStr::from_ptr(core::LLVMGetBasicBlockName(block)).to_string_lossy().into_owned();
+    if !name.is_empty() {
+        return name;
+    }
+    let mut index = 0;
+    let mut current = core::LLVMGetFirstBasicBlock(core::LLVMGetBasicBlockParent(block));
+    while !current.is_null() && current != block {
+        index += 1;
+        current = core::LLVMGetNextBasicBlock(current);
+    }
+    format!("<block {}>", index)
+}
+
+/// Renders a value as it appears in textual IR.
+///
+/// # Parameters
+/// * `value` - The value to print.
+///
+/// # Safety
+/// `value` must be a valid value whose context has not been disposed.
+///
+/// # Returns
+/// The textual IR of the value, without surrounding whitespace.
+pub unsafe fn print_value(value: LLVMValueRef) -> String {
+    let raw_str = core::LLVMPrintValueToString(value);
+    let value_str = CStr::from_ptr(raw_str).to_string_lossy().trim().to_string();
+    core::LLVMDisposeMessage(raw_str);
+    value_str
+}
+
+/// Renders a type as it appears in textual IR.
+///
+/// # Parameters
+/// * `type_ref` - The type to print.
+///
+/// # Safety
+/// `type_ref` must be a valid type whose context has not been disposed.
+///
+/// # Returns
+/// The textual IR of the type.
+pub unsafe fn print_type(type_ref: LLVMTypeRef) -> String {
+    let raw_str = core::LLVMPrintTypeToString(type_ref);
+    let type_str = CStr::from_ptr(raw_str).to_string_lossy().into_owned();
+    core::LLVMDisposeMessage(raw_str);
+    type_str
+}
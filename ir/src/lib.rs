@@ -29,4 +29,15 @@ pub mod block;
 
 /// The core functionalities that are used across different modules of the IRGeneration
 /// toolchain.
-pub mod core;
\ No newline at end of file
+pub mod core;
+
+/// Opt-in tracing of every create and build call through the `logging` crate, recording the
+/// arguments, resulting tags and emitted IR of each call.
+pub mod trace;
+
+/// Routing of LLVM diagnostics from contexts created by the IRManager into the `logging` crate,
+/// with optional collection for retrieval by context tag.
+pub mod diagnostics;
+
+/// Naming and printing of raw LLVM values, basic blocks and types as they appear in textual IR.
+pub mod inspect;
@@ -0,0 +1,146 @@
+//! Tracing of IRManager operations.
+//!
+//! When tracing is enabled, every call that creates or builds an LLVM object is logged with its
+//! arguments, the tag it produced and the IR it emitted, and every call that fails is logged as an
+//! error. Reading the trace in order shows which call produced each instruction of a module.
+
+use slog::Logger;
+use crate::{core::{BasicBlockTag, BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag}, inspect::{block_name, print_type, print_value}};
+
+/// A result of an IRManager operation that can be described in a trace.
+pub(crate) trait TracedResult {
+    /// Describes the result, including the IR it refers to where there is any.
+    ///
+    /// # Parameters
+    /// * `manager` - The IRManager owning the result.
+    ///
+    /// # Returns
+    /// A human-readable description of the result.
+    fn describe(&self, manager: &IRManager) -> String;
+}
+
+impl TracedResult for ValueTag {
+    fn describe(&self, manager: &IRManager) -> String {
+        match manager.get_value_ptr(*self) {
+            Some(ptr) => format!("{:?}: {}", self, unsafe { print_value(ptr) }),
+            None => format!("{:?}", self),
+        }
+    }
+}
+
+impl TracedResult for TypeTag {
+    fn describe(&self, manager: &IRManager) -> String {
+        match manager.get_type_ptr(*self) {
+            Some(ptr) => format!("{:?}: {}", self, unsafe { print_type(ptr) }),
+            None => format!("{:?}", self),
+        }
+    }
+}
+
+impl TracedResult for BasicBlockTag {
+    fn describe(&self, manager: &IRManager) -> String {
+        match manager.get_basic_block_ptr(*self) {
+            Some(ptr) => format!("{:?}: {}:", self, unsafe { block_name(ptr) }),
+            None => format!("{:?}", self),
+        }
+    }
+}
+
+impl TracedResult for ContextTag {
+    fn describe(&self, _manager: &IRManager) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl TracedResult for ModuleTag {
+    fn describe(&self, _manager: &IRManager) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl TracedResult for BuilderTag {
+    fn describe(&self, _manager: &IRManager) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl TracedResult for () {
+    fn describe(&self, _manager: &IRManager) -> String {
+        "ok".to_string()
+    }
+}
+
+/// A traced IRManager operation in progress.
+///
+/// The operation is logged when it is finished with a result. If it is dropped without being
+/// finished, because the operation returned early, it is logged as a failure.
+pub(crate) struct TraceCall {
+    logger: Option<Logger>,
+    operation: &'static str,
+    arguments: String,
+}
+
+impl TraceCall {
+    /// Logs the outcome of the operation.
+    ///
+    /// # Parameters
+    /// * `result` - The result the operation returns.
+    /// * `manager` - The IRManager the operation was called on.
+    ///
+    /// # Returns
+    /// The unchanged `result`.
+    pub(crate) fn finish<T: TracedResult>(mut self, result: Option<T>, manager: &IRManager) -> Option<T> {
+        if let Some(logger) = self.logger.take() {
+            match &result {
+                Some(value) => logging::core::log_info(&logger, &format!("{}({}) -> {}", self.operation, self.arguments, value.describe(manager))),
+                None => logging::core::log_error(&logger, &format!("{}({}) failed", self.operation, self.arguments)),
+            }
+        }
+        result
+    }
+}
+
+impl Drop for TraceCall {
+    fn drop(&mut self) {
+        if let Some(logger) = self.logger.take() {
+            logging::core::log_error(&logger, &format!("{}({}) failed", self.operation, self.arguments));
+        }
+    }
+}
+
+impl IRManager {
+    /// Starts logging every create and build call to the given logger.
+    ///
+    /// # Parameters
+    /// * `logger` - The logger to write the trace to.
+    pub fn enable_trace(&mut self, logger: Logger) {
+        self.set_trace_logger(Some(logger));
+    }
+
+    /// Stops logging create and build calls.
+    pub fn disable_trace(&mut self) {
+        self.set_trace_logger(None);
+    }
+
+    /// Determines whether create and build calls are being traced.
+    ///
+    /// # Returns
+    /// True if tracing is enabled, false otherwise.
+    pub fn is_tracing(&self) -> bool {
+        self.get_trace_logger().is_some()
+    }
+
+    /// Starts tracing an operation.
+    ///
+    /// # Parameters
+    /// * `operation` - The name of the IRManager method being called.
+    /// * `arguments` - Renders the arguments of the call. Only invoked when tracing is enabled.
+    ///
+    /// # Returns
+    /// A `TraceCall` to finish with the operation's result.
+    pub(crate) fn begin_trace<A: FnOnce() -> String>(&self, operation: &'static str, arguments: A) -> TraceCall {
+        let logger = self.get_trace_logger().cloned();
+        let arguments = if logger.is_some() { arguments() } else { String::new() };
+        TraceCall { logger, operation, arguments }
+    }
+}
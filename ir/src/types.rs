@@ -18,6 +18,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created void type or None if the creation fails.
     pub fn void_type(&mut self, context_tag: ContextTag) -> Option<TypeTag> {
+        let trace = self.begin_trace("void_type", || format!("context_tag={context_tag:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let void_type = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -34,7 +35,7 @@ impl IRManager {
         if void_type.is_null() {
             None
         } else {
-            self.store_type(void_type)
+            trace.finish(self.store_type(void_type), self)
         }
     }
 
@@ -47,6 +48,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created integer type or None if the creation fails.
     pub fn int_type(&mut self, context_tag: ContextTag, bits: u32) -> Option<TypeTag> {
+        let trace = self.begin_trace("int_type", || format!("context_tag={context_tag:?}, bits={bits:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let int_type = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -63,7 +65,7 @@ impl IRManager {
         if int_type.is_null() {
             None
         } else {
-            self.store_type(int_type)
+            trace.finish(self.store_type(int_type), self)
         }
     }
 
@@ -75,6 +77,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created float type or None if the creation fails.
     pub fn float_type(&mut self, context_tag: ContextTag) -> Option<TypeTag> {
+        let trace = self.begin_trace("float_type", || format!("context_tag={context_tag:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let float_type = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -91,7 +94,7 @@ impl IRManager {
         if float_type.is_null() {
             None
         } else {
-            self.store_type(float_type)
+            trace.finish(self.store_type(float_type), self)
         }
     }
 
@@ -103,6 +106,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created boolean type or None if the creation fails.
     pub fn boolean_type(&mut self, context_tag: ContextTag) -> Option<TypeTag> {
+        let trace = self.begin_trace("boolean_type", || format!("context_tag={context_tag:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let boolean_type = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -119,7 +123,7 @@ impl IRManager {
         if boolean_type.is_null() {
             None
         } else {
-            self.store_type(boolean_type)
+            trace.finish(self.store_type(boolean_type), self)
         }
     }
 
@@ -131,6 +135,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created pointer type or None if the creation fails.
     pub fn pointer_type(&mut self, element_type_tag: TypeTag) -> Option<TypeTag> {
+        let trace = self.begin_trace("pointer_type", || format!("element_type_tag={element_type_tag:?}"));
         let element_type_arc_rwlock = self.get_type(element_type_tag)?;
         let pointer_type = {
             let element_type_rwlock = element_type_arc_rwlock.read().expect("Failed to lock type for reading");
@@ -147,7 +152,7 @@ impl IRManager {
         if pointer_type.is_null() {
             None
         } else {
-            self.store_type(pointer_type)
+            trace.finish(self.store_type(pointer_type), self)
         }
     }
 
@@ -160,6 +165,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created array type or None if the creation fails.
     pub fn array_type(&mut self, element_type_tag: TypeTag, num_elements: u64) -> Option<TypeTag> {
+        let trace = self.begin_trace("array_type", || format!("element_type_tag={element_type_tag:?}, num_elements={num_elements:?}"));
         let element_type_arc_rwlock = self.get_type(element_type_tag)?;
         let array_type = {
             let element_type_rwlock = element_type_arc_rwlock.read().expect("Failed to lock type for reading");
@@ -176,7 +182,7 @@ impl IRManager {
         if array_type.is_null() {
             None
         } else {
-            self.store_type(array_type)
+            trace.finish(self.store_type(array_type), self)
         }
     }
 
@@ -190,6 +196,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created struct type or None if the creation fails.
     pub fn struct_type(&mut self, context_tag: ContextTag, element_type_tags: &[TypeTag], packed: bool) -> Option<TypeTag> {
+        let trace = self.begin_trace("struct_type", || format!("context_tag={context_tag:?}, element_type_tags={element_type_tags:?}, packed={packed:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let mut element_types = {
             let mut types = Vec::new();
@@ -225,7 +232,7 @@ impl IRManager {
         if struct_type.is_null() {
             None
         } else {
-            self.store_type(struct_type)
+            trace.finish(self.store_type(struct_type), self)
         }
     }
 
@@ -237,6 +244,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created void return instruction or None if the creation fails.
     pub fn void_return(&mut self, builder_tag: BuilderTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("void_return", || format!("builder_tag={builder_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let void_return_inst = {
             let builder_rwlock = builder_arc_rwlock.read().expect("Failed to lock builder for reading");
@@ -253,7 +261,7 @@ impl IRManager {
         if void_return_inst.is_null() {
             None
         } else {
-            self.store_value(void_return_inst)
+            trace.finish(self.store_value(void_return_inst), self)
         }
     }
 
@@ -266,6 +274,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created return instruction or None if the creation fails.
     pub fn nonvoid_return(&mut self, builder_tag: BuilderTag, value_tag: ValueTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("nonvoid_return", || format!("builder_tag={builder_tag:?}, value_tag={value_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let value_arc_rwlock = self.get_value(value_tag)?;
 
@@ -297,7 +306,7 @@ impl IRManager {
         if nonvoid_return_inst.is_null() {
             None
         } else {
-            self.store_value(nonvoid_return_inst)
+            trace.finish(self.store_value(nonvoid_return_inst), self)
         }
     }
 
@@ -318,6 +327,7 @@ impl IRManager {
         is_var_arg: bool,
         context_tag: ContextTag,
     ) -> Option<TypeTag> {
+        let trace = self.begin_trace("create_function", || format!("return_type_tag={return_type_tag:?}, param_type_tags={param_type_tags:?}, is_var_arg={is_var_arg:?}, context_tag={context_tag:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
 
         let context_ptr = context_arc_rwlock.read().expect("Failed to lock context for reading").read(LLVMRefType::Context, |context_ref| {
@@ -367,7 +377,7 @@ impl IRManager {
             return None;
         }
 
-        self.store_type(function_type)
+        trace.finish(self.store_type(function_type), self)
     }
 
 
@@ -381,6 +391,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created struct type or None if the creation fails.
     pub fn create_struct(&mut self, context_tag: ContextTag, member_types: Vec<TypeTag>, packed: bool) -> Option<TypeTag> {
+        let trace = self.begin_trace("create_struct", || format!("context_tag={context_tag:?}, member_types={member_types:?}, packed={packed:?}"));
         let mut member_llvm_types: Vec<LLVMTypeRef> = member_types.iter()
             .map(|type_tag| {
                 let type_arc_rwlock = self.get_type(*type_tag)?;
@@ -417,7 +428,7 @@ impl IRManager {
         if struct_type.is_null() {
             None
         } else {
-            self.store_type(struct_type)
+            trace.finish(self.store_type(struct_type), self)
         }
     }
 
@@ -432,6 +443,7 @@ impl IRManager {
     /// # Returns
     /// Option<TypeTag> - The tag of the created enum type or None if the creation fails.
     pub fn create_enum(&mut self, context_tag: ContextTag, num_bits: u32, name: &str, variants: &[String]) -> Option<TypeTag> {
+        let trace = self.begin_trace("create_enum", || format!("context_tag={context_tag:?}, num_bits={num_bits:?}, name={name:?}, variants={variants:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let context_ptr = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -459,7 +471,7 @@ impl IRManager {
             self.store_enum_definition(type_tag, EnumDefinition::new(name.to_string(), variant_map));
 
 
-            Some(type_tag)
+            trace.finish(Some(type_tag), self)
         }
     }
 }
@@ -19,6 +19,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created integer constant or None if the creation fails.
     pub fn create_integer(&mut self, context_tag: ContextTag, val: i64) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_integer", || format!("context_tag={context_tag:?}, val={val:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let integer_value = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -37,7 +38,7 @@ impl IRManager {
         if integer_value.is_null() {
             None
         } else {
-            self.store_value(integer_value)
+            trace.finish(self.store_value(integer_value), self)
         }
     }
 
@@ -50,6 +51,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created floating-point constant or None if the creation fails.
     pub fn create_float(&mut self, context_tag: ContextTag, val: f64) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_float", || format!("context_tag={context_tag:?}, val={val:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let float_value = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -66,7 +68,7 @@ impl IRManager {
         if float_value.is_null() {
             None
         } else {
-            self.store_value(float_value)
+            trace.finish(self.store_value(float_value), self)
         }
     }
 
@@ -79,6 +81,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created boolean constant or None if the creation fails.
     pub fn create_boolean(&mut self, context_tag: ContextTag, val: bool) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_boolean", || format!("context_tag={context_tag:?}, val={val:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let boolean_value = {
             let context_rwlock = context_arc_rwlock.read().expect("Failed to lock context for reading");
@@ -95,7 +98,7 @@ impl IRManager {
         if boolean_value.is_null() {
             None
         } else {
-            self.store_value(boolean_value)
+            trace.finish(self.store_value(boolean_value), self)
         }
     }
 
@@ -108,6 +111,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created array or None if the creation fails.
     pub fn create_array(&mut self, value_tag: ValueTag, num_elements: u64) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_array", || format!("value_tag={value_tag:?}, num_elements={num_elements:?}"));
         let value_arc_rwlock = self.get_value(value_tag)?;
         
         let array_type = unsafe {
@@ -127,7 +131,7 @@ impl IRManager {
         if array_type.is_null() {
             None
         } else {
-            self.store_value(array_type)
+            trace.finish(self.store_value(array_type), self)
         }
     }
 
@@ -139,6 +143,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created pointer or None if the creation fails.
     pub fn create_pointer(&mut self, element_type_tag: TypeTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_pointer", || format!("element_type_tag={element_type_tag:?}"));
         let element_type_arc_rwlock = self.get_type(element_type_tag)?;
 
         let pointer_type = unsafe {
@@ -156,7 +161,7 @@ impl IRManager {
         if pointer_type.is_null() {
             None
         } else {
-            self.store_value(pointer_type)
+            trace.finish(self.store_value(pointer_type), self)
         }
     }
     
@@ -171,6 +176,7 @@ impl IRManager {
         &mut self,
         val: &str,
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_string", || format!("val={val:?}"));
 
         let c_val = CString::new(val).expect("Failed to create CString for string value");
 
@@ -180,7 +186,7 @@ impl IRManager {
         };
 
         if !str_pointer.is_null() {
-            return self.store_value(str_pointer);
+            return trace.finish(self.store_value(str_pointer), self);
         }
         
         
@@ -202,6 +208,7 @@ impl IRManager {
         context_tag: ContextTag,
         builder_tag: BuilderTag
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_mut_string", || format!("val={val:?}, context_tag={context_tag:?}, builder_tag={builder_tag:?}"));
         let context_arc_rwlock = self.get_context(context_tag)?;
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
 
@@ -241,7 +248,7 @@ impl IRManager {
         if local_str.is_null() {
             None
         } else {
-            self.store_value(local_str)
+            trace.finish(self.store_value(local_str), self)
         }
     }
 
@@ -253,6 +260,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the created null pointer or None if the creation fails.
     pub fn create_null_pointer(&mut self, ty_tag: TypeTag) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_null_pointer", || format!("ty_tag={ty_tag:?}"));
         let ty_arc_rwlock = self.get_type(ty_tag)?;
 
         let null_pointer = unsafe {
@@ -270,7 +278,7 @@ impl IRManager {
         if null_pointer.is_null() {
             None
         } else {
-            self.store_value(null_pointer)
+            trace.finish(self.store_value(null_pointer), self)
         }
     }
 
@@ -287,6 +295,7 @@ impl IRManager {
         builder_tag: BuilderTag,
         continue_block_tag: BasicBlockTag
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_continue_statement", || format!("builder_tag={builder_tag:?}, continue_block_tag={continue_block_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let continue_block_arc_rwlock = self.get_basic_block(continue_block_tag)?;
 
@@ -311,7 +320,7 @@ impl IRManager {
         };
 
         if let Some(continue_statement) = continue_statement {
-            self.store_value(continue_statement)
+            trace.finish(self.store_value(continue_statement), self)
         } else {
             None
         }
@@ -330,6 +339,7 @@ impl IRManager {
         builder_tag: BuilderTag,
         break_block_tag: BasicBlockTag
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("create_break_statement", || format!("builder_tag={builder_tag:?}, break_block_tag={break_block_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let break_block_arc_rwlock = self.get_basic_block(break_block_tag)?;
 
@@ -354,7 +364,7 @@ impl IRManager {
         };
 
         if let Some(break_statement) = break_statement {
-            self.store_value(break_statement)
+            trace.finish(self.store_value(break_statement), self)
         } else {
             None
         }
@@ -369,6 +379,7 @@ impl IRManager {
     /// # Returns
     /// Option<ValueTag> - The tag of the retrieved parameter or None if the parameter cannot be retrieved.
     pub fn get_param(&mut self, function_tag: ValueTag, index: u32) -> Option<ValueTag> {
+        let trace = self.begin_trace("get_param", || format!("function_tag={function_tag:?}, index={index:?}"));
         let function_arc_rwlock = self.get_value(function_tag)?;
         
         let param = {
@@ -387,7 +398,7 @@ impl IRManager {
         if param.is_null() {
             None
         } else {
-            self.store_value(param)
+            trace.finish(self.store_value(param), self)
         }
     }
 }
\ No newline at end of file
@@ -7,7 +7,7 @@ extern crate llvm_sys as llvm;
 use llvm::core;
 use std::ffi::CString;
 use common::pointer::{LLVMRef, LLVMRefType};
-use super::core::{BuilderTag, IRManager, TypeTag, ValueTag};
+use super::core::{BuilderTag, IRManager, ModuleTag, TypeTag, ValueTag};
 
 impl IRManager {
     /// Initializes a variable with an optional initial value in the specified builder context.
@@ -27,6 +27,7 @@ impl IRManager {
         data_type_tag: TypeTag, 
         initial_value_tag: Option<ValueTag>
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("init_var", || format!("builder_tag={builder_tag:?}, var_name={var_name:?}, data_type_tag={data_type_tag:?}, initial_value_tag={initial_value_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let data_type_arc_rwlock = self.get_type(data_type_tag)?;
 
@@ -78,7 +79,7 @@ impl IRManager {
                 }
             }
 
-            Some(alloca_tag)
+            trace.finish(Some(alloca_tag), self)
         }
     }
 
@@ -97,6 +98,7 @@ impl IRManager {
         variable_alloc_tag: ValueTag, 
         new_value_tag: ValueTag
     ) -> Option<()> {
+        let trace = self.begin_trace("reassign_var", || format!("builder_tag={builder_tag:?}, variable_alloc_tag={variable_alloc_tag:?}, new_value_tag={new_value_tag:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let variable_alloc_arc_rwlock = self.get_value(variable_alloc_tag)?;
         let new_value_arc_rwlock = self.get_value(new_value_tag)?;
@@ -129,7 +131,7 @@ impl IRManager {
             core::LLVMBuildStore(builder_ptr, new_value_ptr, variable_alloc_ptr);
         }
 
-        Some(())
+        trace.finish(Some(()), self)
     }
 
     /// Retrieves a variable's current value.
@@ -148,6 +150,7 @@ impl IRManager {
         variable_alloc_tag: ValueTag,
         temp_name: &str
     ) -> Option<ValueTag> {
+        let trace = self.begin_trace("get_var", || format!("builder_tag={builder_tag:?}, variable_type_tag={variable_type_tag:?}, variable_alloc_tag={variable_alloc_tag:?}, temp_name={temp_name:?}"));
         let builder_arc_rwlock = self.get_builder(builder_tag)?;
         let variable_type_arc_rwlock = self.get_type(variable_type_tag)?;
         let variable_alloc_arc_rwlock = self.get_value(variable_alloc_tag)?;
@@ -185,7 +188,130 @@ impl IRManager {
         if raw_ptr.is_null() {
             None
         } else {
-            self.store_value(raw_ptr)
+            trace.finish(self.store_value(raw_ptr), self)
         }
     }
-}
\ No newline at end of file
+
+    /// Allocates an array of variables whose length is only known at runtime.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - Tag of the builder to use for the allocation.
+    /// * `var_name` - Name of the array.
+    /// * `element_type_tag` - Type tag of the array elements.
+    /// * `count_tag` - Tag of the integer number of elements to allocate.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the pointer to the first element or None if the allocation fails.
+    pub fn init_array_var(
+        &mut self,
+        builder_tag: BuilderTag,
+        var_name: &str,
+        element_type_tag: TypeTag,
+        count_tag: ValueTag
+    ) -> Option<ValueTag> {
+        let trace = self.begin_trace("init_array_var", || format!("builder_tag={builder_tag:?}, var_name={var_name:?}, element_type_tag={element_type_tag:?}, count_tag={count_tag:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+        let element_type_ptr = self.get_type_ptr(element_type_tag)?;
+        let count_ptr = self.get_value_ptr(count_tag)?;
+
+        let var_name_cstr = CString::new(var_name).expect("Failed to create CString from var_name");
+
+        let alloca = unsafe {
+            core::LLVMBuildArrayAlloca(builder_ptr, element_type_ptr, count_ptr, var_name_cstr.as_ptr())
+        };
+
+        if alloca.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(alloca), self)
+        }
+    }
+
+    /// Computes the address of an element of an aggregate in memory.
+    ///
+    /// # Parameters
+    /// * `builder_tag` - Tag of the builder to use for the address computation.
+    /// * `pointee_type_tag` - Type tag of the value `pointer_tag` points to.
+    /// * `pointer_tag` - Tag of the base pointer.
+    /// * `index_tags` - Tags of the integer indices, the first of which steps over whole values of the pointee type.
+    /// * `in_bounds` - Whether the result is promised to stay within the object the base pointer points into.
+    /// * `temp_name` - Name of the resulting pointer.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the element pointer or None if the computation fails.
+    pub fn build_gep(
+        &mut self,
+        builder_tag: BuilderTag,
+        pointee_type_tag: TypeTag,
+        pointer_tag: ValueTag,
+        index_tags: &[ValueTag],
+        in_bounds: bool,
+        temp_name: &str
+    ) -> Option<ValueTag> {
+        let trace = self.begin_trace("build_gep", || format!("builder_tag={builder_tag:?}, pointee_type_tag={pointee_type_tag:?}, pointer_tag={pointer_tag:?}, index_tags={index_tags:?}, in_bounds={in_bounds:?}, temp_name={temp_name:?}"));
+        let builder_ptr = self.get_builder_ptr(builder_tag)?;
+        let pointee_type_ptr = self.get_type_ptr(pointee_type_tag)?;
+        let pointer_ptr = self.get_value_ptr(pointer_tag)?;
+        let mut index_ptrs = index_tags.iter().map(|index_tag| self.get_value_ptr(*index_tag)).collect::<Option<Vec<_>>>()?;
+
+        let temp_name_cstr = CString::new(temp_name).expect("Failed to create CString for temp_name");
+
+        let gep = unsafe {
+            if in_bounds {
+                core::LLVMBuildInBoundsGEP2(builder_ptr, pointee_type_ptr, pointer_ptr, index_ptrs.as_mut_ptr(), index_ptrs.len() as u32, temp_name_cstr.as_ptr())
+            } else {
+                core::LLVMBuildGEP2(builder_ptr, pointee_type_ptr, pointer_ptr, index_ptrs.as_mut_ptr(), index_ptrs.len() as u32, temp_name_cstr.as_ptr())
+            }
+        };
+
+        if gep.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(gep), self)
+        }
+    }
+
+    /// Adds a global variable to a module.
+    ///
+    /// # Parameters
+    /// * `module_tag` - Tag of the module to add the variable to.
+    /// * `var_name` - Name of the variable.
+    /// * `data_type_tag` - Type tag of the variable.
+    /// * `initial_value_tag` - Optional tag of the constant the variable is initialized with.
+    ///
+    /// # Returns
+    /// Option<ValueTag> - The tag of the global variable or None if it cannot be added.
+    pub fn init_global_var(
+        &mut self,
+        module_tag: ModuleTag,
+        var_name: &str,
+        data_type_tag: TypeTag,
+        initial_value_tag: Option<ValueTag>
+    ) -> Option<ValueTag> {
+        let trace = self.begin_trace("init_global_var", || format!("module_tag={module_tag:?}, var_name={var_name:?}, data_type_tag={data_type_tag:?}, initial_value_tag={initial_value_tag:?}"));
+        let module_ptr = self.get_module_ptr(module_tag)?;
+        let data_type_ptr = self.get_type_ptr(data_type_tag)?;
+        let initial_value_ptr = match initial_value_tag {
+            Some(value_tag) => Some(self.get_value_ptr(value_tag)?),
+            None => None,
+        };
+
+        let var_name_cstr = CString::new(var_name).expect("Failed to create CString from var_name");
+
+        let global = unsafe {
+            let global = core::LLVMAddGlobal(module_ptr, data_type_ptr, var_name_cstr.as_ptr());
+            if !global.is_null() {
+                if let Some(value_ptr) = initial_value_ptr {
+                    core::LLVMSetInitializer(global, value_ptr);
+                }
+            }
+            global
+        };
+
+        if global.is_null() {
+            None
+        } else {
+            trace.finish(self.store_value(global), self)
+        }
+    }
+}
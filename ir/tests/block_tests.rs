@@ -381,3 +381,37 @@ fn test_get_last_instruction() {
     let function = resource_pools.get_value(function_tag).expect("Failed to get function");
     assert!(validator.is_valid_function(function), "Invalid function");
 }
+
+#[test]
+fn test_create_switch_and_unreachable() {
+    let mut resource_pools = IRManager::new();
+
+    let context_tag = resource_pools.create_context().expect("Failed to create context");
+    let module_tag = resource_pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module within context");
+    let int_type = resource_pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let void_type = resource_pools.void_type(context_tag).expect("Failed to create void type");
+    let function_value = resource_pools.create_function(Some(void_type), &[int_type], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = resource_pools.add_function_to_module(module_tag, DEFAULT_FUNCTION_NAME, function_value).expect("Failed to add function to module");
+    let builder_tag = resource_pools.create_builder(context_tag).expect("Failed to create builder");
+    let entry_tag = resource_pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    let dead_tag = resource_pools.create_basic_block(context_tag, function_tag, "dead").expect("Failed to create block");
+    let exit_tag = resource_pools.create_basic_block(context_tag, function_tag, "exit").expect("Failed to create block");
+    let param_tag = resource_pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one = resource_pools.create_integer(context_tag, 1).expect("Failed to create integer");
+
+    resource_pools.position_builder_at_end(builder_tag, entry_tag).expect("Failed to position builder");
+    let switch_tag = resource_pools.create_switch(builder_tag, param_tag, exit_tag, &[(one, dead_tag)]).expect("Failed to create switch");
+    assert_eq!(resource_pools.get_last_instruction(entry_tag), Some(switch_tag), "The switch should terminate the entry block");
+
+    resource_pools.position_builder_at_end(builder_tag, dead_tag).expect("Failed to position builder");
+    resource_pools.create_unreachable(builder_tag).expect("Failed to create unreachable");
+    resource_pools.position_builder_at_end(builder_tag, exit_tag).expect("Failed to position builder");
+    resource_pools.void_return(builder_tag).expect("Failed to create return");
+
+    let module = resource_pools.get_module(module_tag).expect("Failed to get module");
+    let validator = Validator::new(module);
+    assert!(validator.is_valid_module(), "Invalid module");
+
+    let function = resource_pools.get_value(function_tag).expect("Failed to get function");
+    assert!(validator.is_valid_function(function), "Invalid function");
+}
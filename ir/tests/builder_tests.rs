@@ -77,3 +77,46 @@ fn test_create_function_with_params() {
     let function = llvm_resource_pool.get_value(function_tag).expect("Failed to get function");
     assert!(validator.is_valid_function(function), "Invalid function");
 }
+
+#[test]
+fn test_build_call_and_phi() {
+    let mut llvm_resource_pool = IRManager::new();
+    let context_tag = llvm_resource_pool.create_context().expect("Failed to create context");
+    let module_tag = llvm_resource_pool.create_module("test_call_and_phi", context_tag).expect("Failed to create module");
+    let int_type_tag = llvm_resource_pool.int_type(context_tag, 64).expect("Failed to create integer type");
+    let callee_type = llvm_resource_pool.create_function(Some(int_type_tag), &[int_type_tag], false, context_tag).expect("Failed to create function prototype");
+    let callee_tag = llvm_resource_pool.add_function_to_module(module_tag, "identity", callee_type).expect("Failed to add function to module");
+    let function_type = llvm_resource_pool.create_function(Some(int_type_tag), &[], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = llvm_resource_pool.add_function_to_module(module_tag, DEFAULT_FUNCTION_NAME, function_type).expect("Failed to add function to module");
+    let builder_tag = llvm_resource_pool.create_builder(context_tag).expect("Failed to create builder");
+
+    let callee_entry = llvm_resource_pool.create_basic_block(context_tag, callee_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    llvm_resource_pool.position_builder_at_end(builder_tag, callee_entry).expect("Failed to position builder");
+    let param_tag = llvm_resource_pool.get_param(callee_tag, 0).expect("Failed to get parameter");
+    llvm_resource_pool.nonvoid_return(builder_tag, param_tag).expect("Failed to create return");
+
+    let entry_tag = llvm_resource_pool.create_basic_block(context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    let other_tag = llvm_resource_pool.create_basic_block(context_tag, function_tag, "other").expect("Failed to create block");
+    let merge_tag = llvm_resource_pool.create_basic_block(context_tag, function_tag, "merge").expect("Failed to create block");
+    let condition = llvm_resource_pool.create_boolean(context_tag, true).expect("Failed to create boolean");
+    let argument = llvm_resource_pool.create_integer(context_tag, 7).expect("Failed to create integer");
+    let fallback = llvm_resource_pool.create_integer(context_tag, 0).expect("Failed to create integer");
+
+    llvm_resource_pool.position_builder_at_end(builder_tag, entry_tag).expect("Failed to position builder");
+    let call_tag = llvm_resource_pool.build_call(builder_tag, callee_type, callee_tag, &[argument], "call").expect("Failed to build call");
+    llvm_resource_pool.create_cond_br(builder_tag, condition, merge_tag, other_tag).expect("Failed to create branch");
+    llvm_resource_pool.position_builder_at_end(builder_tag, other_tag).expect("Failed to position builder");
+    llvm_resource_pool.create_br(builder_tag, merge_tag).expect("Failed to create branch");
+
+    llvm_resource_pool.position_builder_at_end(builder_tag, merge_tag).expect("Failed to position builder");
+    let phi_tag = llvm_resource_pool.build_phi(builder_tag, int_type_tag, "result").expect("Failed to build phi");
+    llvm_resource_pool.add_incoming(phi_tag, &[(call_tag, entry_tag), (fallback, other_tag)]).expect("Failed to add incoming values");
+    llvm_resource_pool.nonvoid_return(builder_tag, phi_tag).expect("Failed to create return");
+
+    let module = llvm_resource_pool.get_module(module_tag).expect("Failed to get module");
+    let validator = Validator::new(module);
+    assert!(validator.is_valid_module(), "Invalid module");
+
+    let function = llvm_resource_pool.get_value(function_tag).expect("Failed to get function");
+    assert!(validator.is_valid_function(function), "Invalid function");
+}
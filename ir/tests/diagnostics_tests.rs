@@ -0,0 +1,75 @@
+extern crate llvm_sys as llvm;
+use std::ffi::CString;
+use ir::{core::{ContextTag, IRManager, ModuleTag}, diagnostics::DiagnosticSeverity};
+use logging::capture::LogCapture;
+
+/// Creates a module containing a `void answer()` function that returns immediately.
+fn build_answer_module(pools: &mut IRManager, context_tag: ContextTag, module_name: &str) -> ModuleTag {
+    let module_tag = pools.create_module(module_name, context_tag).expect("Failed to create module");
+    let void_type = pools.void_type(context_tag).expect("Failed to create void type");
+    let function_type = pools.create_function(Some(void_type), &[], false, context_tag).expect("Failed to create function type");
+    let function_tag = pools.add_function_to_module(module_tag, "answer", function_type).expect("Failed to add function");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let entry_tag = pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    pools.position_builder_at_end(builder_tag, entry_tag).expect("Failed to position builder");
+    pools.void_return(builder_tag).expect("Failed to build return");
+    module_tag
+}
+
+#[test]
+fn test_linker_error_is_logged_and_collected() {
+    let capture = LogCapture::new();
+    let mut pools = IRManager::new();
+    pools.set_diagnostic_logger(capture.logger());
+    pools.collect_diagnostics(true);
+
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let first = build_answer_module(&mut pools, context_tag, "first");
+    let second = build_answer_module(&mut pools, context_tag, "second");
+
+    let first_ptr = pools.get_module_ptr(first).expect("Failed to get module pointer");
+    let second_ptr = pools.get_module_ptr(second).expect("Failed to get module pointer");
+    let failed = unsafe { llvm::linker::LLVMLinkModules2(first_ptr, second_ptr) };
+    assert_ne!(failed, 0, "Linking two definitions of the same symbol should fail");
+
+    capture.assert_error_logged("answer");
+    let diagnostics = pools.get_diagnostics(context_tag).expect("Context should have a diagnostic handler");
+    assert_eq!(diagnostics.len(), 1, "One diagnostic should be collected");
+    assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    assert!(diagnostics[0].message.contains("answer"), "The diagnostic should name the symbol: {}", diagnostics[0]);
+
+    assert_eq!(pools.take_diagnostics(context_tag).map(|diagnostics| diagnostics.len()), Some(1));
+    assert_eq!(pools.get_diagnostics(context_tag), Some(Vec::new()), "Taking diagnostics should clear them");
+}
+
+#[test]
+fn test_linker_warning_is_logged() {
+    let capture = LogCapture::new();
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    pools.set_diagnostic_logger(capture.logger());
+
+    let destination = pools.create_module("destination", context_tag).expect("Failed to create module");
+    let source = build_answer_module(&mut pools, context_tag, "source");
+    let layout = CString::new("e-m:e-i64:64-n32:64").expect("Failed to create CString");
+    let destination_ptr = pools.get_module_ptr(destination).expect("Failed to get module pointer");
+    let source_ptr = pools.get_module_ptr(source).expect("Failed to get module pointer");
+    unsafe { llvm::core::LLVMSetDataLayout(source_ptr, layout.as_ptr()) };
+
+    let failed = unsafe { llvm::linker::LLVMLinkModules2(destination_ptr, source_ptr) };
+    assert_eq!(failed, 0, "Linking modules with different data layouts should succeed");
+
+    capture.assert_warning_logged("different data layouts");
+    assert!(!pools.is_collecting_diagnostics(), "Diagnostics should not be collected by default");
+    assert_eq!(pools.get_diagnostics(context_tag), Some(Vec::new()), "Diagnostics should only be collected when enabled");
+}
+
+#[test]
+fn test_diagnostics_of_unknown_context() {
+    let mut other_pools = IRManager::new();
+    let foreign_tag = other_pools.create_context().expect("Failed to create context");
+    other_pools.create_context().expect("Failed to create context");
+
+    let pools = IRManager::new();
+    assert!(pools.get_diagnostics(foreign_tag).is_none(), "Unknown contexts should have no diagnostics");
+}
@@ -0,0 +1,46 @@
+use ir::{core::IRManager, inspect::{block_name, print_type, print_value, value_name}};
+
+#[test]
+fn test_inspect_values_blocks_and_types() {
+    let mut resource_pools = IRManager::new();
+    let context_tag = resource_pools.create_context().expect("Failed to create context");
+    let module_tag = resource_pools.create_module("inspected", context_tag).expect("Failed to create module");
+    let int_type = resource_pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = resource_pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = resource_pools.add_function_to_module(module_tag, "increment", function_type).expect("Failed to add function");
+    let builder_tag = resource_pools.create_builder(context_tag).expect("Failed to create builder");
+    let entry_tag = resource_pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    let exit_tag = resource_pools.create_basic_block(context_tag, function_tag, "").expect("Failed to create block");
+    resource_pools.position_builder_at_end(builder_tag, entry_tag).expect("Failed to position builder");
+    let param_tag = resource_pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one_tag = resource_pools.create_integer(context_tag, 1).expect("Failed to create integer");
+    let sum_tag = resource_pools.build_add(builder_tag, param_tag, one_tag, "sum").expect("Failed to build add");
+
+    assert!(resource_pools.get_module_ptr(module_tag).is_some());
+    assert!(resource_pools.get_context_ptr(context_tag).is_some());
+    assert!(resource_pools.get_builder_ptr(builder_tag).is_some());
+    assert!(resource_pools.get_type_ptr(function_type).is_some());
+
+    let sum_ptr = resource_pools.get_value_ptr(sum_tag).expect("Failed to get value pointer");
+    let entry_ptr = resource_pools.get_basic_block_ptr(entry_tag).expect("Failed to get block pointer");
+    let exit_ptr = resource_pools.get_basic_block_ptr(exit_tag).expect("Failed to get block pointer");
+    let type_ptr = resource_pools.get_type_ptr(function_type).expect("Failed to get type pointer");
+    unsafe {
+        assert_eq!(value_name(sum_ptr), "sum");
+        assert_eq!(print_value(sum_ptr), "%sum = add i64 %0, 1");
+        assert_eq!(value_name(resource_pools.get_value_ptr(one_tag).expect("Failed to get value pointer")), "", "Constants are unnamed");
+        assert_eq!(block_name(entry_ptr), "entry");
+        assert_eq!(block_name(exit_ptr), "<block 1>", "Unnamed blocks are named after their position");
+        assert_eq!(print_type(type_ptr), "i64 (i64)");
+    }
+
+    assert_eq!(resource_pools.get_or_store_value(sum_ptr), Some(sum_tag), "Stored values keep their tag");
+    assert_eq!(resource_pools.get_or_store_basic_block(entry_ptr), Some(entry_tag), "Stored blocks keep their tag");
+    let mut other_pools = IRManager::new();
+    let other_context_tag = other_pools.create_context().expect("Failed to create context");
+    let other_two_tag = other_pools.create_integer(other_context_tag, 2).expect("Failed to create integer");
+    let two_ptr = other_pools.get_value_ptr(other_two_tag).expect("Failed to get value pointer");
+    assert_eq!(resource_pools.get_value_tag(two_ptr), None);
+    let two_tag = resource_pools.get_or_store_value(two_ptr).expect("Failed to store value");
+    assert_eq!(resource_pools.get_or_store_value(two_ptr), Some(two_tag), "Values are stored only once");
+}
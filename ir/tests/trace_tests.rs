@@ -0,0 +1,64 @@
+use std::{fs, path::PathBuf};
+use ir::core::IRManager;
+use logging::config::{FileFormat, LoggerConfig};
+use slog::Logger;
+
+/// Builds a plain-text logger writing to a fresh file for a test.
+fn file_logger(name: &str) -> (Logger, PathBuf) {
+    let path = std::env::temp_dir().join(format!("safe_llvm_ir_trace_{}_{}.log", name, std::process::id()));
+    let logger = LoggerConfig::new().with_terminal(false).with_file(&path).with_file_format(FileFormat::Plain).build().expect("Failed to build logger");
+    (logger, path)
+}
+
+#[test]
+fn test_trace_records_calls_and_ir() {
+    let (logger, path) = file_logger("calls");
+    let mut resource_pools = IRManager::new();
+    resource_pools.enable_trace(logger);
+    assert!(resource_pools.is_tracing(), "Tracing should be enabled");
+
+    let context_tag = resource_pools.create_context().expect("Failed to create context");
+    let module_tag = resource_pools.create_module("traced", context_tag).expect("Failed to create module");
+    let int_type = resource_pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_type = resource_pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function type");
+    let function_tag = resource_pools.add_function_to_module(module_tag, "increment", function_type).expect("Failed to add function");
+    let builder_tag = resource_pools.create_builder(context_tag).expect("Failed to create builder");
+    let entry_tag = resource_pools.create_basic_block(context_tag, function_tag, "entry").expect("Failed to create block");
+    resource_pools.position_builder_at_end(builder_tag, entry_tag).expect("Failed to position builder");
+    let param_tag = resource_pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let one_tag = resource_pools.create_integer(context_tag, 1).expect("Failed to create integer");
+    resource_pools.build_add(builder_tag, param_tag, one_tag, "sum").expect("Failed to build add");
+    assert!(resource_pools.build_add(builder_tag, param_tag, foreign_value_tag(), "bad").is_none(), "Building with an unknown tag should fail");
+
+    drop(resource_pools);
+    let contents = fs::read_to_string(&path).expect("Failed to read trace");
+    assert!(contents.contains("create_function(return_type_tag=Some("), "Trace should record call arguments");
+    assert!(contents.contains("i64 (i64)"), "Trace should record created types");
+    assert!(contents.contains("%sum = add i64 %0, 1"), "Trace should record emitted instructions");
+    assert!(contents.contains("name=\"bad\") failed"), "Trace should record failed calls");
+    fs::remove_file(&path).expect("Failed to remove trace");
+}
+
+/// Creates a value tag in a separate IRManager. Tags are numbered in creation order across all
+/// kinds, so the returned tag numbers the module of `test_trace_records_calls_and_ir`, not a value.
+fn foreign_value_tag() -> ir::core::ValueTag {
+    let mut other_pools = IRManager::new();
+    let context_tag = other_pools.create_context().expect("Failed to create context");
+    other_pools.create_integer(context_tag, 0).expect("Failed to create integer")
+}
+
+#[test]
+fn test_disable_trace() {
+    let (logger, path) = file_logger("disabled");
+    let mut resource_pools = IRManager::new();
+    resource_pools.enable_trace(logger);
+    resource_pools.disable_trace();
+    assert!(!resource_pools.is_tracing(), "Tracing should be disabled");
+
+    resource_pools.create_context().expect("Failed to create context");
+    drop(resource_pools);
+
+    let contents = fs::read_to_string(&path).expect("Failed to read trace");
+    assert!(contents.is_empty(), "Nothing should be traced after tracing is disabled");
+    fs::remove_file(&path).expect("Failed to remove trace");
+}
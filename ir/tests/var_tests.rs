@@ -64,3 +64,29 @@ fn test_get_var() {
 
     assert!(retrieved_var_tag.is_some(), "Variable should be retrieved successfully");
 }
+
+
+#[test]
+fn test_array_var_gep_and_global_var() {
+    let mut resource_pools = IRManager::new();
+
+    let context_tag = resource_pools.create_context().expect("Failed to create context");
+    let module_tag = resource_pools.create_module("MyModule", context_tag).expect("Failed to create module");
+    let builder_tag = resource_pools.create_builder(context_tag).expect("Failed to create builder");
+    let int_type_tag = resource_pools.int_type(context_tag, 64).expect("Failed to create integer type");
+    let initial_value_tag = resource_pools.create_integer(context_tag, 42).expect("Failed to create initial value");
+    let count_tag = resource_pools.create_integer(context_tag, 4).expect("Failed to create count");
+
+    let global_tag = resource_pools.init_global_var(module_tag, "myGlobal", int_type_tag, Some(initial_value_tag)).expect("Global variable should be added successfully");
+    assert!(resource_pools.init_global_var(module_tag, "myDeclaration", int_type_tag, None).is_some(), "Global variables do not need an initializer");
+
+    let function_type_tag = resource_pools.create_function(Some(int_type_tag), &[], false, context_tag).expect("Failed to create function type");
+    let function_value_tag = resource_pools.add_function_to_module(module_tag, "testFunction", function_type_tag).expect("Failed to add function to module");
+    let block_tag = resource_pools.create_basic_block(context_tag, function_value_tag, "entry").expect("Failed to create basic block");
+    resource_pools.position_builder_at_end(builder_tag, block_tag).expect("Failed to position builder");
+
+    let array_tag = resource_pools.init_array_var(builder_tag, "myArray", int_type_tag, count_tag).expect("Array should be allocated successfully");
+    let element_tag = resource_pools.build_gep(builder_tag, int_type_tag, array_tag, &[count_tag], false, "element").expect("Element pointer should be computed successfully");
+    let global_value_tag = resource_pools.get_var(builder_tag, int_type_tag, global_tag, "tmp").expect("Failed to load global variable");
+    resource_pools.reassign_var(builder_tag, element_tag, global_value_tag).expect("Failed to store element");
+}
@@ -0,0 +1,336 @@
+//! A common interface over the ways a module can be executed.
+//!
+//! Both the MCJIT-backed `ExecutionEngine` and the `Interpreter` implement `ExecutionBackend`, so
+//! the same IRManager module can be run on either and the results compared. Values are passed in
+//! and out as `GenericValue`s, LLVM's dynamically typed representation of function arguments.
+
+extern crate llvm_sys as llvm;
+use std::ffi::c_void;
+use llvm::{core, execution_engine::{self, LLVMExecutionEngineRef, LLVMGenericValueRef}, prelude::{LLVMModuleRef, LLVMTypeRef, LLVMValueRef}, LLVMTypeKind};
+use ir::inspect::print_type;
+use crate::core::{find_defined_function, ExecutionEngine};
+
+/// The widest integer type LLVM can represent, in bits.
+const MAX_INT_BITS: u32 = 1 << 23;
+
+/// The kind of value held by a `GenericValue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenericValueKind {
+    /// An integer of the given bit width.
+    Int(u32),
+    /// A single-precision floating-point number.
+    Float,
+    /// A double-precision floating-point number.
+    Double,
+    /// A pointer.
+    Pointer,
+    /// The result of a function returning `void`.
+    Void,
+}
+
+impl GenericValueKind {
+    /// Determines the kind of value that represents the given LLVM type.
+    ///
+    /// # Parameters
+    /// * `type_ref` - The LLVM type.
+    ///
+    /// # Returns
+    /// An `Option` containing the kind, or `None` if the type cannot be passed as a `GenericValue`.
+    fn from_llvm_type(type_ref: LLVMTypeRef) -> Option<Self> {
+        unsafe {
+            match core::LLVMGetTypeKind(type_ref) {
+                LLVMTypeKind::LLVMIntegerTypeKind => Some(Self::Int(core::LLVMGetIntTypeWidth(type_ref))),
+                LLVMTypeKind::LLVMFloatTypeKind => Some(Self::Float),
+                LLVMTypeKind::LLVMDoubleTypeKind => Some(Self::Double),
+                LLVMTypeKind::LLVMPointerTypeKind => Some(Self::Pointer),
+                LLVMTypeKind::LLVMVoidTypeKind => Some(Self::Void),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// An owned LLVM generic value, disposed on drop.
+#[derive(Debug)]
+pub struct GenericValue {
+    value: LLVMGenericValueRef,
+    kind: GenericValueKind,
+}
+
+impl GenericValue {
+    /// Creates an integer value.
+    ///
+    /// # Parameters
+    /// * `bits` - The bit width of the integer, matching the LLVM parameter type.
+    /// * `value` - The value, truncated to `bits` bits.
+    /// * `signed` - Whether `value` should be treated as signed.
+    ///
+    /// # Returns
+    /// A `Result` containing the new `GenericValue`, or an error if `bits` is not a valid LLVM integer width.
+    pub fn from_int(bits: u32, value: u64, signed: bool) -> Result<Self, String> {
+        if bits == 0 || bits > MAX_INT_BITS {
+            return Err(format!("Invalid integer bit width {bits}; expected 1 to {MAX_INT_BITS}."));
+        }
+        let value = unsafe { execution_engine::LLVMCreateGenericValueOfInt(core::LLVMIntType(bits), value, signed as i32) };
+        Ok(Self {
+            value,
+            kind: GenericValueKind::Int(bits),
+        })
+    }
+
+    /// Creates a single-precision floating-point value.
+    ///
+    /// # Parameters
+    /// * `value` - The value.
+    ///
+    /// # Returns
+    /// A new `GenericValue` holding the float.
+    pub fn from_f32(value: f32) -> Self {
+        let value = unsafe { execution_engine::LLVMCreateGenericValueOfFloat(core::LLVMFloatType(), value as f64) };
+        Self {
+            value,
+            kind: GenericValueKind::Float,
+        }
+    }
+
+    /// Creates a double-precision floating-point value.
+    ///
+    /// # Parameters
+    /// * `value` - The value.
+    ///
+    /// # Returns
+    /// A new `GenericValue` holding the double.
+    pub fn from_f64(value: f64) -> Self {
+        let value = unsafe { execution_engine::LLVMCreateGenericValueOfFloat(core::LLVMDoubleType(), value) };
+        Self {
+            value,
+            kind: GenericValueKind::Double,
+        }
+    }
+
+    /// Creates a pointer value.
+    ///
+    /// # Parameters
+    /// * `pointer` - The pointer to pass to the executed code.
+    ///
+    /// # Returns
+    /// A new `GenericValue` holding the pointer.
+    ///
+    /// # Safety
+    /// The pointer must remain valid for as long as the executed code uses it.
+    pub unsafe fn from_pointer(pointer: *mut c_void) -> Self {
+        let value = execution_engine::LLVMCreateGenericValueOfPointer(pointer);
+        Self {
+            value,
+            kind: GenericValueKind::Pointer,
+        }
+    }
+
+    /// Retrieves the kind of value held.
+    ///
+    /// # Returns
+    /// The `GenericValueKind` of this value.
+    pub fn kind(&self) -> GenericValueKind {
+        self.kind
+    }
+
+    /// Reads the value as an unsigned integer, zero-extended to 64 bits.
+    ///
+    /// # Returns
+    /// The integer value, or zero if this is not an integer.
+    pub fn to_u64(&self) -> u64 {
+        match self.kind {
+            GenericValueKind::Int(_) => unsafe { execution_engine::LLVMGenericValueToInt(self.value, 0) },
+            _ => 0,
+        }
+    }
+
+    /// Reads the value as a signed integer, sign-extended to 64 bits.
+    ///
+    /// # Returns
+    /// The integer value, or zero if this is not an integer.
+    pub fn to_i64(&self) -> i64 {
+        match self.kind {
+            GenericValueKind::Int(_) => unsafe { execution_engine::LLVMGenericValueToInt(self.value, 1) as i64 },
+            _ => 0,
+        }
+    }
+
+    /// Reads the value as a floating-point number.
+    ///
+    /// # Returns
+    /// The value widened to `f64`, or zero if this is not a floating-point number.
+    pub fn to_f64(&self) -> f64 {
+        unsafe {
+            match self.kind {
+                GenericValueKind::Float => execution_engine::LLVMGenericValueToFloat(core::LLVMFloatType(), self.value),
+                GenericValueKind::Double => execution_engine::LLVMGenericValueToFloat(core::LLVMDoubleType(), self.value),
+                _ => 0.0,
+            }
+        }
+    }
+
+    /// Reads the value as a pointer.
+    ///
+    /// # Returns
+    /// The pointer, or null if this is not a pointer.
+    pub fn to_pointer(&self) -> *mut c_void {
+        match self.kind {
+            GenericValueKind::Pointer => unsafe { execution_engine::LLVMGenericValueToPointer(self.value) },
+            _ => std::ptr::null_mut(),
+        }
+    }
+}
+
+impl Drop for GenericValue {
+    fn drop(&mut self) {
+        unsafe { execution_engine::LLVMDisposeGenericValue(self.value) };
+    }
+}
+
+/// A way of executing the functions of an LLVM module.
+pub trait ExecutionBackend {
+    /// Runs a function with dynamically typed arguments.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to run.
+    /// * `args` - The arguments, one per parameter, each matching the parameter's LLVM type.
+    ///
+    /// # Returns
+    /// Returns `Ok(GenericValue)` containing the function's return value, or `Err(String)` if the
+    /// function could not be found or cannot be called with the given arguments on this backend.
+    fn run_function(&mut self, function_name: &str, args: &[GenericValue]) -> Result<GenericValue, String>;
+
+    /// Runs the module's `main` function as a whole program.
+    ///
+    /// # Parameters
+    /// * `args` - The command-line arguments passed as `argv`.
+    /// * `env` - The environment passed as `envp`, as key-value pairs.
+    ///
+    /// # Returns
+    /// Returns `Ok(i32)` containing the program's exit code, or `Err(String)` if the module has no
+    /// suitable `main` function.
+    fn run_main(&mut self, args: &[&str], env: &[(&str, &str)]) -> Result<i32, String>;
+}
+
+/// Checks that the given arguments match a function's parameters.
+///
+/// # Parameters
+/// * `function` - The LLVM function to be called.
+/// * `args` - The arguments to be passed.
+///
+/// # Returns
+/// Returns `Ok(GenericValueKind)` describing the function's return value, or `Err(String)`
+/// describing the first mismatch.
+fn check_arguments(function: LLVMValueRef, args: &[GenericValue]) -> Result<GenericValueKind, String> {
+    unsafe {
+        let function_type = core::LLVMGlobalGetValueType(function);
+        let param_count = core::LLVMCountParamTypes(function_type) as usize;
+        if param_count != args.len() {
+            return Err(format!("Function expects {} arguments but {} were given.", param_count, args.len()));
+        }
+
+        let mut param_types: Vec<LLVMTypeRef> = vec![std::ptr::null_mut(); param_count];
+        core::LLVMGetParamTypes(function_type, param_types.as_mut_ptr());
+        for (index, (param_type, arg)) in param_types.iter().zip(args).enumerate() {
+            if GenericValueKind::from_llvm_type(*param_type) != Some(arg.kind) {
+                return Err(format!("Argument {} has kind {:?} but the parameter has type {}.", index, arg.kind, print_type(*param_type)));
+            }
+        }
+
+        let return_type = core::LLVMGetReturnType(function_type);
+        GenericValueKind::from_llvm_type(return_type).ok_or_else(|| format!("Return type {} is not supported.", print_type(return_type)))
+    }
+}
+
+/// Runs a function on an LLVM execution engine once its arguments have been checked.
+///
+/// # Parameters
+/// * `engine_ptr` - The execution engine owning the function.
+/// * `function` - The function to run.
+/// * `args` - The arguments to pass.
+/// * `return_kind` - The kind of the function's return value.
+///
+/// # Returns
+/// The function's return value.
+fn run_checked_function(engine_ptr: LLVMExecutionEngineRef, function: LLVMValueRef, args: &[GenericValue], return_kind: GenericValueKind) -> GenericValue {
+    let mut arg_refs: Vec<LLVMGenericValueRef> = args.iter().map(|arg| arg.value).collect();
+    let value = unsafe { execution_engine::LLVMRunFunction(engine_ptr, function, arg_refs.len() as u32, arg_refs.as_mut_ptr()) };
+    GenericValue {
+        value,
+        kind: return_kind,
+    }
+}
+
+/// Runs a function through LLVM's generic calling interface.
+///
+/// # Parameters
+/// * `engine_ptr` - The execution engine owning the module.
+/// * `module_ptr` - The module defining the function.
+/// * `function_name` - The name of the function to run.
+/// * `args` - The arguments to pass.
+/// * `logger` - An optional logger used to report failures.
+/// * `supports_call` - Decides whether the backend can call a function with the given arguments.
+///
+/// # Returns
+/// Returns `Ok(GenericValue)` containing the return value, or `Err(String)` on failure.
+pub(crate) fn run_generic_function<F>(engine_ptr: LLVMExecutionEngineRef, module_ptr: LLVMModuleRef, function_name: &str, args: &[GenericValue], logger: Option<&slog::Logger>, supports_call: F) -> Result<GenericValue, String>
+where
+    F: FnOnce(&[GenericValue], GenericValueKind) -> Result<(), String>,
+{
+    let result = find_defined_function(module_ptr, function_name, logger).and_then(|function| {
+        let return_kind = check_arguments(function, args)?;
+        supports_call(args, return_kind)?;
+        Ok(run_checked_function(engine_ptr, function, args, return_kind))
+    });
+
+    if let Some(logger) = logger {
+        match &result {
+            Ok(_) => logging::core::log_info(logger, &format!("Function '{}' executed successfully.", function_name)),
+            Err(e) => logging::core::log_error(logger, &format!("Execution error: {}", e)),
+        }
+    }
+
+    result
+}
+
+/// Checks that MCJIT can call a function through `LLVMRunFunction`.
+///
+/// MCJIT only implements the generic calling interface for `main`-like signatures and for
+/// functions without parameters, and aborts the process for anything else, so unsupported calls
+/// are rejected up front. Other signatures can be called with `ExecutionEngine::execute`.
+///
+/// # Parameters
+/// * `args` - The arguments to be passed.
+/// * `return_kind` - The kind of the function's return value.
+///
+/// # Returns
+/// Returns `Ok(())` if the call is supported, or `Err(String)` otherwise.
+fn check_mcjit_call(args: &[GenericValue], return_kind: GenericValueKind) -> Result<(), String> {
+    let supported = if args.is_empty() {
+        matches!(return_kind, GenericValueKind::Int(1 | 8 | 16 | 32 | 64) | GenericValueKind::Float | GenericValueKind::Double | GenericValueKind::Pointer | GenericValueKind::Void)
+    } else {
+        let main_like_args = args.len() <= 3
+            && args[0].kind == GenericValueKind::Int(32)
+            && args[1..].iter().all(|arg| arg.kind == GenericValueKind::Pointer);
+        main_like_args && matches!(return_kind, GenericValueKind::Int(32) | GenericValueKind::Void)
+    };
+
+    if supported {
+        Ok(())
+    } else {
+        Err("MCJIT only supports calling functions without parameters or with main-like parameters through run_function; use execute instead.".to_string())
+    }
+}
+
+impl ExecutionBackend for ExecutionEngine {
+    fn run_function(&mut self, function_name: &str, args: &[GenericValue]) -> Result<GenericValue, String> {
+        let engine_ptr = self.get_engine_ptr()?;
+        let module_ptr = self.get_module_ptr()?;
+        run_generic_function(engine_ptr, module_ptr, function_name, args, self.get_logger(), check_mcjit_call)
+    }
+
+    fn run_main(&mut self, args: &[&str], env: &[(&str, &str)]) -> Result<i32, String> {
+        ExecutionEngine::run_main(self, args, env)
+    }
+}
@@ -0,0 +1,518 @@
+//! On-disk caching of compiled machine code.
+//!
+//! Each cached object is stored under a key derived from the module's bitcode together with the
+//! target triple, CPU, features, optimization level and LLVM version it was compiled for. The key is
+//! only a hash, so every entry also records the full description it was compiled from, and an entry
+//! is only reused if that description matches exactly.
+//! Engines created from the cache link the object with ORC LLJIT instead of compiling with MCJIT.
+
+extern crate llvm_sys as llvm;
+use std::{ffi::{c_char, CStr, CString}, fs, path::{Path, PathBuf}, sync::{atomic::{AtomicU64, Ordering}, Arc, RwLock}};
+use llvm::{bit_writer, core, orc2::lljit::{self, LLVMOrcLLJITRef}, prelude::LLVMModuleRef, target_machine::{self, LLVMCodeGenFileType, LLVMCodeGenOptLevel, LLVMCodeModel, LLVMRelocMode, LLVMTargetMachineRef}};
+use common::{pointer::SafeLLVMPointer, target::{GeneralTargetConfigurator, TargetConfigurator}};
+use ir::core::IRManager;
+use crate::{core::ExecutionEngine, object, orc};
+
+/// The file extension of cached objects.
+const OBJECT_EXTENSION: &str = "o";
+
+/// Bumped whenever the layout of cache keys or cached objects changes.
+const CACHE_FORMAT_VERSION: &str = "safe_llvm-object-cache-2";
+
+/// The optimization level cached objects are compiled with.
+const OPT_LEVEL: LLVMCodeGenOptLevel = LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault;
+
+/// Counts the temporary files written by this process, so that concurrent writes never share one.
+static TEMPORARY_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Computes the 64-bit FNV-1a hash of a byte string.
+///
+/// The hash is not collision resistant, which is why cache entries are verified against their
+/// full identity when they are loaded.
+///
+/// # Parameters
+/// * `bytes` - The bytes to hash.
+///
+/// # Returns
+/// The hash value.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME))
+}
+
+/// Computes the cache key of an entry from its identity.
+///
+/// # Parameters
+/// * `identity` - The identity returned by `HostTarget::cache_identity`.
+///
+/// # Returns
+/// The cache key, as a hexadecimal string.
+fn cache_key(identity: &[u8]) -> String {
+    format!("{:016x}-{:x}", fnv1a_64(identity), identity.len())
+}
+
+/// Takes ownership of a string allocated by LLVM.
+///
+/// # Parameters
+/// * `raw_str` - A string allocated by LLVM, which is disposed.
+///
+/// # Returns
+/// The string's contents.
+fn take_llvm_string(raw_str: *mut c_char) -> String {
+    unsafe {
+        let string = CStr::from_ptr(raw_str).to_string_lossy().into_owned();
+        core::LLVMDisposeMessage(raw_str);
+        string
+    }
+}
+
+/// Serializes a module to bitcode.
+///
+/// # Parameters
+/// * `module_ptr` - The module to serialize.
+///
+/// # Returns
+/// The module's bitcode.
+fn module_bitcode(module_ptr: LLVMModuleRef) -> Vec<u8> {
+    unsafe {
+        let buffer = bit_writer::LLVMWriteBitcodeToMemoryBuffer(module_ptr);
+        let bytes = std::slice::from_raw_parts(core::LLVMGetBufferStart(buffer) as *const u8, core::LLVMGetBufferSize(buffer)).to_vec();
+        core::LLVMDisposeMemoryBuffer(buffer);
+        bytes
+    }
+}
+
+/// The host target that modules are compiled for outside of MCJIT.
+pub(crate) struct HostTarget {
+    triple: String,
+    cpu: String,
+    features: String,
+    reloc_mode: LLVMRelocMode,
+}
+
+impl HostTarget {
+    /// Detects the host's target triple, CPU and CPU features.
+    ///
+    /// # Returns
+    /// A new instance of `HostTarget`.
+    pub(crate) fn detect() -> Self {
+        unsafe {
+            Self {
+                triple: take_llvm_string(target_machine::LLVMGetDefaultTargetTriple()),
+                cpu: take_llvm_string(target_machine::LLVMGetHostCPUName()),
+                features: take_llvm_string(target_machine::LLVMGetHostCPUFeatures()),
+                reloc_mode: LLVMRelocMode::LLVMRelocPIC,
+            }
+        }
+    }
+
+    /// Describes the target MCJIT compiles a module for, which is the module's own triple, or the
+    /// host triple if it has none, with a generic CPU and default relocations.
+    ///
+    /// # Parameters
+    /// * `module_ptr` - The module compiled by MCJIT.
+    ///
+    /// # Returns
+    /// A new instance of `HostTarget`.
+    pub(crate) fn mcjit_equivalent(module_ptr: LLVMModuleRef) -> Self {
+        let module_triple = unsafe { CStr::from_ptr(core::LLVMGetTarget(module_ptr)) }.to_string_lossy().into_owned();
+        let triple = if module_triple.is_empty() {
+            take_llvm_string(unsafe { target_machine::LLVMGetDefaultTargetTriple() })
+        } else {
+            module_triple
+        };
+
+        Self {
+            triple,
+            cpu: String::new(),
+            features: String::new(),
+            reloc_mode: LLVMRelocMode::LLVMRelocDefault,
+        }
+    }
+
+    /// Retrieves the target triple.
+    ///
+    /// # Returns
+    /// The target triple, such as `x86_64-unknown-linux-gnu`.
+    pub(crate) fn get_triple(&self) -> &str {
+        &self.triple
+    }
+
+    /// Describes everything an object compiled from a module for this target depends on.
+    ///
+    /// # Parameters
+    /// * `bitcode` - The module's bitcode.
+    ///
+    /// # Returns
+    /// The length-prefixed format version, LLVM version, target options and bitcode, which are
+    /// equal exactly when the compiled objects would be.
+    fn cache_identity(&self, bitcode: &[u8]) -> Vec<u8> {
+        let (mut major, mut minor, mut patch) = (0, 0, 0);
+        unsafe { core::LLVMGetVersion(&mut major, &mut minor, &mut patch) };
+        let llvm_version = format!("{}.{}.{}", major, minor, patch);
+        let opt_level = format!("{:?}", OPT_LEVEL);
+        let reloc_mode = format!("{:?}", self.reloc_mode);
+
+        let parts: [&[u8]; 8] = [
+            CACHE_FORMAT_VERSION.as_bytes(),
+            llvm_version.as_bytes(),
+            self.triple.as_bytes(),
+            self.cpu.as_bytes(),
+            self.features.as_bytes(),
+            opt_level.as_bytes(),
+            reloc_mode.as_bytes(),
+            bitcode,
+        ];
+        let mut identity = Vec::new();
+        for part in parts {
+            identity.extend_from_slice(&(part.len() as u64).to_le_bytes());
+            identity.extend_from_slice(part);
+        }
+        identity
+    }
+
+    /// Creates a target machine for this target.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMTargetMachineRef)` on success, or `Err(String)` if the target is not available.
+    fn create_target_machine(&self) -> Result<LLVMTargetMachineRef, String> {
+        let triple = CString::new(self.triple.as_str()).map_err(|_| "Failed to create CString for target triple.")?;
+        let cpu = CString::new(self.cpu.as_str()).map_err(|_| "Failed to create CString for CPU name.")?;
+        let features = CString::new(self.features.as_str()).map_err(|_| "Failed to create CString for CPU features.")?;
+
+        unsafe {
+            let mut target = std::ptr::null_mut();
+            let mut error: *mut c_char = std::ptr::null_mut();
+            if target_machine::LLVMGetTargetFromTriple(triple.as_ptr(), &mut target, &mut error) != 0 {
+                return Err(format!("Failed to find target for {}: {}", self.triple, take_llvm_string(error)));
+            }
+
+            Ok(target_machine::LLVMCreateTargetMachine(
+                target,
+                triple.as_ptr(),
+                cpu.as_ptr(),
+                features.as_ptr(),
+                OPT_LEVEL,
+                self.reloc_mode,
+                LLVMCodeModel::LLVMCodeModelJITDefault,
+            ))
+        }
+    }
+
+    /// Compiles a module to an object file for this target.
+    ///
+    /// The module is copied first, so compiling does not change the caller's module.
+    ///
+    /// # Parameters
+    /// * `module_ptr` - The module to compile.
+    ///
+    /// # Returns
+    /// Returns `Ok(Vec<u8>)` containing the object file, or `Err(String)` if compilation failed.
+    pub(crate) fn compile(&self, module_ptr: LLVMModuleRef) -> Result<Vec<u8>, String> {
+        let machine = self.create_target_machine()?;
+        let triple = CString::new(self.triple.as_str()).map_err(|_| "Failed to create CString for target triple.")?;
+
+        unsafe {
+            let module_copy = core::LLVMCloneModule(module_ptr);
+            core::LLVMSetTarget(module_copy, triple.as_ptr());
+
+            let mut error: *mut c_char = std::ptr::null_mut();
+            let mut buffer = std::ptr::null_mut();
+            let failed = target_machine::LLVMTargetMachineEmitToMemoryBuffer(machine, module_copy, LLVMCodeGenFileType::LLVMObjectFile, &mut error, &mut buffer) != 0;
+
+            core::LLVMDisposeModule(module_copy);
+            target_machine::LLVMDisposeTargetMachine(machine);
+
+            if failed {
+                return Err(format!("Failed to compile module: {}", take_llvm_string(error)));
+            }
+
+            let object = std::slice::from_raw_parts(core::LLVMGetBufferStart(buffer) as *const u8, core::LLVMGetBufferSize(buffer)).to_vec();
+            core::LLVMDisposeMemoryBuffer(buffer);
+            Ok(object)
+        }
+    }
+}
+
+/// A directory of compiled object files, keyed by module and target.
+#[derive(Debug, Clone)]
+pub struct ObjectCache {
+    directory: PathBuf,
+}
+
+impl ObjectCache {
+    /// Constructs a new `ObjectCache` stored in the given directory, creating it if necessary.
+    ///
+    /// # Parameters
+    /// * `directory` - The directory holding cached objects.
+    ///
+    /// # Returns
+    /// Returns `Ok(ObjectCache)` on success, or `Err(String)` if the directory could not be created.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Result<Self, String> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|e| format!("Failed to create cache directory {}: {}", directory.display(), e))?;
+        Ok(Self { directory })
+    }
+
+    /// Retrieves the directory holding cached objects.
+    ///
+    /// # Returns
+    /// The cache directory.
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Computes the key under which a module compiled for the host is cached.
+    ///
+    /// # Parameters
+    /// * `module` - A thread-safe `SafeLLVMPointer` containing an `LLVMModuleRef`.
+    ///
+    /// # Returns
+    /// Returns `Ok(String)` containing the key, or `Err(String)` if the module could not be read.
+    pub fn key_for_module(&self, module: &Arc<RwLock<SafeLLVMPointer>>) -> Result<String, String> {
+        let module_ptr = IRManager::read_module_ptr(module).ok_or("Invalid module pointer.")?;
+        Ok(cache_key(&HostTarget::detect().cache_identity(&module_bitcode(module_ptr))))
+    }
+
+    /// Determines the path of the object cached under a key.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    ///
+    /// # Returns
+    /// The path of the cached object file.
+    fn object_path(&self, key: &str) -> PathBuf {
+        self.directory.join(key).with_extension(OBJECT_EXTENSION)
+    }
+
+    /// Determines whether an object is cached under a key.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    ///
+    /// # Returns
+    /// True if a cached object exists for the key, false otherwise.
+    pub fn contains(&self, key: &str) -> bool {
+        self.object_path(key).is_file()
+    }
+
+    /// Loads the object cached under a key.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    ///
+    /// # Returns
+    /// An `Option` containing the object file, or `None` if nothing readable is cached under the key.
+    pub fn load(&self, key: &str) -> Option<Vec<u8>> {
+        self.load_entry(key).map(|(_, object)| object)
+    }
+
+    /// Loads the entry cached under a key.
+    ///
+    /// Entries consist of the length of the identity as a little-endian `u64`, the identity and the
+    /// object file.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    ///
+    /// # Returns
+    /// An `Option` containing the identity the object was compiled from and the object file, or
+    /// `None` if nothing readable is cached under the key.
+    fn load_entry(&self, key: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+        let mut entry = fs::read(self.object_path(key)).ok()?;
+        let header: [u8; 8] = entry.get(..8)?.try_into().ok()?;
+        let identity_end = usize::try_from(u64::from_le_bytes(header)).ok()?.checked_add(8)?;
+        if identity_end > entry.len() {
+            return None;
+        }
+        let object = entry.split_off(identity_end);
+        Some((entry.split_off(8), object))
+    }
+
+    /// Removes the object cached under a key, if any.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the cached object could not be removed.
+    pub fn remove(&self, key: &str) -> Result<(), String> {
+        let path = self.object_path(key);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove cached object {}: {}", path.display(), e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores an object under a key, replacing any object already cached under it.
+    ///
+    /// Objects stored this way are not associated with a module, so `with_object_cache` never
+    /// links them and recompiles the module instead.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    /// * `object` - The object file.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the object could not be written.
+    pub fn store(&self, key: &str, object: &[u8]) -> Result<(), String> {
+        self.store_entry(key, &[], object)
+    }
+
+    /// Stores an object together with the identity it was compiled from.
+    ///
+    /// The entry is written to a temporary file unique to this write and then renamed into place,
+    /// so concurrent readers and writers, in this process or another, never observe a partially
+    /// written entry.
+    ///
+    /// # Parameters
+    /// * `key` - The cache key.
+    /// * `identity` - The identity returned by `HostTarget::cache_identity`.
+    /// * `object` - The object file.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the entry could not be written.
+    fn store_entry(&self, key: &str, identity: &[u8], object: &[u8]) -> Result<(), String> {
+        let path = self.object_path(key);
+        let counter = TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temporary_path = self.directory.join(format!("{}.{}.{}.tmp", key, std::process::id(), counter));
+
+        let mut entry = Vec::with_capacity(8 + identity.len() + object.len());
+        entry.extend_from_slice(&(identity.len() as u64).to_le_bytes());
+        entry.extend_from_slice(identity);
+        entry.extend_from_slice(object);
+
+        fs::write(&temporary_path, entry).map_err(|e| format!("Failed to write cached object {}: {}", temporary_path.display(), e))?;
+        fs::rename(&temporary_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&temporary_path);
+            format!("Failed to store cached object {}: {}", path.display(), e)
+        })
+    }
+
+    /// Removes every cached object.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the cache directory could not be cleared.
+    pub fn clear(&self) -> Result<(), String> {
+        let entries = fs::read_dir(&self.directory).map_err(|e| format!("Failed to read cache directory {}: {}", self.directory.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|extension| extension == OBJECT_EXTENSION) {
+                fs::remove_file(&path).map_err(|e| format!("Failed to remove cached object {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ExecutionEngine {
+    /// Constructs a new `ExecutionEngine` that reuses machine code from an object cache.
+    ///
+    /// If an object for the module and host target is cached, it is linked directly; otherwise the
+    /// module is compiled and the object is added to the cache before being linked. A cached entry
+    /// that was compiled from a different module or target, or whose object cannot be parsed or
+    /// linked, is replaced by recompiling the module. Failing to write the cache is logged but not
+    /// fatal.
+    ///
+    /// The object is linked with ORC LLJIT rather than MCJIT, because MCJIT's object cache is not
+    /// available through the C API. The resulting engine supports `execute`, `get_function` and
+    /// `run_main`, but `run_function` returns an error, and so does `run_main` for modules with
+    /// static constructors or destructors. Use `ExecutionEngine::new` for those.
+    ///
+    /// # Parameters
+    /// * `module` - A thread-safe `SafeLLVMPointer` containing an `LLVMModuleRef`.
+    /// * `cache` - The object cache to read from and write to.
+    /// * `debug_info` - If true, records debugging information with the process-wide default logger.
+    ///
+    /// # Returns
+    /// Returns `Ok(ExecutionEngine)` on success, or `Err(String)` if the module could not be
+    /// compiled or linked.
+    pub fn with_object_cache(module: Arc<RwLock<SafeLLVMPointer>>, cache: &ObjectCache, debug_info: bool) -> Result<Self, String> {
+        GeneralTargetConfigurator.configure();
+
+        let logger = if debug_info {
+            Some(logging::core::shared_logger())
+        } else {
+            None
+        };
+
+        let module_ptr = IRManager::read_module_ptr(&module).ok_or("Invalid module pointer.")?;
+        let host = HostTarget::detect();
+        let identity = host.cache_identity(&module_bitcode(module_ptr));
+        let key = cache_key(&identity);
+
+        let cached_object = match cache.load_entry(&key) {
+            Some((cached_identity, object)) if cached_identity == identity => Some(object),
+            Some(_) => {
+                if let Some(logger) = &logger {
+                    logging::core::log_warning(logger, &format!("Cached object {} was compiled from a different module or target; recompiling.", key));
+                }
+                None
+            },
+            None => None,
+        };
+
+        if let Some(object) = cached_object {
+            match link_object(&object, &key) {
+                Ok(jit) => {
+                    if let Some(logger) = &logger {
+                        logging::core::log_info(logger, &format!("Loaded object {} from cache.", key));
+                    }
+                    return ExecutionEngine::from_lljit(jit, module, object, logger);
+                },
+                Err(e) => {
+                    if let Some(logger) = &logger {
+                        logging::core::log_warning(logger, &format!("Discarding corrupt cached object {}: {}", key, e));
+                    }
+                    if let Err(e) = cache.remove(&key) {
+                        if let Some(logger) = &logger {
+                            logging::core::log_warning(logger, &e);
+                        }
+                    }
+                },
+            }
+        }
+
+        let object = host.compile(module_ptr)?;
+        match cache.store_entry(&key, &identity, &object) {
+            Ok(()) => {
+                if let Some(logger) = &logger {
+                    logging::core::log_info(logger, &format!("Stored object {} in cache.", key));
+                }
+            },
+            Err(e) => {
+                if let Some(logger) = &logger {
+                    logging::core::log_warning(logger, &e);
+                }
+            },
+        }
+
+        let jit = link_object(&object, &key)?;
+        ExecutionEngine::from_lljit(jit, module, object, logger)
+    }
+}
+
+/// Creates an LLJIT instance and adds an object file to it.
+///
+/// The object is parsed before it is handed to LLJIT, so that a truncated or otherwise corrupt
+/// object is rejected here instead of failing when its symbols are first looked up.
+///
+/// # Parameters
+/// * `object` - The contents of the object file.
+/// * `name` - A name identifying the object in diagnostics.
+///
+/// # Returns
+/// Returns `Ok(LLVMOrcLLJITRef)` on success, or `Err(String)` if the object could not be parsed
+/// or added.
+fn link_object(object: &[u8], name: &str) -> Result<LLVMOrcLLJITRef, String> {
+    object::read_symbols(object)?;
+
+    let jit = orc::create_lljit()?;
+    if let Err(e) = orc::add_object_file(jit, object, name) {
+        let _ = orc::check_error(unsafe { lljit::LLVMOrcDisposeLLJIT(jit) });
+        return Err(e);
+    }
+    Ok(jit)
+}
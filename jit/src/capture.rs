@@ -0,0 +1,210 @@
+//! Capture of the standard output and standard error written by JIT-compiled code.
+//!
+//! Generated programs mostly communicate through `printf` and `puts`, which write straight to file
+//! descriptors 1 and 2 rather than through Rust's `std::io`. To observe that output, the descriptors
+//! are temporarily redirected into pipes for the duration of a call and restored afterwards.
+
+use std::{any::Any, fs::File, io::{Read, Write}, os::unix::io::FromRawFd, sync::Mutex, thread::{self, JoinHandle}};
+use crate::core::ExecutionEngine;
+
+/// Serializes redirections, since file descriptors 1 and 2 are shared by every thread in the process.
+static REDIRECT_LOCK: Mutex<()> = Mutex::new(());
+
+/// A pair of pipe file descriptors, closed on drop.
+pub(crate) struct Pipe {
+    pub(crate) read_fd: libc::c_int,
+    pub(crate) write_fd: libc::c_int,
+}
+
+impl Pipe {
+    /// Creates a new anonymous pipe.
+    ///
+    /// # Returns
+    /// Returns `Ok(Pipe)` on success, or `Err(String)` if the pipe could not be created.
+    pub(crate) fn new() -> Result<Self, String> {
+        let mut fds: [libc::c_int; 2] = [-1; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(format!("Failed to create pipe: {}", std::io::Error::last_os_error()));
+        }
+        Ok(Self {
+            read_fd: fds[0],
+            write_fd: fds[1],
+        })
+    }
+
+    /// Closes the write end and turns the read end into a `File` owned by the caller.
+    ///
+    /// # Returns
+    /// A `File` reading from the pipe.
+    pub(crate) fn into_reader(mut self) -> File {
+        unsafe { libc::close(self.write_fd) };
+        self.write_fd = -1;
+        let reader = unsafe { File::from_raw_fd(self.read_fd) };
+        self.read_fd = -1;
+        reader
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        unsafe {
+            if self.read_fd >= 0 {
+                libc::close(self.read_fd);
+            }
+            if self.write_fd >= 0 {
+                libc::close(self.write_fd);
+            }
+        }
+    }
+}
+
+/// Spawns a thread that reads a pipe until end of file.
+///
+/// Reading on a separate thread prevents a chatty writer from blocking on a full pipe while the
+/// caller waits for it to finish.
+///
+/// # Parameters
+/// * `reader` - The read end of the pipe.
+///
+/// # Returns
+/// A handle resolving to the captured text.
+pub(crate) fn spawn_reader(mut reader: File) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// Flushes both Rust's and C's buffered standard streams.
+fn flush_all() {
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    unsafe { libc::fflush(std::ptr::null_mut()) };
+}
+
+/// A file descriptor temporarily redirected into a pipe.
+///
+/// The original descriptor is restored by `finish`, or on drop if the redirect is abandoned early.
+struct Redirect {
+    fd: libc::c_int,
+    saved_fd: libc::c_int,
+    reader: Option<JoinHandle<String>>,
+}
+
+impl Redirect {
+    /// Redirects a file descriptor into a new pipe.
+    ///
+    /// # Parameters
+    /// * `fd` - The file descriptor to redirect, such as `STDOUT_FILENO`.
+    ///
+    /// # Returns
+    /// Returns `Ok(Redirect)` on success, or `Err(String)` if the descriptor could not be redirected.
+    fn start(fd: libc::c_int) -> Result<Self, String> {
+        let pipe = Pipe::new()?;
+
+        let saved_fd = unsafe { libc::dup(fd) };
+        if saved_fd < 0 {
+            return Err(format!("Failed to duplicate file descriptor {}: {}", fd, std::io::Error::last_os_error()));
+        }
+        if unsafe { libc::dup2(pipe.write_fd, fd) } < 0 {
+            unsafe { libc::close(saved_fd) };
+            return Err(format!("Failed to redirect file descriptor {}: {}", fd, std::io::Error::last_os_error()));
+        }
+
+        Ok(Self {
+            fd,
+            saved_fd,
+            reader: Some(spawn_reader(pipe.into_reader())),
+        })
+    }
+
+    /// Points the file descriptor back at its original target, if it has not been restored yet.
+    fn restore(&mut self) {
+        if self.saved_fd >= 0 {
+            unsafe {
+                libc::dup2(self.saved_fd, self.fd);
+                libc::close(self.saved_fd);
+            }
+            self.saved_fd = -1;
+        }
+    }
+
+    /// Restores the original file descriptor and collects everything written to the pipe.
+    ///
+    /// # Returns
+    /// Returns `Ok(String)` containing the captured text, or `Err(String)` if it could not be read.
+    fn finish(mut self) -> Result<String, String> {
+        self.restore();
+        let reader = self.reader.take().ok_or_else(|| format!("Output of file descriptor {} was already collected.", self.fd))?;
+        reader.join().map_err(|_| format!("Failed to capture output of file descriptor {}.", self.fd))
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        // Restoring closes the last write end of the pipe, so a detached reader thread still terminates
+        self.restore();
+    }
+}
+
+/// The result of a function executed with its output captured.
+#[derive(Debug, Clone)]
+pub struct CapturedExecution<ReturnType> {
+    /// The function's return value.
+    pub return_value: ReturnType,
+    /// Everything the function wrote to standard output.
+    pub stdout: String,
+    /// Everything the function wrote to standard error.
+    pub stderr: String,
+}
+
+impl ExecutionEngine {
+    /// Executes a specified function within the module, capturing its standard output and standard error.
+    ///
+    /// File descriptors 1 and 2 are redirected for the whole process while the function runs, so
+    /// output written by other threads during that window is captured as well. Concurrent captures
+    /// are serialized.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to be executed.
+    /// * `args` - The arguments passed to the function.
+    ///
+    /// # Returns
+    /// Returns `Ok(CapturedExecution)` containing the return value and the captured output, or
+    /// `Err(String)` if the function could not be found or the output could not be redirected.
+    pub fn execute_captured<ReturnType, ArgType>(&mut self, function_name: &str, args: ArgType) -> Result<CapturedExecution<ReturnType>, String>
+    where
+        ReturnType: 'static,
+        ArgType: Any + Send + Sync,
+    {
+        let function_address = self.get_function_address(function_name)?;
+
+        let _guard = REDIRECT_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        flush_all();
+        // Both redirects restore their descriptor when dropped, so every early return leaves 1 and 2 intact
+        let stdout_redirect = Redirect::start(libc::STDOUT_FILENO)?;
+        let stderr_redirect = Redirect::start(libc::STDERR_FILENO)?;
+
+        let return_value = unsafe {
+            let func: extern "C" fn(ArgType) -> ReturnType = std::mem::transmute(function_address);
+            func(args)
+        };
+
+        flush_all();
+        let stdout = stdout_redirect.finish();
+        let stderr = stderr_redirect.finish();
+        let (stdout, stderr) = (stdout?, stderr?);
+
+        if let Some(logger) = self.get_logger() {
+            logging::core::log_info(logger, &format!("Function '{}' executed successfully with {} bytes of captured output.", function_name, stdout.len() + stderr.len()));
+        }
+
+        Ok(CapturedExecution {
+            return_value,
+            stdout,
+            stderr,
+        })
+    }
+}
@@ -1,15 +1,29 @@
 //! The ExecutionEngine class manages the initialization and operation of an LLVM execution engine, along with its context and module.
 
 extern crate llvm_sys as llvm;
-use llvm::{core, execution_engine};
+use llvm::{core, execution_engine, orc2::lljit::LLVMOrcLLJITRef, prelude::{LLVMModuleRef, LLVMTypeRef, LLVMValueRef}, LLVMTypeKind};
 use std::{any::Any, ffi::{c_char, CStr, CString}, sync::{Arc, RwLock}};
 use slog::Logger;
-use common::{pointer::{LLVMRef, LLVMRefType, SafeLLVMPointer}, target::{GeneralTargetConfigurator, TargetConfigurator}};
+use common::{constants::DEFAULT_FUNCTION_NAME, pointer::{LLVMRef, LLVMRefType, SafeLLVMPointer}, target::{GeneralTargetConfigurator, TargetConfigurator}};
+use ir::core::IRManager;
+use crate::orc;
+
+/// The LLVM JIT implementation backing an `ExecutionEngine`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EngineBackend {
+    /// MCJIT, which compiles the module when it is first used.
+    Mcjit,
+    /// ORC LLJIT, which links a precompiled object file of the module.
+    Lljit,
+}
 
 /// Represents an LLVM execution engine for a multi-threaded environment.
 /// This struct encapsulates all necessary LLVM components: context, module, and execution engine.
 pub struct ExecutionEngine {
     engine: Arc<RwLock<SafeLLVMPointer>>,
+    backend: EngineBackend,
+    module: Arc<RwLock<SafeLLVMPointer>>,
+    object: Option<Vec<u8>>,
     logger: Option<Logger>,
 }
 
@@ -21,7 +35,7 @@ impl ExecutionEngine {
     ///
     /// # Parameters
     /// * `module` - A thread-safe `SafeLLVMPointer` containing an `LLVMModuleRef`.
-    /// * `debug_info` - If true, initializes a logger to record debugging information.
+    /// * `debug_info` - If true, records debugging information with the process-wide default logger.
     ///
     /// # Returns
     /// A new instance of `ExecutionEngine`.
@@ -51,21 +65,60 @@ impl ExecutionEngine {
                 panic!("Module pointer is not correctly retrieved.");
             }
         });
+        drop(module_rw_lock);
 
         let engine_cptr = SafeLLVMPointer::new(LLVMRef::ExecutionEngine(engine_ref)).expect("Engine cannot be null");
 
         let logger = if debug_info {
-            Some(logging::core::init_logger())
+            Some(logging::core::shared_logger())
         } else {
             None
         };
 
         Self {
             engine: Arc::new(RwLock::new(engine_cptr)),
+            backend: EngineBackend::Mcjit,
+            module,
+            object: None,
             logger,
         }
     }
 
+    /// Constructs an `ExecutionEngine` around an LLJIT instance that has already been given an
+    /// object file of the module.
+    ///
+    /// # Parameters
+    /// * `jit` - The LLJIT instance.
+    /// * `module` - The module the object file was compiled from, used for type information.
+    /// * `object` - The object file linked into `jit`.
+    /// * `logger` - An optional logger for debugging information.
+    ///
+    /// # Returns
+    /// Returns `Ok(ExecutionEngine)` on success, or `Err(String)` if `jit` is null.
+    pub(crate) fn from_lljit(jit: LLVMOrcLLJITRef, module: Arc<RwLock<SafeLLVMPointer>>, object: Vec<u8>, logger: Option<Logger>) -> Result<Self, String> {
+        let engine_cptr = SafeLLVMPointer::new(LLVMRef::Lljit(jit)).ok_or("Engine cannot be null")?;
+
+        Ok(Self {
+            engine: Arc::new(RwLock::new(engine_cptr)),
+            backend: EngineBackend::Lljit,
+            module,
+            object: Some(object),
+            logger,
+        })
+    }
+
+    /// Replaces the logger that records debugging information.
+    ///
+    /// # Parameters
+    /// * `logger` - The logger to write to, for example one built from a `logging::config::LoggerConfig`.
+    ///
+    /// # Returns
+    /// The updated `ExecutionEngine`.
+    pub fn with_logger(mut self, logger: Logger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
     /// Configures the LLVM execution engine using a specified target configurator.
     ///
     /// # Parameters
@@ -82,6 +135,127 @@ impl ExecutionEngine {
         Ok(())
     }
 
+    /// Looks up the address of a compiled function within the module.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to be looked up.
+    ///
+    /// # Returns
+    /// Returns `Ok(u64)` containing the address of the compiled function, or `Err(String)` if the
+    /// function could not be found in the module.
+    pub(crate) fn get_function_address(&self, function_name: &str) -> Result<u64, String> {
+        let function_address = match self.backend {
+            EngineBackend::Mcjit => {
+                let engine_ptr = self.get_engine_ptr()?;
+                let function_name_c = CString::new(function_name).map_err(|_| "Failed to create CString for function name.")?;
+                unsafe { execution_engine::LLVMGetFunctionAddress(engine_ptr, function_name_c.as_ptr()) }
+            },
+            EngineBackend::Lljit => {
+                let module_ptr = self.get_module_ptr()?;
+                find_defined_function(module_ptr, function_name, self.logger.as_ref())?;
+                orc::lookup(self.get_lljit_ptr()?, function_name).unwrap_or(0)
+            },
+        };
+
+        if function_address == 0 {
+            if let Some(logger) = &self.logger {
+                logging::core::log_warning(logger, &format!("Function \"{}\" not found.", function_name));
+            }
+            return Err("Function not found in given module.".to_string());
+        }
+
+        Ok(function_address)
+    }
+
+    /// Looks up the address of a global variable within the compiled module.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    ///
+    /// # Returns
+    /// Returns `Ok(u64)` containing the address of the global, which is zero if the compiled
+    /// module does not define it, or `Err(String)` if the engine could not be accessed.
+    pub(crate) fn get_global_address(&self, name: &str) -> Result<u64, String> {
+        match self.backend {
+            EngineBackend::Mcjit => {
+                let engine_ptr = self.get_engine_ptr()?;
+                let name_c = CString::new(name).map_err(|_| "Failed to create CString for global name.")?;
+                Ok(unsafe { execution_engine::LLVMGetGlobalValueAddress(engine_ptr, name_c.as_ptr()) })
+            },
+            EngineBackend::Lljit => Ok(orc::lookup(self.get_lljit_ptr()?, name).unwrap_or(0)),
+        }
+    }
+
+    /// Retrieves the raw LLVM execution engine.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMExecutionEngineRef)` on success, or `Err(String)` if the engine lock could not be obtained.
+    pub(crate) fn get_engine_ptr(&self) -> Result<execution_engine::LLVMExecutionEngineRef, String> {
+        if self.backend != EngineBackend::Mcjit {
+            return Err("This operation needs MCJIT, but engines created with `with_object_cache` or `with_event_listeners` link a precompiled object with ORC LLJIT; create the engine with `ExecutionEngine::new` instead.".to_string());
+        }
+        let engine_lock = self.engine.try_read().map_err(|e| format!("Failed to obtain read lock on engine: {}", e))?;
+        engine_lock.read(LLVMRefType::ExecutionEngine, |engine_ref| {
+            if let LLVMRef::ExecutionEngine(engine_ptr) = engine_ref {
+                Ok(*engine_ptr)
+            } else {
+                Err("Invalid engine pointer.".to_string())
+            }
+        })
+    }
+
+    /// Retrieves the raw LLJIT instance of an engine loaded from an object cache.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMOrcLLJITRef)` on success, or `Err(String)` if the engine is not backed by LLJIT.
+    fn get_lljit_ptr(&self) -> Result<LLVMOrcLLJITRef, String> {
+        if self.backend != EngineBackend::Lljit {
+            return Err("Engine is not backed by LLJIT.".to_string());
+        }
+        let engine_lock = self.engine.try_read().map_err(|e| format!("Failed to obtain read lock on engine: {}", e))?;
+        engine_lock.read(LLVMRefType::Lljit, |engine_ref| {
+            if let LLVMRef::Lljit(jit_ptr) = engine_ref {
+                Ok(*jit_ptr)
+            } else {
+                Err("Invalid engine pointer.".to_string())
+            }
+        })
+    }
+
+    /// Retrieves the raw LLVM module executed by this engine.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMModuleRef)` on success, or `Err(String)` if the module lock could not be obtained.
+    pub(crate) fn get_module_ptr(&self) -> Result<LLVMModuleRef, String> {
+        IRManager::read_module_ptr(&self.module).ok_or_else(|| "Invalid module pointer.".to_string())
+    }
+
+    /// Retrieves shared ownership of the engine and its module.
+    ///
+    /// Holding these keeps the compiled code alive independently of this `ExecutionEngine`.
+    ///
+    /// # Returns
+    /// A tuple containing the engine pointer and the module pointer.
+    pub(crate) fn get_shared_pointers(&self) -> (Arc<RwLock<SafeLLVMPointer>>, Arc<RwLock<SafeLLVMPointer>>) {
+        (Arc::clone(&self.engine), Arc::clone(&self.module))
+    }
+
+    /// Retrieves the object file linked into an engine backed by LLJIT.
+    ///
+    /// # Returns
+    /// An `Option` containing the object file, or `None` for engines compiling with MCJIT.
+    pub(crate) fn get_object(&self) -> Option<&[u8]> {
+        self.object.as_deref()
+    }
+
+    /// Retrieves the logger attached to this engine, if debugging information is enabled.
+    ///
+    /// # Returns
+    /// An `Option` containing a reference to the engine's `Logger`.
+    pub(crate) fn get_logger(&self) -> Option<&Logger> {
+        self.logger.as_ref()
+    }
+
     /// Executes a specified function within the module.
     ///
     /// # Parameters
@@ -96,25 +270,11 @@ impl ExecutionEngine {
         ReturnType: 'static, 
         ArgType: Any + Send + Sync, 
     {
-        let engine_lock = self.engine.try_read().map_err(|e| format!("Failed to obtain read lock on engine: {}", e))?;
-        let result = engine_lock.write(LLVMRefType::ExecutionEngine, |engine_ref| {
-            if let LLVMRef::ExecutionEngine(engine_ptr) = engine_ref {
-                let function_name_c = CString::new(function_name).map_err(|_| "Failed to create CString for function name.")?;
-                let function_address = unsafe { execution_engine::LLVMGetFunctionAddress(*engine_ptr, function_name_c.as_ptr()) };
-                
-                if function_address == 0 {
-                    if let Some(logger) = &self.logger {
-                        logging::core::log_warning(&logger, &format!("Function \"{}\" not found.", function_name));
-                    }
-                    return Err("Function not found in given module.".to_string());
-                }
-
-                unsafe {
-                    let func: extern "C" fn(ArgType) -> ReturnType = std::mem::transmute(function_address);
-                    Ok(func(args))
-                }
-            } else {
-                Err("Invalid engine pointer.".to_string())
+        let _span = logging::spans::jit_execution(function_name);
+        let result = self.get_function_address(function_name).map(|function_address| {
+            unsafe {
+                let func: extern "C" fn(ArgType) -> ReturnType = std::mem::transmute(function_address);
+                func(args)
             }
         });
 
@@ -133,4 +293,217 @@ impl ExecutionEngine {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Runs the module's `main` function as a whole program.
+    ///
+    /// Static constructors are run before `main` and static destructors after it returns, mirroring
+    /// how a native C program starts up and shuts down. `main` may take no parameters, `(int argc)`,
+    /// `(int argc, char **argv)` or `(int argc, char **argv, char **envp)`, and must return `int` or `void`.
+    ///
+    /// # Parameters
+    /// * `args` - The command-line arguments passed as `argv`, conventionally starting with the program name.
+    /// * `env` - The environment passed as `envp`, as key-value pairs.
+    ///
+    /// Engines created with `with_object_cache` or `with_event_listeners` cannot run static
+    /// constructors or destructors, and return an error for modules that declare them.
+    ///
+    /// # Returns
+    /// Returns `Ok(i32)` containing the program's exit code, or `Err(String)` if the module has no
+    /// suitable `main` function.
+    pub fn run_main(&mut self, args: &[&str], env: &[(&str, &str)]) -> Result<i32, String> {
+        let _span = logging::spans::jit_execution("main");
+        let module_ptr = self.get_module_ptr()?;
+        match self.backend {
+            EngineBackend::Mcjit => run_function_as_main(self.get_engine_ptr()?, module_ptr, args, env, self.logger.as_ref()),
+            EngineBackend::Lljit => self.run_linked_main(module_ptr, args, env),
+        }
+    }
+
+    /// Runs `main` by calling its linked address directly, as done for engines loaded from an object cache.
+    ///
+    /// # Parameters
+    /// * `module_ptr` - The module containing `main`.
+    /// * `args` - The command-line arguments passed as `argv`.
+    /// * `env` - The environment passed as `envp`, as key-value pairs.
+    ///
+    /// # Returns
+    /// Returns `Ok(i32)` containing the program's exit code, or `Err(String)` if `main` cannot be run.
+    fn run_linked_main(&self, module_ptr: LLVMModuleRef, args: &[&str], env: &[(&str, &str)]) -> Result<i32, String> {
+        let main_function = find_defined_function(module_ptr, DEFAULT_FUNCTION_NAME, self.logger.as_ref())?;
+        check_main_signature(main_function)?;
+
+        for table in ["llvm.global_ctors", "llvm.global_dtors"] {
+            let table_c = CString::new(table).map_err(|_| "Failed to create CString for global name.")?;
+            if !unsafe { core::LLVMGetNamedGlobal(module_ptr, table_c.as_ptr()) }.is_null() {
+                return Err("Static constructors and destructors are not run by engines created with `with_object_cache` or `with_event_listeners`; create the engine with `ExecutionEngine::new` instead.".to_string());
+            }
+        }
+
+        let program_args = ProgramArguments::new(args, env)?;
+        let main_address = self.get_function_address(DEFAULT_FUNCTION_NAME)?;
+        let returns_void = unsafe { core::LLVMGetTypeKind(core::LLVMGetReturnType(core::LLVMGlobalGetValueType(main_function))) } == LLVMTypeKind::LLVMVoidTypeKind;
+
+        // Unused trailing arguments are harmless under the C calling convention.
+        let exit_code = unsafe {
+            let main: extern "C" fn(i32, *const *const c_char, *const *const c_char) -> i32 = std::mem::transmute(main_address);
+            let exit_code = main(program_args.argc() as i32, program_args.argv(), program_args.envp());
+            if returns_void { 0 } else { exit_code }
+        };
+
+        if let Some(logger) = &self.logger {
+            logging::core::log_info(logger, &format!("Program exited with code {}.", exit_code));
+        }
+
+        Ok(exit_code)
+    }
+}
+
+/// Null-terminated `argv` and `envp` arrays for a program entry point.
+struct ProgramArguments {
+    _strings: Vec<CString>,
+    arg_ptrs: Vec<*const c_char>,
+    env_ptrs: Vec<*const c_char>,
+}
+
+impl ProgramArguments {
+    /// Builds the `argv` and `envp` arrays.
+    ///
+    /// # Parameters
+    /// * `args` - The command-line arguments.
+    /// * `env` - The environment, as key-value pairs.
+    ///
+    /// # Returns
+    /// Returns `Ok(ProgramArguments)` on success, or `Err(String)` if a string contains a nul byte.
+    fn new(args: &[&str], env: &[(&str, &str)]) -> Result<Self, String> {
+        let arg_strings = args.iter()
+            .map(|arg| CString::new(*arg))
+            .collect::<Result<Vec<CString>, _>>()
+            .map_err(|_| "Failed to create CString for program argument.")?;
+        let env_strings = env.iter()
+            .map(|(key, value)| CString::new(format!("{}={}", key, value)))
+            .collect::<Result<Vec<CString>, _>>()
+            .map_err(|_| "Failed to create CString for environment variable.")?;
+
+        let mut arg_ptrs: Vec<*const c_char> = arg_strings.iter().map(|arg| arg.as_ptr()).collect();
+        let mut env_ptrs: Vec<*const c_char> = env_strings.iter().map(|var| var.as_ptr()).collect();
+        arg_ptrs.push(std::ptr::null());
+        env_ptrs.push(std::ptr::null());
+
+        Ok(Self {
+            _strings: arg_strings.into_iter().chain(env_strings).collect(),
+            arg_ptrs,
+            env_ptrs,
+        })
+    }
+
+    /// The number of command-line arguments.
+    fn argc(&self) -> usize {
+        self.arg_ptrs.len() - 1
+    }
+
+    /// The null-terminated `argv` array.
+    fn argv(&self) -> *const *const c_char {
+        self.arg_ptrs.as_ptr()
+    }
+
+    /// The null-terminated `envp` array.
+    fn envp(&self) -> *const *const c_char {
+        self.env_ptrs.as_ptr()
+    }
+}
+
+/// Looks up a function that is defined, not merely declared, within a module.
+///
+/// # Parameters
+/// * `module_ptr` - The module to search.
+/// * `function_name` - The name of the function.
+/// * `logger` - An optional logger used to report a missing function.
+///
+/// # Returns
+/// Returns `Ok(LLVMValueRef)` for the function, or `Err(String)` if the module does not define it.
+pub(crate) fn find_defined_function(module_ptr: LLVMModuleRef, function_name: &str, logger: Option<&Logger>) -> Result<LLVMValueRef, String> {
+    let function_name_c = CString::new(function_name).map_err(|_| "Failed to create CString for function name.")?;
+    let function = unsafe { core::LLVMGetNamedFunction(module_ptr, function_name_c.as_ptr()) };
+    if function.is_null() || unsafe { core::LLVMIsDeclaration(function) } != 0 {
+        if let Some(logger) = logger {
+            logging::core::log_warning(logger, &format!("Function \"{}\" not found.", function_name));
+        }
+        return Err("Function not found in given module.".to_string());
+    }
+    Ok(function)
+}
+
+/// Runs a module's `main` function on an LLVM execution engine, together with its static
+/// constructors and destructors.
+///
+/// Shared by every backend, since `LLVMRunFunctionAsMain` works the same way for compiled and
+/// interpreted execution.
+///
+/// # Parameters
+/// * `engine_ptr` - The execution engine owning the module.
+/// * `module_ptr` - The module containing `main`.
+/// * `args` - The command-line arguments passed as `argv`.
+/// * `env` - The environment passed as `envp`, as key-value pairs.
+/// * `logger` - An optional logger used to report the exit code.
+///
+/// # Returns
+/// Returns `Ok(i32)` containing the program's exit code, or `Err(String)` if the module has no
+/// suitable `main` function.
+pub(crate) fn run_function_as_main(engine_ptr: execution_engine::LLVMExecutionEngineRef, module_ptr: LLVMModuleRef, args: &[&str], env: &[(&str, &str)], logger: Option<&Logger>) -> Result<i32, String> {
+    let main_function = find_defined_function(module_ptr, DEFAULT_FUNCTION_NAME, logger)?;
+    check_main_signature(main_function)?;
+
+    let program_args = ProgramArguments::new(args, env)?;
+
+    let exit_code = unsafe {
+        execution_engine::LLVMRunStaticConstructors(engine_ptr);
+        let exit_code = execution_engine::LLVMRunFunctionAsMain(engine_ptr, main_function, program_args.argc() as u32, program_args.argv(), program_args.envp());
+        execution_engine::LLVMRunStaticDestructors(engine_ptr);
+        exit_code
+    };
+
+    if let Some(logger) = logger {
+        logging::core::log_info(logger, &format!("Program exited with code {}.", exit_code));
+    }
+
+    Ok(exit_code)
+}
+
+/// Checks that a function has a signature LLVM can run as a program entry point.
+///
+/// # Parameters
+/// * `function` - The LLVM function to check.
+///
+/// # Returns
+/// Returns `Ok(())` if the signature is supported, or `Err(String)` describing the problem.
+fn check_main_signature(function: LLVMValueRef) -> Result<(), String> {
+    unsafe {
+        let function_type = core::LLVMGlobalGetValueType(function);
+        let return_type = core::LLVMGetReturnType(function_type);
+        let returns_int = core::LLVMGetTypeKind(return_type) == LLVMTypeKind::LLVMIntegerTypeKind && core::LLVMGetIntTypeWidth(return_type) == 32;
+        let returns_void = core::LLVMGetTypeKind(return_type) == LLVMTypeKind::LLVMVoidTypeKind;
+        if !returns_int && !returns_void {
+            return Err("Function \"main\" must return i32 or void.".to_string());
+        }
+
+        let param_count = core::LLVMCountParamTypes(function_type) as usize;
+        if param_count > 3 {
+            return Err("Function \"main\" takes at most three parameters.".to_string());
+        }
+
+        let mut param_types: Vec<LLVMTypeRef> = vec![std::ptr::null_mut(); param_count];
+        core::LLVMGetParamTypes(function_type, param_types.as_mut_ptr());
+        for (index, param_type) in param_types.iter().enumerate() {
+            let valid = if index == 0 {
+                core::LLVMGetTypeKind(*param_type) == LLVMTypeKind::LLVMIntegerTypeKind && core::LLVMGetIntTypeWidth(*param_type) == 32
+            } else {
+                core::LLVMGetTypeKind(*param_type) == LLVMTypeKind::LLVMPointerTypeKind
+            };
+            if !valid {
+                return Err("Function \"main\" must be declared as main(i32 argc, ptr argv, ptr envp).".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! Disassembly of JIT-compiled functions.
+//!
+//! For engines backed by LLJIT, the machine code is read from where the engine placed it in
+//! memory, so addresses and resolved call targets match what actually runs, and the length of each
+//! function is taken from the symbol table of the object the engine linked. MCJIT keeps the objects
+//! it emits to itself, so functions of MCJIT engines are disassembled from an equivalent
+//! compilation of the module instead, and never from memory whose extent is unknown.
+
+extern crate llvm_sys as llvm;
+use std::{collections::HashMap, ffi::{c_char, c_void, CStr, CString}, fmt};
+use llvm::{disassembler, target};
+use crate::{cache::HostTarget, core::ExecutionEngine, listeners::defined_function_names, object::{find_function_symbol, read_symbols, ObjectSymbol}};
+
+/// The size of the buffer each instruction is printed into.
+const INSTRUCTION_TEXT_SIZE: usize = 256;
+
+/// A single disassembled machine instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembledInstruction {
+    /// The address of the instruction in executable memory, or its offset within the compiled
+    /// object for engines compiling with MCJIT.
+    pub address: u64,
+    /// The encoded bytes of the instruction.
+    pub bytes: Vec<u8>,
+    /// The instruction in assembly syntax, with branch targets resolved to function names where known.
+    pub text: String,
+}
+
+/// Where the machine code of a `Disassembly` was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisassemblySource {
+    /// The code the engine loaded into executable memory, as it runs.
+    LoadedCode,
+    /// A separate compilation of the module for the same target, because MCJIT does not expose
+    /// the code it loaded. It may differ from the running code in addresses and relocated calls.
+    Recompiled,
+}
+
+/// The disassembled machine code of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disassembly {
+    /// The name of the function.
+    pub function_name: String,
+    /// Whether the instructions are those of the running code or of a recompilation.
+    pub source: DisassemblySource,
+    /// The function's instructions, in address order.
+    pub instructions: Vec<DisassembledInstruction>,
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.function_name)?;
+        for instruction in &self.instructions {
+            let bytes: Vec<String> = instruction.bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            writeln!(f, "  {:#014x}:  {:<32} {}", instruction.address, bytes.join(" "), instruction.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// The names of the functions in the engine, keyed by address, for resolving branch targets.
+struct SymbolTable {
+    names: HashMap<u64, CString>,
+}
+
+/// Resolves an address referenced by an instruction to a function name.
+extern "C" fn lookup_symbol(dis_info: *mut c_void, reference_value: u64, reference_type: *mut u64, _reference_pc: u64, reference_name: *mut *const c_char) -> *const c_char {
+    unsafe {
+        let table = &*(dis_info as *const SymbolTable);
+        *reference_type = disassembler::LLVMDisassembler_ReferenceType_InOut_None;
+        *reference_name = std::ptr::null();
+        table.names.get(&reference_value).map_or(std::ptr::null(), |name| name.as_ptr())
+    }
+}
+
+impl ExecutionEngine {
+    /// Disassembles a compiled function for the host target.
+    ///
+    /// Only engines backed by LLJIT are disassembled from the code they loaded; for MCJIT engines
+    /// the result comes from recompiling the module, as recorded in `Disassembly::source`.
+    ///
+    /// For engines backed by LLJIT, the machine code is read from executable memory, bounded by
+    /// the size recorded in the object file the engine linked, so addresses and call targets are
+    /// those of the running code. MCJIT does not expose the objects it emits, so for engines
+    /// compiling with MCJIT the module is compiled again for the same target and the function is
+    /// disassembled from that object instead: addresses are offsets within the object, and calls
+    /// to other objects are shown before relocation.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to disassemble.
+    ///
+    /// # Returns
+    /// Returns `Ok(Disassembly)` containing the function's instructions, or `Err(String)` if the
+    /// function could not be found or its machine code could not be decoded.
+    pub fn disassemble(&self, function_name: &str) -> Result<Disassembly, String> {
+        let module_ptr = self.get_module_ptr()?;
+        let mut names = HashMap::new();
+
+        let (source, triple, base_address, mut code) = match self.get_object() {
+            Some(object) => {
+                let symbols = read_symbols(object)?;
+                let size = function_symbol(&symbols, function_name)?.size;
+                let function_address = self.get_function_address(function_name)?;
+                for name in defined_function_names(module_ptr) {
+                    let address = self.get_function_address(&name)?;
+                    names.insert(address, CString::new(name).map_err(|_| "Failed to create CString for function name.")?);
+                }
+
+                // SAFETY: The engine linked exactly this object, so the function's machine code
+                // occupies `size` bytes of executable memory starting at its address.
+                let code = unsafe { std::slice::from_raw_parts(function_address as *const u8, size as usize) }.to_vec();
+                (DisassemblySource::LoadedCode, HostTarget::detect().get_triple().to_string(), function_address, code)
+            },
+            None => {
+                let target = HostTarget::mcjit_equivalent(module_ptr);
+                let symbols = read_symbols(&target.compile(module_ptr)?)?;
+                let symbol = function_symbol(&symbols, function_name)?;
+                if symbol.contents.len() as u64 != symbol.size {
+                    return Err(format!("Function \"{}\" has no machine code in the compiled object.", function_name));
+                }
+                for name in defined_function_names(module_ptr) {
+                    if let Some(defined) = find_function_symbol(&symbols, &name) {
+                        names.insert(defined.address, CString::new(name).map_err(|_| "Failed to create CString for function name.")?);
+                    }
+                }
+                (DisassemblySource::Recompiled, target.get_triple().to_string(), symbol.address, symbol.contents.clone())
+            },
+        };
+
+        let table = SymbolTable { names };
+        let triple_c = CString::new(triple.as_str()).map_err(|_| "Failed to create CString for target triple.")?;
+        let mut instructions = Vec::new();
+
+        unsafe {
+            target::LLVM_InitializeNativeDisassembler();
+            let context = disassembler::LLVMCreateDisasm(triple_c.as_ptr(), &table as *const SymbolTable as *mut c_void, 0, None, Some(lookup_symbol));
+            if context.is_null() {
+                return Err(format!("No disassembler is available for {}.", triple));
+            }
+            disassembler::LLVMSetDisasmOptions(context, disassembler::LLVMDisassembler_Option_PrintImmHex | disassembler::LLVMDisassembler_Option_SetInstrComments);
+
+            let mut offset = 0;
+            let mut text = [0 as c_char; INSTRUCTION_TEXT_SIZE];
+            while offset < code.len() {
+                let address = base_address + offset as u64;
+                let length = disassembler::LLVMDisasmInstruction(context, code.as_mut_ptr().add(offset), (code.len() - offset) as u64, address, text.as_mut_ptr(), text.len());
+                if length == 0 {
+                    disassembler::LLVMDisasmDispose(context);
+                    return Err(format!("Failed to decode instruction at {:#x} in function \"{}\".", address, function_name));
+                }
+
+                instructions.push(DisassembledInstruction {
+                    address,
+                    bytes: code[offset..offset + length].to_vec(),
+                    text: CStr::from_ptr(text.as_ptr()).to_string_lossy().trim().to_string(),
+                });
+                offset += length;
+            }
+
+            disassembler::LLVMDisasmDispose(context);
+        }
+
+        if let Some(logger) = self.get_logger() {
+            logging::core::log_info(logger, &format!("Disassembled {} instructions of function '{}'.", instructions.len(), function_name));
+        }
+
+        Ok(Disassembly {
+            function_name: function_name.to_string(),
+            source,
+            instructions,
+        })
+    }
+}
+
+/// Finds the symbol of a function that must be defined by an object file.
+///
+/// # Parameters
+/// * `symbols` - The symbols of the object file.
+/// * `function_name` - The unmangled function name.
+///
+/// # Returns
+/// Returns `Ok(&ObjectSymbol)` containing the symbol, or `Err(String)` if the object does not
+/// define the function.
+fn function_symbol<'a>(symbols: &'a [ObjectSymbol], function_name: &str) -> Result<&'a ObjectSymbol, String> {
+    find_function_symbol(symbols, function_name).ok_or_else(|| format!("Function \"{}\" has no symbol in the compiled object.", function_name))
+}
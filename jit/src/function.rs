@@ -0,0 +1,224 @@
+//! Typed handles to JIT-compiled functions.
+//!
+//! A `JitFunction<F>` is looked up once and then called directly, without the string lookup
+//! `ExecutionEngine::execute` performs on every call. `F` is an `extern "C" fn` type whose
+//! parameter and return types are checked against the LLVM signature of the function when the
+//! handle is created.
+
+extern crate llvm_sys as llvm;
+use std::{fmt, sync::{Arc, RwLock}};
+use llvm::{core, prelude::LLVMTypeRef};
+use common::pointer::SafeLLVMPointer;
+use ir::inspect;
+use crate::{core::{find_defined_function, ExecutionEngine}, types::{JitReturnType, JitType}};
+
+/// An `extern "C" fn` type that JIT-compiled functions can be called through.
+///
+/// Implemented for functions of up to eight parameters whose parameter types implement `JitType`
+/// and whose return type implements `JitReturnType`.
+///
+/// # Safety
+/// Implementors must be function pointer types with the C calling convention, and
+/// `matches_llvm_function_type` must only accept LLVM function types that can be called through them.
+pub unsafe trait JitSignature: Copy + 'static {
+    /// Determines whether an LLVM function type can be called through this function pointer type.
+    ///
+    /// # Parameters
+    /// * `function_type` - The LLVM function type to compare against.
+    ///
+    /// # Safety
+    /// `function_type` must be a valid LLVM function type whose context is still alive.
+    ///
+    /// # Returns
+    /// True if the signatures are compatible, false otherwise.
+    unsafe fn matches_llvm_function_type(function_type: LLVMTypeRef) -> bool;
+
+    /// A human-readable description of the LLVM function type this signature corresponds to.
+    ///
+    /// # Returns
+    /// A string such as `i64 (i64, double)`, used in error messages.
+    fn llvm_signature_name() -> String;
+
+    /// Converts the address of compiled code into a function pointer.
+    ///
+    /// # Parameters
+    /// * `address` - The address of a function with a matching signature.
+    ///
+    /// # Safety
+    /// `address` must point to the machine code of a function with this signature.
+    ///
+    /// # Returns
+    /// The function pointer.
+    unsafe fn from_address(address: u64) -> Self;
+}
+
+/// Checks an LLVM function type against the expected Rust parameter and return types.
+///
+/// # Parameters
+/// * `function_type` - The LLVM function type.
+/// * `param_checks` - The `matches_llvm_type` check of each expected parameter type, in order.
+/// * `return_check` - The `matches_llvm_type` check of the expected return type.
+///
+/// # Safety
+/// `function_type` must be a valid LLVM function type.
+///
+/// # Returns
+/// True if the function takes exactly the expected parameters, is not variadic, and returns the
+/// expected type.
+unsafe fn signature_matches(function_type: LLVMTypeRef, param_checks: &[unsafe fn(LLVMTypeRef) -> bool], return_check: unsafe fn(LLVMTypeRef) -> bool) -> bool {
+    if !return_check(core::LLVMGetReturnType(function_type)) {
+        return false;
+    }
+
+    let param_count = core::LLVMCountParamTypes(function_type) as usize;
+    if param_count != param_checks.len() || core::LLVMIsFunctionVarArg(function_type) != 0 {
+        return false;
+    }
+
+    let mut param_types: Vec<LLVMTypeRef> = vec![std::ptr::null_mut(); param_count];
+    core::LLVMGetParamTypes(function_type, param_types.as_mut_ptr());
+    param_types.iter().zip(param_checks).all(|(param_type, matches)| matches(*param_type))
+}
+
+/// A typed handle to a JIT-compiled function.
+///
+/// The handle shares ownership of the engine, so the compiled code stays available for as long as
+/// the handle exists, even after the `ExecutionEngine` it came from is dropped.
+pub struct JitFunction<F: JitSignature> {
+    name: String,
+    function: F,
+    _engine: Arc<RwLock<SafeLLVMPointer>>,
+    _module: Arc<RwLock<SafeLLVMPointer>>,
+}
+
+// SAFETY: The handle never touches the engine or module it holds, which are only kept to keep the
+// compiled code alive. Compiled code is immutable once emitted and can be called from any thread.
+unsafe impl<F: JitSignature> Send for JitFunction<F> {}
+unsafe impl<F: JitSignature> Sync for JitFunction<F> {}
+
+impl<F: JitSignature> JitFunction<F> {
+    /// Retrieves the name of the function.
+    ///
+    /// # Returns
+    /// The name the function was looked up by.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Retrieves the raw function pointer.
+    ///
+    /// # Safety
+    /// The returned pointer is not tied to the lifetime of this handle. The caller must not
+    /// call it after this handle and every clone of it have been dropped, since the engine
+    /// that owns the machine code may then have been freed.
+    ///
+    /// # Returns
+    /// The function pointer.
+    pub unsafe fn as_raw(&self) -> F {
+        self.function
+    }
+}
+
+impl<F: JitSignature> Clone for JitFunction<F> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            function: self.function,
+            _engine: Arc::clone(&self._engine),
+            _module: Arc::clone(&self._module),
+        }
+    }
+}
+
+impl<F: JitSignature> fmt::Debug for JitFunction<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JitFunction")
+            .field("name", &self.name)
+            .field("signature", &F::llvm_signature_name())
+            .finish()
+    }
+}
+
+/// Implements `JitSignature` and a typed `call` method for `extern "C" fn` types of one arity.
+macro_rules! impl_jit_signature {
+    ($($arg:ident: $arg_type:ident),*) => {
+        unsafe impl<ReturnType: JitReturnType, $($arg_type: JitType),*> JitSignature for extern "C" fn($($arg_type),*) -> ReturnType {
+            unsafe fn matches_llvm_function_type(function_type: LLVMTypeRef) -> bool {
+                let param_checks: &[unsafe fn(LLVMTypeRef) -> bool] = &[$(<$arg_type as JitType>::matches_llvm_type),*];
+                signature_matches(function_type, param_checks, ReturnType::matches_llvm_type)
+            }
+
+            fn llvm_signature_name() -> String {
+                let param_names: &[&str] = &[$(<$arg_type as JitType>::llvm_type_name()),*];
+                format!("{} ({})", ReturnType::llvm_type_name(), param_names.join(", "))
+            }
+
+            unsafe fn from_address(address: u64) -> Self {
+                std::mem::transmute::<usize, Self>(address as usize)
+            }
+        }
+
+        impl<ReturnType: JitReturnType, $($arg_type: JitType),*> JitFunction<extern "C" fn($($arg_type),*) -> ReturnType> {
+            /// Calls the compiled function.
+            ///
+            /// # Returns
+            /// The value returned by the function.
+            #[allow(clippy::too_many_arguments)]
+            pub fn call(&self, $($arg: $arg_type),*) -> ReturnType {
+                (self.function)($($arg),*)
+            }
+        }
+    };
+}
+
+impl_jit_signature!();
+impl_jit_signature!(a: A);
+impl_jit_signature!(a: A, b: B);
+impl_jit_signature!(a: A, b: B, c: C);
+impl_jit_signature!(a: A, b: B, c: C, d: D);
+impl_jit_signature!(a: A, b: B, c: C, d: D, e: E);
+impl_jit_signature!(a: A, b: B, c: C, d: D, e: E, f: G);
+impl_jit_signature!(a: A, b: B, c: C, d: D, e: E, f: G, g: H);
+impl_jit_signature!(a: A, b: B, c: C, d: D, e: E, f: G, g: H, h: I);
+
+impl ExecutionEngine {
+    /// Looks up a compiled function as a typed handle.
+    ///
+    /// The signature `F` is checked against the LLVM type of the function, and the module is
+    /// compiled if it has not been compiled yet.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to look up.
+    ///
+    /// # Returns
+    /// Returns `Ok(JitFunction<F>)` on success, or `Err(String)` if the function could not be found
+    /// or its signature does not match `F`.
+    pub fn get_function<F: JitSignature>(&self, function_name: &str) -> Result<JitFunction<F>, String> {
+        let module_ptr = self.get_module_ptr()?;
+        let function = find_defined_function(module_ptr, function_name, self.get_logger())?;
+
+        let function_type = unsafe { core::LLVMGlobalGetValueType(function) };
+        if !unsafe { F::matches_llvm_function_type(function_type) } {
+            let message = format!(
+                "Function \"{}\" has type {}, which cannot be called as {}.",
+                function_name,
+                unsafe { inspect::print_type(function_type) },
+                F::llvm_signature_name()
+            );
+            if let Some(logger) = self.get_logger() {
+                logging::core::log_error(logger, &message);
+            }
+            return Err(message);
+        }
+
+        let address = self.get_function_address(function_name)?;
+        let (engine, module) = self.get_shared_pointers();
+
+        Ok(JitFunction {
+            name: function_name.to_string(),
+            function: unsafe { F::from_address(address) },
+            _engine: engine,
+            _module: module,
+        })
+    }
+}
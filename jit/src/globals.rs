@@ -0,0 +1,127 @@
+//! Host-side access to the global variables of a JIT-compiled module.
+//!
+//! These accessors let tests seed a program's inputs and inspect its state after execution without
+//! adding getter and setter functions to the generated IR. Every typed access is checked against
+//! the LLVM type of the global before any memory is touched.
+
+extern crate llvm_sys as llvm;
+use std::ffi::CString;
+use llvm::{core, prelude::LLVMValueRef};
+use ir::inspect;
+use crate::{core::ExecutionEngine, types::JitType};
+
+impl ExecutionEngine {
+    /// Looks up a global variable declared in the engine's module.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMValueRef)` for the global, or `Err(String)` if the module has no such global.
+    fn get_named_global(&self, name: &str) -> Result<LLVMValueRef, String> {
+        let module_ptr = self.get_module_ptr()?;
+        let name_c = CString::new(name).map_err(|_| "Failed to create CString for global name.")?;
+
+        let global = unsafe { core::LLVMGetNamedGlobal(module_ptr, name_c.as_ptr()) };
+        if global.is_null() {
+            if let Some(logger) = self.get_logger() {
+                logging::core::log_warning(logger, &format!("Global \"{}\" not found.", name));
+            }
+            return Err("Global not found in given module.".to_string());
+        }
+
+        Ok(global)
+    }
+
+    /// Checks that a global variable can be accessed as the Rust type `T`.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    /// * `global` - The LLVM global variable.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` if the types match, or `Err(String)` describing the mismatch.
+    fn check_global_type<T: JitType>(&self, name: &str, global: LLVMValueRef) -> Result<(), String> {
+        let value_type = unsafe { core::LLVMGlobalGetValueType(global) };
+        if unsafe { T::matches_llvm_type(value_type) } {
+            Ok(())
+        } else {
+            Err(format!(
+                "Global \"{}\" has type {}, which cannot be accessed as {}.",
+                name,
+                unsafe { inspect::print_type(value_type) },
+                T::llvm_type_name()
+            ))
+        }
+    }
+
+    /// Retrieves the address of a global variable in JIT-compiled memory.
+    ///
+    /// Compiles the module if it has not been compiled yet.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    ///
+    /// # Returns
+    /// Returns `Ok(u64)` containing the address of the global, or `Err(String)` if the global could not be found.
+    pub fn global_address(&self, name: &str) -> Result<u64, String> {
+        self.get_named_global(name)?;
+
+        let address = self.get_global_address(name)?;
+
+        if address == 0 {
+            return Err(format!("Global \"{}\" has no address in the compiled module.", name));
+        }
+
+        Ok(address)
+    }
+
+    /// Reads the current value of a global variable.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    ///
+    /// # Type Parameters
+    /// * `T` - The Rust type to read the global as. Must match the global's LLVM type.
+    ///
+    /// # Returns
+    /// Returns `Ok(T)` containing the value, or `Err(String)` if the global could not be found or
+    /// its type does not match `T`.
+    pub fn read_global<T: JitType>(&self, name: &str) -> Result<T, String> {
+        let global = self.get_named_global(name)?;
+        self.check_global_type::<T>(name, global)?;
+
+        let address = self.global_address(name)?;
+        Ok(unsafe { std::ptr::read(address as *const T) })
+    }
+
+    /// Overwrites the value of a global variable.
+    ///
+    /// # Parameters
+    /// * `name` - The name of the global variable.
+    /// * `value` - The new value.
+    ///
+    /// # Type Parameters
+    /// * `T` - The Rust type to write the global as. Must match the global's LLVM type.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if the global could not be found, is constant,
+    /// or its type does not match `T`.
+    pub fn write_global<T: JitType>(&self, name: &str, value: T) -> Result<(), String> {
+        let global = self.get_named_global(name)?;
+        self.check_global_type::<T>(name, global)?;
+
+        if unsafe { core::LLVMIsGlobalConstant(global) } != 0 {
+            return Err(format!("Global \"{}\" is constant and cannot be written.", name));
+        }
+
+        let address = self.global_address(name)?;
+        unsafe { std::ptr::write(address as *mut T, value) };
+
+        if let Some(logger) = self.get_logger() {
+            logging::core::log_info(logger, &format!("Global '{}' written.", name));
+        }
+
+        Ok(())
+    }
+}
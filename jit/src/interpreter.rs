@@ -0,0 +1,125 @@
+//! An LLVM interpreter backend.
+//!
+//! The interpreter executes IR directly instead of generating native code, so it works without a
+//! native target and can serve as a reference implementation to compare MCJIT results against.
+
+extern crate llvm_sys as llvm;
+use std::{ffi::{c_char, CStr}, sync::{Arc, RwLock}};
+use llvm::{core, execution_engine, prelude::LLVMModuleRef};
+use slog::Logger;
+use common::pointer::{LLVMRef, LLVMRefType, SafeLLVMPointer};
+use ir::core::IRManager;
+use crate::{backend::{run_generic_function, ExecutionBackend, GenericValue}, core::run_function_as_main};
+
+/// Executes LLVM modules with LLVM's IR interpreter.
+pub struct Interpreter {
+    engine: Arc<RwLock<SafeLLVMPointer>>,
+    module: Arc<RwLock<SafeLLVMPointer>>,
+    logger: Option<Logger>,
+}
+
+impl Interpreter {
+    /// Constructs a new `Interpreter`.
+    ///
+    /// The interpreter takes ownership of a copy of the module rather than the module itself, so
+    /// the same module can also be handed to an `ExecutionEngine` for differential testing.
+    ///
+    /// # Parameters
+    /// * `module` - A thread-safe `SafeLLVMPointer` containing an `LLVMModuleRef`.
+    /// * `debug_info` - If true, records debugging information with the process-wide default logger.
+    ///
+    /// # Returns
+    /// A new instance of `Interpreter`.
+    pub fn new(module: Arc<RwLock<SafeLLVMPointer>>, debug_info: bool) -> Self {
+        unsafe { execution_engine::LLVMLinkInInterpreter() };
+
+        let module_rw_lock = module.try_read().expect("Failed to read module");
+        let module_copy = module_rw_lock.read(LLVMRefType::Module, |module_ref| {
+            if let LLVMRef::Module(module_ptr) = module_ref {
+                unsafe { core::LLVMCloneModule(*module_ptr) }
+            } else {
+                panic!("Module pointer is not correctly retrieved.");
+            }
+        });
+        drop(module_rw_lock);
+
+        let mut engine_ref: execution_engine::LLVMExecutionEngineRef = std::ptr::null_mut();
+        let mut out_error: *mut c_char = std::ptr::null_mut();
+        unsafe {
+            if execution_engine::LLVMCreateInterpreterForModule(&mut engine_ref, module_copy, &mut out_error) != 0 {
+                if !out_error.is_null() {
+                    let error_str = CStr::from_ptr(out_error).to_str().unwrap_or("Unknown error");
+                    eprintln!("{}", error_str);
+                    core::LLVMDisposeMessage(out_error);
+                    panic!("Failed to create interpreter");
+                } else {
+                    panic!("Failed to create interpreter with unknown error.");
+                }
+            }
+        }
+
+        let engine_cptr = SafeLLVMPointer::new(LLVMRef::ExecutionEngine(engine_ref)).expect("Engine cannot be null");
+        let module_cptr = SafeLLVMPointer::new(LLVMRef::Module(module_copy)).expect("Module cannot be null");
+
+        let logger = if debug_info {
+            Some(logging::core::shared_logger())
+        } else {
+            None
+        };
+
+        Self {
+            engine: Arc::new(RwLock::new(engine_cptr)),
+            module: Arc::new(RwLock::new(module_cptr)),
+            logger,
+        }
+    }
+
+    /// Replaces the logger that records debugging information.
+    ///
+    /// # Parameters
+    /// * `logger` - The logger to write to, for example one built from a `logging::config::LoggerConfig`.
+    ///
+    /// # Returns
+    /// The updated `Interpreter`.
+    pub fn with_logger(mut self, logger: Logger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Retrieves the raw LLVM execution engine.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMExecutionEngineRef)` on success, or `Err(String)` if the engine lock could not be obtained.
+    fn get_engine_ptr(&self) -> Result<execution_engine::LLVMExecutionEngineRef, String> {
+        let engine_lock = self.engine.try_read().map_err(|e| format!("Failed to obtain read lock on engine: {}", e))?;
+        engine_lock.read(LLVMRefType::ExecutionEngine, |engine_ref| {
+            if let LLVMRef::ExecutionEngine(engine_ptr) = engine_ref {
+                Ok(*engine_ptr)
+            } else {
+                Err("Invalid engine pointer.".to_string())
+            }
+        })
+    }
+
+    /// Retrieves the interpreter's copy of the module.
+    ///
+    /// # Returns
+    /// Returns `Ok(LLVMModuleRef)` on success, or `Err(String)` if the module lock could not be obtained.
+    fn get_module_ptr(&self) -> Result<LLVMModuleRef, String> {
+        IRManager::read_module_ptr(&self.module).ok_or_else(|| "Invalid module pointer.".to_string())
+    }
+}
+
+impl ExecutionBackend for Interpreter {
+    fn run_function(&mut self, function_name: &str, args: &[GenericValue]) -> Result<GenericValue, String> {
+        let engine_ptr = self.get_engine_ptr()?;
+        let module_ptr = self.get_module_ptr()?;
+        run_generic_function(engine_ptr, module_ptr, function_name, args, self.logger.as_ref(), |_, _| Ok(()))
+    }
+
+    fn run_main(&mut self, args: &[&str], env: &[(&str, &str)]) -> Result<i32, String> {
+        let engine_ptr = self.get_engine_ptr()?;
+        let module_ptr = self.get_module_ptr()?;
+        run_function_as_main(engine_ptr, module_ptr, args, env, self.logger.as_ref())
+    }
+}
@@ -0,0 +1,270 @@
+//! Process-isolated execution of JIT-compiled functions.
+//!
+//! Functions are compiled in the parent process and then invoked inside a forked child, so that a
+//! crash, an infinite loop or runaway memory use in the executed code cannot take down the caller.
+//! The child's standard output and standard error are captured, and the function's return value is
+//! sent back to the parent through a pipe.
+
+use std::{io::{Read, Write}, mem::{size_of, MaybeUninit}, thread, time::{Duration, Instant}};
+use crate::{capture::{spawn_reader, Pipe}, core::ExecutionEngine};
+
+/// Interval at which the parent polls the child process for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Exit code of a child process that could not be set up or could not report its result.
+const CHILD_SETUP_FAILED: libc::c_int = 127;
+
+/// Resource limits applied to a function executed in an isolated child process.
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionLimits {
+    timeout: Duration,
+    memory_limit: Option<u64>,
+}
+
+impl ExecutionLimits {
+    /// Constructs a new `ExecutionLimits` with the given wall-clock timeout and no memory limit.
+    ///
+    /// # Parameters
+    /// * `timeout` - The maximum wall-clock time the child process may run before it is killed.
+    ///
+    /// # Returns
+    /// A new instance of `ExecutionLimits`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            memory_limit: None,
+        }
+    }
+
+    /// Sets the maximum size of the child's address space.
+    ///
+    /// # Parameters
+    /// * `bytes` - The memory limit in bytes, applied with `RLIMIT_AS`.
+    ///
+    /// # Returns
+    /// The updated `ExecutionLimits`.
+    pub fn with_memory_limit(mut self, bytes: u64) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Retrieves the wall-clock timeout.
+    ///
+    /// # Returns
+    /// The timeout as a `Duration`.
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Retrieves the memory limit, if any.
+    ///
+    /// # Returns
+    /// An `Option` containing the memory limit in bytes.
+    pub fn get_memory_limit(&self) -> Option<u64> {
+        self.memory_limit
+    }
+}
+
+impl Default for ExecutionLimits {
+    /// Defaults to a five second timeout with no memory limit.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+/// Describes how an isolated child process finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The child exited normally with the given exit code.
+    Exited(i32),
+    /// The child was terminated by the given signal, e.g. `SIGSEGV` for a segmentation fault.
+    Signaled(i32),
+    /// The child exceeded its wall-clock timeout and was killed by the parent.
+    TimedOut,
+}
+
+/// The outcome of a function executed in an isolated child process.
+#[derive(Debug, Clone)]
+pub struct IsolatedOutcome<ReturnType> {
+    /// How the child process finished.
+    pub status: ExitStatus,
+    /// The function's return value, if the function returned before the child finished.
+    pub return_value: Option<ReturnType>,
+    /// Everything the child wrote to standard output.
+    pub stdout: String,
+    /// Everything the child wrote to standard error.
+    pub stderr: String,
+}
+
+impl<ReturnType> IsolatedOutcome<ReturnType> {
+    /// Determines whether the function returned and the child exited cleanly.
+    ///
+    /// # Returns
+    /// True if the child exited with code zero and produced a return value, false otherwise.
+    pub fn is_success(&self) -> bool {
+        self.status == ExitStatus::Exited(0) && self.return_value.is_some()
+    }
+}
+
+/// Waits for a child process, killing it if the timeout elapses.
+///
+/// # Parameters
+/// * `pid` - The process id of the child.
+/// * `timeout` - The maximum time to wait.
+///
+/// # Returns
+/// Returns `Ok(ExitStatus)` once the child has been reaped, or `Err(String)` if waiting failed.
+fn wait_with_timeout(pid: libc::pid_t, timeout: Duration) -> Result<ExitStatus, String> {
+    let deadline = Instant::now() + timeout;
+    let mut status: libc::c_int = 0;
+
+    loop {
+        let result = unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) };
+        if result == pid {
+            break;
+        }
+        if result < 0 {
+            return Err(format!("Failed to wait for child process: {}", std::io::Error::last_os_error()));
+        }
+        if Instant::now() >= deadline {
+            unsafe {
+                libc::kill(pid, libc::SIGKILL);
+                libc::waitpid(pid, &mut status, 0);
+            }
+            return Ok(ExitStatus::TimedOut);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    if libc::WIFEXITED(status) {
+        Ok(ExitStatus::Exited(libc::WEXITSTATUS(status)))
+    } else if libc::WIFSIGNALED(status) {
+        Ok(ExitStatus::Signaled(libc::WTERMSIG(status)))
+    } else {
+        Err(format!("Child process finished with unexpected status {}.", status))
+    }
+}
+
+impl ExecutionEngine {
+    /// Executes a specified function within the module in a forked child process.
+    ///
+    /// The function is compiled in the calling process before forking, so compilation errors are
+    /// reported as `Err` rather than through the child's exit status. Inside the child, standard
+    /// output and standard error are redirected into pipes and the memory limit, if any, is
+    /// applied before the function is called.
+    ///
+    /// Only the calling thread exists in the forked child, while every lock held by another thread
+    /// at the moment of the fork stays locked there. The child therefore makes nothing but
+    /// async-signal-safe system calls before the function runs, and reports the result with a raw
+    /// `write` and `_exit`. The executed function itself, and the flush of its C standard streams
+    /// afterwards, may still take such locks (for example through `malloc` or `printf`), so this
+    /// should be called from a single-threaded process. If it is not, a child that deadlocks is
+    /// killed once the timeout elapses and reported as `ExitStatus::TimedOut`.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to be executed.
+    /// * `args` - The arguments passed to the function.
+    /// * `limits` - The timeout and memory limit applied to the child process.
+    ///
+    /// # Returns
+    /// Returns `Ok(IsolatedOutcome)` describing how the child finished, or `Err(String)` if the
+    /// function could not be found or the child process could not be set up.
+    pub fn execute_isolated<ReturnType, ArgType>(&mut self, function_name: &str, args: ArgType, limits: ExecutionLimits) -> Result<IsolatedOutcome<ReturnType>, String>
+    where
+        ReturnType: Copy + 'static,
+        ArgType: Send + Sync + 'static,
+    {
+        let function_address = self.get_function_address(function_name)?;
+
+        let stdout_pipe = Pipe::new()?;
+        let stderr_pipe = Pipe::new()?;
+        let result_pipe = Pipe::new()?;
+
+        // Everything the child needs is prepared here, so it does not have to allocate after the fork.
+        let memory_limit = limits.memory_limit.map(|bytes| libc::rlimit {
+            rlim_cur: bytes as libc::rlim_t,
+            rlim_max: bytes as libc::rlim_t,
+        });
+        let func: extern "C" fn(ArgType) -> ReturnType = unsafe { std::mem::transmute(function_address) };
+
+        // Flush buffered output so that it is not written a second time by the child.
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        unsafe { libc::fflush(std::ptr::null_mut()) };
+
+        let pid = unsafe { libc::fork() };
+        if pid < 0 {
+            return Err(format!("Failed to fork child process: {}", std::io::Error::last_os_error()));
+        }
+
+        if pid == 0 {
+            // Only async-signal-safe calls are made here, apart from the executed function and the flush of its output.
+            unsafe {
+                if libc::dup2(stdout_pipe.write_fd, libc::STDOUT_FILENO) < 0 || libc::dup2(stderr_pipe.write_fd, libc::STDERR_FILENO) < 0 {
+                    libc::_exit(CHILD_SETUP_FAILED);
+                }
+                libc::close(stdout_pipe.read_fd);
+                libc::close(stderr_pipe.read_fd);
+                libc::close(result_pipe.read_fd);
+
+                if let Some(limit) = &memory_limit {
+                    if libc::setrlimit(libc::RLIMIT_AS, limit) != 0 {
+                        libc::_exit(CHILD_SETUP_FAILED);
+                    }
+                }
+
+                let value = func(args);
+                libc::fflush(std::ptr::null_mut());
+
+                let bytes = &value as *const ReturnType as *const u8;
+                let mut written = 0;
+                while written < size_of::<ReturnType>() {
+                    let result = libc::write(result_pipe.write_fd, bytes.add(written) as *const libc::c_void, size_of::<ReturnType>() - written);
+                    if result > 0 {
+                        written += result as usize;
+                    } else if result == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::EINTR) {
+                        libc::_exit(CHILD_SETUP_FAILED);
+                    }
+                }
+                libc::_exit(0);
+            }
+        }
+
+        let stdout_reader = spawn_reader(stdout_pipe.into_reader());
+        let stderr_reader = spawn_reader(stderr_pipe.into_reader());
+        let mut result_reader = result_pipe.into_reader();
+
+        let status = wait_with_timeout(pid, limits.timeout)?;
+
+        let mut bytes = Vec::with_capacity(size_of::<ReturnType>());
+        let _ = result_reader.read_to_end(&mut bytes);
+        let return_value = if bytes.len() == size_of::<ReturnType>() {
+            let mut value = MaybeUninit::<ReturnType>::uninit();
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), value.as_mut_ptr() as *mut u8, bytes.len());
+                Some(value.assume_init())
+            }
+        } else {
+            None
+        };
+
+        let stdout = stdout_reader.join().map_err(|_| "Failed to capture standard output.".to_string())?;
+        let stderr = stderr_reader.join().map_err(|_| "Failed to capture standard error.".to_string())?;
+
+        if let Some(logger) = self.get_logger() {
+            match status {
+                ExitStatus::Exited(0) => logging::core::log_info(logger, &format!("Function '{}' executed successfully in isolation.", function_name)),
+                ExitStatus::Exited(code) => logging::core::log_warning(logger, &format!("Function '{}' exited with code {}.", function_name, code)),
+                ExitStatus::Signaled(signal) => logging::core::log_error(logger, &format!("Function '{}' was terminated by signal {}.", function_name, signal)),
+                ExitStatus::TimedOut => logging::core::log_error(logger, &format!("Function '{}' timed out after {:?}.", function_name, limits.timeout)),
+            }
+        }
+
+        Ok(IsolatedOutcome {
+            status,
+            return_value,
+            stdout,
+            stderr,
+        })
+    }
+}
@@ -2,3 +2,45 @@
 
 /// Core functionalities for JIT compilation and execution.
 pub mod core;
+
+/// Capture of the standard output and standard error written by JIT-compiled code.
+#[cfg(unix)]
+pub mod capture;
+
+/// Process-isolated execution of JIT-compiled functions, with timeouts, memory limits and
+/// capture of the child's output.
+#[cfg(unix)]
+pub mod isolation;
+
+/// Mapping between Rust types and the LLVM types of values shared with JIT-compiled code.
+pub mod types;
+
+/// Typed handles to JIT-compiled functions, checked against their LLVM signatures.
+pub mod function;
+
+/// Sharing of a compiled module between threads through `Send + Sync` function handles.
+pub mod shared;
+
+/// Host-side reading and writing of the global variables of a JIT-compiled module.
+pub mod globals;
+
+/// A common interface over execution backends, with LLVM generic values for arguments and results.
+pub mod backend;
+
+/// Execution of LLVM modules with LLVM's IR interpreter.
+pub mod interpreter;
+
+/// On-disk caching of compiled machine code, keyed by module bitcode and target options.
+pub mod cache;
+
+/// Wrappers over LLVM's ORC LLJIT for linking precompiled object files.
+mod orc;
+
+/// JIT event listeners for `gdb`, `perf` and Rust callbacks reporting emitted functions.
+pub mod listeners;
+
+/// Inspection of the symbols and machine code of compiled object files.
+mod object;
+
+/// Disassembly of the machine code of JIT-compiled functions.
+pub mod disassembly;
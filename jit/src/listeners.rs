@@ -0,0 +1,222 @@
+//! JIT event listeners for profilers, debuggers and logging.
+//!
+//! LLVM's GDB registration listener makes JIT-compiled functions visible to `gdb`, and its perf
+//! listener writes the `/tmp/perf-<pid>.map` and jitdump files `perf` uses for symbolization.
+//! Rust callbacks receive the name, address and machine code size of every emitted function.
+//!
+//! Listeners have the following limits:
+//! * They are only attached by `ExecutionEngine::with_event_listeners`. LLVM's C API cannot
+//!   register listeners with MCJIT, so engines from `ExecutionEngine::new` have none, and engines
+//!   from `ExecutionEngine::with_object_cache` have none either.
+//! * Engines with listeners compile the whole module up front and link it with ORC LLJIT, whose
+//!   RuntimeDyld linking layer is the only one listeners can be attached to.
+//! * Rust callbacks are not LLVM JIT event listeners, which the C API cannot implement. They are
+//!   invoked once per function after the module has been linked, from the symbol table of the
+//!   compiled object, and are never told when code is freed.
+
+extern crate llvm_sys as llvm;
+use std::sync::{Arc, RwLock};
+use llvm::{core, execution_engine, orc2::{ee, lljit::{self, LLVMOrcLLJITRef}}, prelude::LLVMModuleRef};
+use slog::Logger;
+use common::{constants::DEFAULT_MODULE_NAME, pointer::SafeLLVMPointer, target::{GeneralTargetConfigurator, TargetConfigurator}};
+use ir::{core::IRManager, inspect};
+use crate::{cache::HostTarget, core::ExecutionEngine, object::{find_function_symbol, read_symbols}, orc};
+
+/// A function emitted into executable memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmittedFunction {
+    /// The name of the function.
+    pub name: String,
+    /// The address of the function's machine code.
+    pub address: u64,
+    /// The size of the function's machine code in bytes.
+    pub size: u64,
+}
+
+/// A callback invoked for every emitted function.
+pub type EmittedFunctionCallback = Arc<dyn Fn(&EmittedFunction) + Send + Sync>;
+
+/// The set of event listeners attached to an engine created with
+/// `ExecutionEngine::with_event_listeners`.
+#[derive(Clone, Default)]
+pub struct JitEventListeners {
+    gdb: bool,
+    perf: bool,
+    callbacks: Vec<EmittedFunctionCallback>,
+}
+
+impl JitEventListeners {
+    /// Constructs an empty set of listeners.
+    ///
+    /// # Returns
+    /// A new instance of `JitEventListeners`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers emitted functions with an attached `gdb`.
+    ///
+    /// # Returns
+    /// The updated `JitEventListeners`.
+    pub fn with_gdb(mut self) -> Self {
+        self.gdb = true;
+        self
+    }
+
+    /// Writes the perf map and jitdump files `perf` uses to symbolize JIT-compiled code.
+    ///
+    /// # Returns
+    /// The updated `JitEventListeners`.
+    pub fn with_perf(mut self) -> Self {
+        self.perf = true;
+        self
+    }
+
+    /// Invokes a Rust callback for every emitted function.
+    ///
+    /// The callback is invoked once per function defined by the module, after the module has been
+    /// linked and before `ExecutionEngine::with_event_listeners` returns.
+    ///
+    /// # Parameters
+    /// * `callback` - The callback receiving each emitted function.
+    ///
+    /// # Returns
+    /// The updated `JitEventListeners`.
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&EmittedFunction) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    /// Logs the name, address and machine code size of every emitted function.
+    ///
+    /// # Parameters
+    /// * `logger` - The logger to write to.
+    ///
+    /// # Returns
+    /// The updated `JitEventListeners`.
+    pub fn with_logger(self, logger: Logger) -> Self {
+        self.with_callback(move |function| {
+            logging::core::log_info(&logger, &format!("Emitted function '{}' at {:#x} ({} bytes).", function.name, function.address, function.size));
+        })
+    }
+
+    /// Attaches the GDB and perf listeners to an LLJIT instance's object linking layer.
+    ///
+    /// # Parameters
+    /// * `jit` - The LLJIT instance, before any object has been added.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if a requested listener is not supported by
+    /// the LLVM build.
+    fn attach(&self, jit: LLVMOrcLLJITRef) -> Result<(), String> {
+        // Both listeners are process-wide singletons owned by LLVM, so they are shared by every
+        // engine and never disposed.
+        unsafe {
+            let layer = lljit::LLVMOrcLLJITGetObjLinkingLayer(jit);
+            if self.gdb {
+                let listener = execution_engine::LLVMCreateGDBRegistrationListener();
+                if listener.is_null() {
+                    return Err("GDB JIT registration is not supported by this LLVM build.".to_string());
+                }
+                ee::LLVMOrcRTDyldObjectLinkingLayerRegisterJITEventListener(layer, listener);
+            }
+            if self.perf {
+                let listener = execution_engine::LLVMCreatePerfJITEventListener();
+                if listener.is_null() {
+                    return Err("Perf JIT events are not supported by this LLVM build.".to_string());
+                }
+                ee::LLVMOrcRTDyldObjectLinkingLayerRegisterJITEventListener(layer, listener);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports every function defined by a linked module to the registered callbacks.
+    ///
+    /// # Parameters
+    /// * `engine` - The engine the module was linked into.
+    /// * `module_ptr` - The module the engine's object was compiled from.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success, or `Err(String)` if a function could not be resolved.
+    fn notify(&self, engine: &ExecutionEngine, module_ptr: LLVMModuleRef) -> Result<(), String> {
+        if self.callbacks.is_empty() {
+            return Ok(());
+        }
+
+        let symbols = read_symbols(engine.get_object().ok_or("Engine has no linked object file.")?)?;
+        for name in defined_function_names(module_ptr) {
+            let size = find_function_symbol(&symbols, &name).map_or(0, |symbol| symbol.size);
+            let address = engine.get_function_address(&name)?;
+            let function = EmittedFunction { name, address, size };
+            for callback in &self.callbacks {
+                callback(&function);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects the names of the functions a module defines.
+///
+/// # Parameters
+/// * `module_ptr` - The module to inspect.
+///
+/// # Returns
+/// The names of every function with a body, in module order.
+pub(crate) fn defined_function_names(module_ptr: LLVMModuleRef) -> Vec<String> {
+    let mut names = Vec::new();
+    unsafe {
+        let mut function = core::LLVMGetFirstFunction(module_ptr);
+        while !function.is_null() {
+            if core::LLVMIsDeclaration(function) == 0 {
+                names.push(inspect::value_name(function));
+            }
+            function = core::LLVMGetNextFunction(function);
+        }
+    }
+    names
+}
+
+impl ExecutionEngine {
+    /// Constructs a new `ExecutionEngine` with JIT event listeners attached.
+    ///
+    /// The module is compiled for the host up front and linked with ORC LLJIT, since MCJIT does
+    /// not expose listener registration through LLVM's C API. This is the only constructor that
+    /// attaches listeners: engines from `new` and `with_object_cache` cannot have any. Callbacks
+    /// are invoked for every function in the module before this returns. Like engines loaded from
+    /// an object cache, these engines do not support `run_function` or static constructors in
+    /// `run_main`.
+    ///
+    /// # Parameters
+    /// * `module` - A thread-safe `SafeLLVMPointer` containing an `LLVMModuleRef`.
+    /// * `listeners` - The listeners to attach.
+    /// * `debug_info` - If true, records debugging information with the process-wide default logger.
+    ///
+    /// # Returns
+    /// Returns `Ok(ExecutionEngine)` on success, or `Err(String)` if the module could not be
+    /// compiled or linked, or a listener is not supported.
+    pub fn with_event_listeners(module: Arc<RwLock<SafeLLVMPointer>>, listeners: &JitEventListeners, debug_info: bool) -> Result<Self, String> {
+        GeneralTargetConfigurator.configure();
+
+        let logger = if debug_info {
+            Some(logging::core::shared_logger())
+        } else {
+            None
+        };
+
+        let module_ptr = IRManager::read_module_ptr(&module).ok_or("Invalid module pointer.")?;
+        let object = HostTarget::detect().compile(module_ptr)?;
+
+        let jit = orc::create_lljit()?;
+        listeners.attach(jit)?;
+        orc::add_object_file(jit, &object, DEFAULT_MODULE_NAME)?;
+
+        let engine = ExecutionEngine::from_lljit(jit, module, object, logger)?;
+        listeners.notify(&engine, module_ptr)?;
+        Ok(engine)
+    }
+}
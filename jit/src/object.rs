@@ -0,0 +1,116 @@
+//! Inspection of compiled object files.
+//!
+//! Object files produced for the host carry a symbol table recording where each function's machine
+//! code starts and how long it is, which the JIT uses to report emitted functions and to
+//! disassemble them.
+
+extern crate llvm_sys as llvm;
+use std::ffi::{c_char, CStr, CString};
+use llvm::{core, object};
+
+/// A symbol defined in an object file.
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectSymbol {
+    /// The symbol name as it appears in the object file, including any global prefix.
+    pub(crate) name: String,
+    /// The address of the symbol within the object file.
+    pub(crate) address: u64,
+    /// The size of the symbol in bytes.
+    pub(crate) size: u64,
+    /// The bytes of the symbol as stored in the object file, before relocation.
+    pub(crate) contents: Vec<u8>,
+}
+
+/// Reads the sized symbols of an object file.
+///
+/// # Parameters
+/// * `object` - The contents of the object file.
+///
+/// # Returns
+/// Returns `Ok(Vec<ObjectSymbol>)` containing every symbol with a non-zero size defined in a
+/// section of the object, or `Err(String)` if the object file could not be parsed.
+pub(crate) fn read_symbols(object: &[u8]) -> Result<Vec<ObjectSymbol>, String> {
+    let buffer_name = CString::new("object").map_err(|_| "Failed to create CString for buffer name.")?;
+    let mut symbols = Vec::new();
+
+    unsafe {
+        let buffer = core::LLVMCreateMemoryBufferWithMemoryRange(object.as_ptr() as *const c_char, object.len(), buffer_name.as_ptr(), 0);
+        let mut error: *mut c_char = std::ptr::null_mut();
+        let binary = object::LLVMCreateBinary(buffer, core::LLVMGetGlobalContext(), &mut error);
+        if binary.is_null() {
+            core::LLVMDisposeMemoryBuffer(buffer);
+            let message = if error.is_null() { "Unknown error".to_string() } else { CStr::from_ptr(error).to_string_lossy().into_owned() };
+            if !error.is_null() {
+                core::LLVMDisposeMessage(error);
+            }
+            return Err(format!("Failed to parse object file: {}", message));
+        }
+
+        let symbol_iterator = object::LLVMObjectFileCopySymbolIterator(binary);
+        let section_iterator = object::LLVMObjectFileCopySectionIterator(binary);
+        while object::LLVMObjectFileIsSymbolIteratorAtEnd(binary, symbol_iterator) == 0 {
+            let size = object::LLVMGetSymbolSize(symbol_iterator);
+            let raw_name = object::LLVMGetSymbolName(symbol_iterator);
+            if size > 0 && !raw_name.is_null() {
+                object::LLVMMoveToContainingSection(section_iterator, symbol_iterator);
+                if object::LLVMObjectFileIsSectionIteratorAtEnd(binary, section_iterator) == 0 {
+                    let address = object::LLVMGetSymbolAddress(symbol_iterator);
+                    symbols.push(ObjectSymbol {
+                        name: CStr::from_ptr(raw_name).to_string_lossy().into_owned(),
+                        address,
+                        size,
+                        contents: section_slice(object, section_iterator, address, size).unwrap_or_default(),
+                    });
+                }
+            }
+            object::LLVMMoveToNextSymbol(symbol_iterator);
+        }
+
+        object::LLVMDisposeSectionIterator(section_iterator);
+        object::LLVMDisposeSymbolIterator(symbol_iterator);
+        object::LLVMDisposeBinary(binary);
+        core::LLVMDisposeMemoryBuffer(buffer);
+    }
+
+    Ok(symbols)
+}
+
+/// Copies part of a section's contents.
+///
+/// The range is checked against both the section and the object file itself, so sections
+/// without contents in the file, such as zero-initialized data, never cause a read outside it.
+///
+/// # Parameters
+/// * `object` - The contents of the object file the section belongs to.
+/// * `section_iterator` - An iterator positioned at the section.
+/// * `address` - The address of the first byte to copy, in the object file's address space.
+/// * `size` - The number of bytes to copy.
+///
+/// # Returns
+/// An `Option` containing the bytes, or `None` if the range lies outside the section or the file.
+unsafe fn section_slice(object: &[u8], section_iterator: object::LLVMSectionIteratorRef, address: u64, size: u64) -> Option<Vec<u8>> {
+    let contents = object::LLVMGetSectionContents(section_iterator) as usize;
+    let start = address.checked_sub(object::LLVMGetSectionAddress(section_iterator))?;
+    let end = start.checked_add(size)?;
+    if end > object::LLVMGetSectionSize(section_iterator) {
+        return None;
+    }
+
+    let file_offset = contents.checked_sub(object.as_ptr() as usize)?.checked_add(start as usize)?;
+    object.get(file_offset..file_offset.checked_add(size as usize)?).map(<[u8]>::to_vec)
+}
+
+/// Finds the symbol of a function, allowing for a global prefix such as the leading underscore
+/// used on Mach-O.
+///
+/// # Parameters
+/// * `symbols` - The symbols of an object file.
+/// * `function_name` - The unmangled function name.
+///
+/// # Returns
+/// An `Option` containing the matching symbol.
+pub(crate) fn find_function_symbol<'a>(symbols: &'a [ObjectSymbol], function_name: &str) -> Option<&'a ObjectSymbol> {
+    symbols.iter()
+        .find(|symbol| symbol.name == function_name)
+        .or_else(|| symbols.iter().find(|symbol| symbol.name.strip_prefix('_') == Some(function_name)))
+}
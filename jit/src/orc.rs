@@ -0,0 +1,97 @@
+//! Thin wrappers over LLVM's ORC LLJIT, used by engines that link precompiled object files.
+//!
+//! MCJIT has no C API for loading object files, so engines created from an object cache link
+//! their code with LLJIT instead. The object linking layer is always RuntimeDyld, which is the
+//! layer JIT event listeners can be attached to.
+
+extern crate llvm_sys as llvm;
+use std::ffi::{c_char, c_void, CStr, CString};
+use llvm::{core, error::{self, LLVMErrorRef}, orc2::{self, ee, lljit::{self, LLVMOrcLLJITRef}, LLVMOrcExecutionSessionRef, LLVMOrcObjectLayerRef}};
+
+/// Converts an LLVM error into its message, consuming the error.
+///
+/// # Parameters
+/// * `error` - A non-null LLVM error.
+///
+/// # Returns
+/// The error message.
+pub(crate) fn take_error_message(error: LLVMErrorRef) -> String {
+    unsafe {
+        let raw_message = error::LLVMGetErrorMessage(error);
+        let message = CStr::from_ptr(raw_message).to_string_lossy().into_owned();
+        error::LLVMDisposeErrorMessage(raw_message);
+        message
+    }
+}
+
+/// Converts an LLVM error into a `Result`, consuming the error.
+///
+/// # Parameters
+/// * `error` - An LLVM error, which is null on success.
+///
+/// # Returns
+/// Returns `Ok(())` if `error` is null, or `Err(String)` containing its message.
+pub(crate) fn check_error(error: LLVMErrorRef) -> Result<(), String> {
+    if error.is_null() {
+        Ok(())
+    } else {
+        Err(take_error_message(error))
+    }
+}
+
+/// Creates the RuntimeDyld object linking layer for a new LLJIT instance.
+extern "C" fn create_object_linking_layer(_ctx: *mut c_void, session: LLVMOrcExecutionSessionRef, _triple: *const c_char) -> LLVMOrcObjectLayerRef {
+    unsafe { ee::LLVMOrcCreateRTDyldObjectLinkingLayerWithSectionMemoryManager(session) }
+}
+
+/// Creates an LLJIT instance for the host, able to resolve symbols from the running process.
+///
+/// # Returns
+/// Returns `Ok(LLVMOrcLLJITRef)` on success, or `Err(String)` if the JIT could not be created.
+pub(crate) fn create_lljit() -> Result<LLVMOrcLLJITRef, String> {
+    unsafe {
+        let builder = lljit::LLVMOrcCreateLLJITBuilder();
+        lljit::LLVMOrcLLJITBuilderSetObjectLinkingLayerCreator(builder, create_object_linking_layer, std::ptr::null_mut());
+
+        let mut jit: LLVMOrcLLJITRef = std::ptr::null_mut();
+        check_error(lljit::LLVMOrcCreateLLJIT(&mut jit, builder))?;
+
+        let mut generator = std::ptr::null_mut();
+        check_error(orc2::LLVMOrcCreateDynamicLibrarySearchGeneratorForProcess(&mut generator, lljit::LLVMOrcLLJITGetGlobalPrefix(jit), None, std::ptr::null_mut()))?;
+        orc2::LLVMOrcJITDylibAddGenerator(lljit::LLVMOrcLLJITGetMainJITDylib(jit), generator);
+
+        Ok(jit)
+    }
+}
+
+/// Adds an object file to the main JIT dylib of an LLJIT instance.
+///
+/// # Parameters
+/// * `jit` - The LLJIT instance.
+/// * `object` - The contents of the object file.
+/// * `name` - A name identifying the object in diagnostics.
+///
+/// # Returns
+/// Returns `Ok(())` on success, or `Err(String)` if the object could not be added.
+pub(crate) fn add_object_file(jit: LLVMOrcLLJITRef, object: &[u8], name: &str) -> Result<(), String> {
+    let name_c = CString::new(name).map_err(|_| "Failed to create CString for object name.")?;
+    unsafe {
+        let buffer = core::LLVMCreateMemoryBufferWithMemoryRangeCopy(object.as_ptr() as *const c_char, object.len(), name_c.as_ptr());
+        check_error(lljit::LLVMOrcLLJITAddObjectFile(jit, lljit::LLVMOrcLLJITGetMainJITDylib(jit), buffer))
+    }
+}
+
+/// Looks up the address of a symbol, linking the objects that define it if necessary.
+///
+/// # Parameters
+/// * `jit` - The LLJIT instance.
+/// * `name` - The unmangled name of the symbol.
+///
+/// # Returns
+/// Returns `Ok(u64)` containing the symbol's address, or `Err(String)` if it could not be resolved.
+pub(crate) fn lookup(jit: LLVMOrcLLJITRef, name: &str) -> Result<u64, String> {
+    let name_c = CString::new(name).map_err(|_| "Failed to create CString for symbol name.")?;
+    let mut address: orc2::LLVMOrcExecutorAddress = 0;
+    check_error(unsafe { lljit::LLVMOrcLLJITLookup(jit, &mut address, name_c.as_ptr()) })?;
+    Ok(address)
+}
@@ -0,0 +1,78 @@
+//! Sharing one compiled module between threads.
+//!
+//! An `ExecutionEngine` wraps raw LLVM pointers and is neither `Send` nor `Sync`, and its methods
+//! may compile code on first use. A `SharedExecutionEngine` serializes all access to the engine
+//! behind a mutex. Functions are looked up once, while the lock is held, into `JitFunction`
+//! handles which call the compiled code directly, so any number of threads can invoke them
+//! concurrently without contending on the engine.
+
+use std::sync::{Arc, Mutex};
+use crate::{core::ExecutionEngine, function::{JitFunction, JitSignature}};
+
+/// An execution engine behind a mutex.
+struct EngineCell {
+    engine: Mutex<ExecutionEngine>,
+}
+
+// SAFETY: The engine's LLVM objects are only reached through the mutex, so no two threads use
+// them at the same time. Compiled code is immutable once emitted and stays mapped for as long as
+// the engine is alive.
+unsafe impl Send for EngineCell {}
+unsafe impl Sync for EngineCell {}
+
+/// An `ExecutionEngine` that can be shared between threads.
+///
+/// Cloning a `SharedExecutionEngine` is cheap and yields another handle to the same engine.
+#[derive(Clone)]
+pub struct SharedExecutionEngine {
+    cell: Arc<EngineCell>,
+}
+
+impl SharedExecutionEngine {
+    /// Constructs a new `SharedExecutionEngine` taking ownership of an engine.
+    ///
+    /// The engine's module must not be modified through other handles once it is shared.
+    ///
+    /// # Parameters
+    /// * `engine` - The engine to share.
+    ///
+    /// # Returns
+    /// A new instance of `SharedExecutionEngine`.
+    pub fn new(engine: ExecutionEngine) -> Self {
+        Self {
+            cell: Arc::new(EngineCell { engine: Mutex::new(engine) }),
+        }
+    }
+
+    /// Runs a closure with exclusive access to the engine.
+    ///
+    /// # Parameters
+    /// * `f` - The closure to run, such as a call to `execute` or `run_main`.
+    ///
+    /// # Returns
+    /// Returns `Ok` containing the result of the closure, or `Err(String)` if the engine lock is
+    /// poisoned.
+    pub fn with_engine<F, ReturnType>(&self, f: F) -> Result<ReturnType, String>
+    where
+        F: FnOnce(&mut ExecutionEngine) -> ReturnType,
+    {
+        let mut engine = self.cell.engine.lock().map_err(|e| format!("Failed to obtain lock on engine: {}", e))?;
+        Ok(f(&mut engine))
+    }
+
+    /// Looks up a compiled function once, returning a handle that can be called from any thread.
+    ///
+    /// The signature `F` is checked against the LLVM type of the function, and the function is
+    /// compiled if it has not been compiled yet. The returned `JitFunction` is `Send + Sync` and
+    /// keeps the engine's compiled code alive, so it may outlive this `SharedExecutionEngine`.
+    ///
+    /// # Parameters
+    /// * `function_name` - The name of the function to look up.
+    ///
+    /// # Returns
+    /// Returns `Ok(JitFunction<F>)` on success, or `Err(String)` if the function could not be found
+    /// or its signature does not match `F`.
+    pub fn get_function<F: JitSignature>(&self, function_name: &str) -> Result<JitFunction<F>, String> {
+        self.with_engine(|engine| engine.get_function::<F>(function_name))?
+    }
+}
@@ -0,0 +1,222 @@
+//! Mapping between Rust types and LLVM types for values that cross the JIT boundary.
+//!
+//! Reading or writing memory owned by JIT-compiled code is only sound if the Rust type has the same
+//! size and representation as the LLVM type it is read as. The `JitType` trait records which LLVM
+//! types a Rust type may stand in for, so that accessors can reject mismatches before touching memory.
+
+extern crate llvm_sys as llvm;
+use llvm::{core, prelude::LLVMTypeRef, LLVMTypeKind};
+
+/// A Rust type with a known LLVM counterpart.
+///
+/// # Safety
+/// Implementors must have the same size and in-memory representation as every LLVM type for which
+/// `matches_llvm_type` returns true.
+pub unsafe trait JitType: Copy + 'static {
+    /// Determines whether the given LLVM type can be read as or written from this Rust type.
+    ///
+    /// # Parameters
+    /// * `type_ref` - The LLVM type to compare against.
+    ///
+    /// # Safety
+    /// `type_ref` must be a valid LLVM type whose context is still alive.
+    ///
+    /// # Returns
+    /// True if the types are compatible, false otherwise.
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool;
+
+    /// A human-readable description of the LLVM type this Rust type corresponds to.
+    ///
+    /// # Returns
+    /// A string such as `i32` or `double`, used in error messages.
+    fn llvm_type_name() -> &'static str;
+}
+
+/// Determines whether an LLVM type is an integer of the given width.
+///
+/// # Parameters
+/// * `type_ref` - The LLVM type to inspect.
+/// * `bits` - The expected bit width.
+///
+/// # Safety
+/// `type_ref` must be a valid LLVM type.
+///
+/// # Returns
+/// True if `type_ref` is an integer type of exactly `bits` bits.
+unsafe fn is_int_type(type_ref: LLVMTypeRef, bits: u32) -> bool {
+    core::LLVMGetTypeKind(type_ref) == LLVMTypeKind::LLVMIntegerTypeKind && core::LLVMGetIntTypeWidth(type_ref) == bits
+}
+
+/// Determines whether an LLVM type is of the given kind.
+///
+/// # Parameters
+/// * `type_ref` - The LLVM type to inspect.
+/// * `kind` - The expected type kind.
+///
+/// # Safety
+/// `type_ref` must be a valid LLVM type.
+///
+/// # Returns
+/// True if `type_ref` is of kind `kind`.
+unsafe fn is_type_kind(type_ref: LLVMTypeRef, kind: LLVMTypeKind) -> bool {
+    core::LLVMGetTypeKind(type_ref) == kind
+}
+
+/// Implements `JitType` for Rust integer types of the given LLVM bit width.
+macro_rules! impl_jit_int_type {
+    ($bits:expr, $name:expr, $($rust_type:ty),+) => {
+        $(
+            unsafe impl JitType for $rust_type {
+                unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+                    is_int_type(type_ref, $bits)
+                }
+
+                fn llvm_type_name() -> &'static str {
+                    $name
+                }
+            }
+        )+
+    };
+}
+
+impl_jit_int_type!(8, "i8", i8, u8);
+impl_jit_int_type!(16, "i16", i16, u16);
+impl_jit_int_type!(32, "i32", i32, u32);
+impl_jit_int_type!(64, "i64", i64, u64);
+
+#[cfg(target_pointer_width = "64")]
+impl_jit_int_type!(64, "i64", isize, usize);
+
+#[cfg(target_pointer_width = "32")]
+impl_jit_int_type!(32, "i32", isize, usize);
+
+/// Booleans are stored by LLVM as a single byte holding zero or one.
+unsafe impl JitType for bool {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_int_type(type_ref, 1)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "i1"
+    }
+}
+
+unsafe impl JitType for f32 {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_type_kind(type_ref, LLVMTypeKind::LLVMFloatTypeKind)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "float"
+    }
+}
+
+unsafe impl JitType for f64 {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_type_kind(type_ref, LLVMTypeKind::LLVMDoubleTypeKind)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "double"
+    }
+}
+
+unsafe impl<T: 'static> JitType for *const T {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_type_kind(type_ref, LLVMTypeKind::LLVMPointerTypeKind)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "ptr"
+    }
+}
+
+unsafe impl<T: 'static> JitType for *mut T {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_type_kind(type_ref, LLVMTypeKind::LLVMPointerTypeKind)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "ptr"
+    }
+}
+
+/// A Rust type that can be returned from JIT-compiled code: any `JitType` except `bool`, or `()`
+/// for `void`.
+///
+/// # Safety
+/// Implementors must be returned with the same calling convention as every LLVM type for which
+/// `matches_llvm_type` returns true.
+pub unsafe trait JitReturnType: 'static {
+    /// Determines whether the given LLVM return type can be returned as this Rust type.
+    ///
+    /// # Parameters
+    /// * `type_ref` - The LLVM return type to compare against.
+    ///
+    /// # Safety
+    /// `type_ref` must be a valid LLVM type whose context is still alive.
+    ///
+    /// # Returns
+    /// True if the types are compatible, false otherwise.
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool;
+
+    /// A human-readable description of the LLVM return type this Rust type corresponds to.
+    ///
+    /// # Returns
+    /// A string such as `i32` or `void`, used in error messages.
+    fn llvm_type_name() -> &'static str;
+}
+
+/// Implements `JitReturnType` for `JitType`s that LLVM returns exactly as Rust expects them.
+///
+/// `bool` is deliberately left out: LLVM returns an `i1` without defining the upper bits of the
+/// return register unless the return carries `zeroext`, so reading it as a Rust `bool` could
+/// produce an invalid value. Functions returning `i1` can be called through an `i8` or `i32`
+/// return instead, after extending the result in IR.
+macro_rules! impl_jit_return_type {
+    ($($rust_type:ty),+) => {
+        $(
+            unsafe impl JitReturnType for $rust_type {
+                unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+                    <$rust_type as JitType>::matches_llvm_type(type_ref)
+                }
+
+                fn llvm_type_name() -> &'static str {
+                    <$rust_type as JitType>::llvm_type_name()
+                }
+            }
+        )+
+    };
+}
+
+impl_jit_return_type!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize, f32, f64);
+
+unsafe impl<T: 'static> JitReturnType for *const T {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        <*const T as JitType>::matches_llvm_type(type_ref)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        <*const T as JitType>::llvm_type_name()
+    }
+}
+
+unsafe impl<T: 'static> JitReturnType for *mut T {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        <*mut T as JitType>::matches_llvm_type(type_ref)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        <*mut T as JitType>::llvm_type_name()
+    }
+}
+
+unsafe impl JitReturnType for () {
+    unsafe fn matches_llvm_type(type_ref: LLVMTypeRef) -> bool {
+        is_type_kind(type_ref, LLVMTypeKind::LLVMVoidTypeKind)
+    }
+
+    fn llvm_type_name() -> &'static str {
+        "void"
+    }
+}
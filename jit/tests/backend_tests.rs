@@ -0,0 +1,90 @@
+mod fixtures;
+
+use common::constants::{DEFAULT_BASIC_BLOCK_NAME, DEFAULT_FUNCTION_NAME, DEFAULT_MODULE_NAME};
+use ir::core::{IRManager, ModuleTag};
+use jit::{backend::{ExecutionBackend, GenericValue, GenericValueKind}, core::ExecutionEngine, interpreter::Interpreter};
+use fixtures::ModuleFixture;
+
+/// Builds a module with a function `constant` returning 42 and a function `increment` adding one to its i64 parameter.
+fn build_arithmetic_module(pools: &mut IRManager) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    fixture.add_constant_function(pools, "constant", 42);
+    fixture.add_increment_function(pools, "increment");
+    fixture.module_tag
+}
+
+#[test]
+fn test_backends_agree_on_constant() {
+    let mut pools = IRManager::new();
+    let module_tag = build_arithmetic_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+
+    let mut interpreter = Interpreter::new(module.clone(), false);
+    let mut engine = ExecutionEngine::new(module, false);
+
+    let backends: [&mut dyn ExecutionBackend; 2] = [&mut interpreter, &mut engine];
+    for backend in backends {
+        let result = backend.run_function("constant", &[]).expect("Failed to run function");
+        assert_eq!(result.to_i64(), 42, "Backend did not return the expected integer value");
+    }
+}
+
+#[test]
+fn test_interpreter_with_arguments() {
+    let mut pools = IRManager::new();
+    let module_tag = build_arithmetic_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut interpreter = Interpreter::new(module, false);
+
+    let result = interpreter.run_function("increment", &[GenericValue::from_int(64, -5i64 as u64, true).expect("Failed to create integer")]).expect("Failed to run function");
+    assert_eq!(result.to_i64(), -4, "Interpreter did not return the expected integer value");
+}
+
+#[test]
+fn test_run_function_rejects_invalid_arguments() {
+    let mut pools = IRManager::new();
+    let module_tag = build_arithmetic_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+
+    let mut interpreter = Interpreter::new(module.clone(), false);
+    let mut engine = ExecutionEngine::new(module, false);
+
+    assert!(interpreter.run_function("increment", &[]).is_err(), "Missing arguments should be rejected");
+    assert!(interpreter.run_function("increment", &[GenericValue::from_f64(1.0)]).is_err(), "Mismatched argument types should be rejected");
+    assert!(interpreter.run_function("missing", &[]).is_err(), "Running a missing function should fail");
+    assert!(engine.run_function("increment", &[GenericValue::from_int(64, 1, true).expect("Failed to create integer")]).is_err(), "MCJIT should reject signatures it cannot call generically");
+}
+
+#[test]
+fn test_generic_int_rejects_invalid_width() {
+    assert!(GenericValue::from_int(0, 1, false).is_err(), "A zero bit width should be rejected");
+    assert!(GenericValue::from_int(u32::MAX, 1, false).is_err(), "An oversized bit width should be rejected");
+    assert_eq!(GenericValue::from_int(1, 1, false).expect("Failed to create integer").kind(), GenericValueKind::Int(1));
+}
+
+#[test]
+fn test_backends_agree_on_main() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 32).expect("Failed to create int type");
+    let char_type = pools.int_type(context_tag, 8).expect("Failed to create char type");
+    let argv_type = pools.pointer_type(char_type).expect("Failed to create pointer type");
+    let function_value = pools.create_function(Some(int_type), &[int_type, argv_type], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = pools.add_function_to_module(module_tag, DEFAULT_FUNCTION_NAME, function_value).expect("Failed to add function to module");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let bb_tag = pools.create_basic_block(context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    let argc = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+
+    pools.position_builder_at_end(builder_tag, bb_tag).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, argc).expect("Failed to create return");
+
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut interpreter = Interpreter::new(module.clone(), false);
+    let mut engine = ExecutionEngine::new(module, false);
+
+    let interpreted = ExecutionBackend::run_main(&mut interpreter, &["program", "first"], &[]).expect("Failed to interpret main");
+    let compiled = ExecutionBackend::run_main(&mut engine, &["program", "first"], &[]).expect("Failed to run main");
+    assert_eq!(interpreted, 2, "Interpreter did not receive the expected argument count");
+    assert_eq!(interpreted, compiled, "Backends disagree on the exit code");
+}
@@ -0,0 +1,121 @@
+mod fixtures;
+
+use std::{env, fs, thread};
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::IRManager;
+use jit::{backend::ExecutionBackend, cache::ObjectCache, core::ExecutionEngine};
+use fixtures::build_constant_module;
+
+/// Creates an empty object cache in a directory unique to the calling test.
+fn create_cache(test_name: &str) -> ObjectCache {
+    let directory = env::temp_dir().join(format!("safe_llvm_cache_{}_{}", test_name, std::process::id()));
+    let cache = ObjectCache::new(directory).expect("Failed to create object cache");
+    cache.clear().expect("Failed to clear object cache");
+    cache
+}
+
+#[test]
+fn test_cached_engine_executes_and_populates_cache() {
+    let cache = create_cache("populate");
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+    let key = cache.key_for_module(&module).expect("Failed to compute cache key");
+    assert!(!cache.contains(&key), "Cache should start out empty");
+
+    let mut engine = ExecutionEngine::with_object_cache(module, &cache, false).expect("Failed to create cached engine");
+    assert!(cache.contains(&key), "Compiled object should have been cached");
+
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 42, "Function did not return the expected integer value");
+    assert!(engine.execute::<i64, ()>("missing", ()).is_err(), "Executing a missing function should fail");
+    let unsupported = engine.run_function(DEFAULT_FUNCTION_NAME, &[]).expect_err("Cached engines should reject run_function");
+    assert!(unsupported.contains("with_object_cache"), "The error should name the constructor: {}", unsupported);
+}
+
+#[test]
+fn test_cached_engine_reuses_identical_module() {
+    let cache = create_cache("reuse");
+
+    let mut first_pools = IRManager::new();
+    let first_module = build_constant_module(&mut first_pools, 7);
+    let mut second_pools = IRManager::new();
+    let second_module = build_constant_module(&mut second_pools, 7);
+    let key = cache.key_for_module(&first_module).expect("Failed to compute cache key");
+    assert_eq!(key, cache.key_for_module(&second_module).expect("Failed to compute cache key"), "Identical modules should share a cache key");
+
+    ExecutionEngine::with_object_cache(first_module, &cache, false).expect("Failed to create cached engine");
+    let cached_object = cache.load(&key).expect("Compiled object should have been cached");
+
+    let mut engine = ExecutionEngine::with_object_cache(second_module, &cache, false).expect("Failed to create cached engine");
+    assert_eq!(cache.load(&key).expect("Cached object should still exist"), cached_object, "Cached object should have been reused");
+
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 7, "Function did not return the expected integer value");
+}
+
+#[test]
+fn test_different_modules_have_different_keys() {
+    let cache = create_cache("keys");
+    let mut pools = IRManager::new();
+    let first_module = build_constant_module(&mut pools, 1);
+    let second_module = build_constant_module(&mut pools, 2);
+
+    let first_key = cache.key_for_module(&first_module).expect("Failed to compute cache key");
+    let second_key = cache.key_for_module(&second_module).expect("Failed to compute cache key");
+    assert_ne!(first_key, second_key, "Different modules should not share a cache key");
+}
+
+#[test]
+fn test_corrupt_cached_object_is_recompiled() {
+    let cache = create_cache("corrupt");
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 9);
+    let key = cache.key_for_module(&module).expect("Failed to compute cache key");
+    cache.store(&key, b"not an object file").expect("Failed to store corrupt object");
+
+    let mut engine = ExecutionEngine::with_object_cache(module, &cache, false).expect("A corrupt cached object should be recompiled");
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 9, "Function did not return the expected integer value");
+    assert_ne!(cache.load(&key).expect("Recompiled object should have been cached"), b"not an object file", "Corrupt object should have been replaced");
+}
+
+#[test]
+fn test_entry_of_a_different_module_is_not_linked() {
+    let cache = create_cache("collision");
+    let mut pools = IRManager::new();
+    let first_module = build_constant_module(&mut pools, 3);
+    let second_module = build_constant_module(&mut pools, 4);
+    let first_key = cache.key_for_module(&first_module).expect("Failed to compute cache key");
+    let second_key = cache.key_for_module(&second_module).expect("Failed to compute cache key");
+
+    // Simulate a hash collision by placing the first module's entry under the second module's key
+    ExecutionEngine::with_object_cache(first_module, &cache, false).expect("Failed to create cached engine");
+    let entry_path = |key: &str| cache.get_directory().join(format!("{}.o", key));
+    fs::copy(entry_path(&first_key), entry_path(&second_key)).expect("Failed to copy cache entry");
+
+    let mut engine = ExecutionEngine::with_object_cache(second_module, &cache, false).expect("Failed to create cached engine");
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 4, "The colliding entry should have been recompiled");
+    assert_ne!(cache.load(&second_key), cache.load(&first_key), "The colliding entry should have been replaced");
+}
+
+#[test]
+fn test_concurrent_stores_never_tear() {
+    let cache = create_cache("concurrent");
+    let objects: Vec<Vec<u8>> = (0..8u8).map(|index| vec![index; 1 << 16]).collect();
+
+    thread::scope(|scope| {
+        for object in &objects {
+            let cache = &cache;
+            scope.spawn(move || cache.store("shared", object).expect("Failed to store object"));
+        }
+    });
+
+    let stored = cache.load("shared").expect("An object should have been stored");
+    assert!(objects.contains(&stored), "Stored object should be exactly one of the written objects");
+    let leftovers = fs::read_dir(cache.get_directory()).expect("Failed to read cache directory")
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "tmp"))
+        .count();
+    assert_eq!(leftovers, 0, "No temporary files should be left behind");
+}
@@ -0,0 +1,42 @@
+mod fixtures;
+
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::{IRManager, ModuleTag};
+use jit::core::ExecutionEngine;
+use fixtures::ModuleFixture;
+
+/// Builds a module whose `main` function writes `text` to standard output and returns zero.
+fn build_print_module(pools: &mut IRManager, text: &str) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    let fwrite = fixture.declare_fwrite(pools);
+
+    fixture.add_function(pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    fixture.build_print(pools, fwrite, "stdout", text);
+    let zero = pools.create_integer(fixture.context_tag, 0).expect("Failed to create integer");
+    pools.nonvoid_return(fixture.builder_tag, zero).expect("Failed to create return");
+
+    fixture.module_tag
+}
+
+#[test]
+fn test_execute_captured_stdout() {
+    let mut pools = IRManager::new();
+    let module_tag = build_print_module(&mut pools, "hello from jit\n");
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    let captured = engine.execute_captured::<i64, ()>(DEFAULT_FUNCTION_NAME, ()).expect("Failed to execute function");
+    assert_eq!(captured.return_value, 0, "Function did not return the expected value");
+    assert_eq!(captured.stdout, "hello from jit\n", "Function output was not captured");
+    assert!(captured.stderr.is_empty(), "Function should not write to standard error");
+}
+
+#[test]
+fn test_execute_captured_missing_function() {
+    let mut pools = IRManager::new();
+    let module_tag = build_print_module(&mut pools, "unused");
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.execute_captured::<i64, ()>("missing", ()).is_err(), "Executing a missing function should fail");
+}
@@ -0,0 +1,55 @@
+mod fixtures;
+
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::IRManager;
+use jit::{core::ExecutionEngine, disassembly::DisassemblySource, listeners::JitEventListeners};
+use fixtures::build_constant_module;
+
+#[test]
+fn test_disassemble_compiled_function() {
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+    let engine = ExecutionEngine::new(module, false);
+
+    let disassembly = engine.disassemble(DEFAULT_FUNCTION_NAME).expect("Failed to disassemble function");
+    assert_eq!(disassembly.source, DisassemblySource::Recompiled, "MCJIT engines are disassembled from a recompilation");
+    assert!(!disassembly.instructions.is_empty(), "Function should have instructions");
+    assert!(disassembly.instructions.iter().any(|instruction| instruction.text.starts_with("ret")), "Function should return");
+    assert!(disassembly.instructions.windows(2).all(|pair| pair[0].address + pair[0].bytes.len() as u64 == pair[1].address), "Instructions should be contiguous");
+    assert!(disassembly.to_string().starts_with("main:\n"), "Listing should be labelled with the function name");
+}
+
+#[test]
+fn test_disassemble_linked_function() {
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+    let engine = ExecutionEngine::with_event_listeners(module, &JitEventListeners::new(), false).expect("Failed to create engine");
+
+    let disassembly = engine.disassemble(DEFAULT_FUNCTION_NAME).expect("Failed to disassemble function");
+    assert_eq!(disassembly.source, DisassemblySource::LoadedCode, "Linked engines are disassembled from the loaded code");
+    assert!(disassembly.instructions.iter().any(|instruction| instruction.text.starts_with("ret")), "Function should return");
+}
+
+#[test]
+fn test_disassemble_missing_function() {
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+    let engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.disassemble("missing").is_err(), "Disassembling a missing function should fail");
+
+    let linked = ExecutionEngine::with_event_listeners(build_constant_module(&mut pools, 42), &JitEventListeners::new(), false).expect("Failed to create engine");
+    assert!(linked.disassemble("missing").is_err(), "Disassembling a missing function of a linked engine should fail");
+}
+
+#[test]
+fn test_disassemble_compiled_and_linked_engines() {
+    let mut pools = IRManager::new();
+    let compiled = ExecutionEngine::new(build_constant_module(&mut pools, 42), false);
+    let linked = ExecutionEngine::with_event_listeners(build_constant_module(&mut pools, 42), &JitEventListeners::new(), false).expect("Failed to create engine");
+
+    let compiled_texts: Vec<String> = compiled.disassemble(DEFAULT_FUNCTION_NAME).expect("Failed to disassemble function").instructions.into_iter().map(|instruction| instruction.text).collect();
+    let linked_texts: Vec<String> = linked.disassemble(DEFAULT_FUNCTION_NAME).expect("Failed to disassemble function").instructions.into_iter().map(|instruction| instruction.text).collect();
+    assert!(compiled_texts.last().is_some_and(|text| text.starts_with("ret")), "The listing should end with the return");
+    assert!(linked_texts.last().is_some_and(|text| text.starts_with("ret")), "The listing should end with the return");
+}
@@ -0,0 +1,99 @@
+//! Module builders shared by the JIT integration tests.
+
+// Each test crate includes this module but only uses some of the builders
+#![allow(dead_code)]
+
+use std::sync::{Arc, RwLock};
+use common::{constants::{DEFAULT_BASIC_BLOCK_NAME, DEFAULT_FUNCTION_NAME, DEFAULT_MODULE_NAME}, pointer::SafeLLVMPointer};
+use ir::core::{BuilderTag, ContextTag, IRManager, ModuleTag, TypeTag, ValueTag};
+
+/// A module under construction, with the context, builder and `i64` type used to fill it.
+pub struct ModuleFixture {
+    pub context_tag: ContextTag,
+    pub module_tag: ModuleTag,
+    pub builder_tag: BuilderTag,
+    pub int_type: TypeTag,
+}
+
+impl ModuleFixture {
+    /// Creates an empty module in a new context.
+    pub fn new(pools: &mut IRManager) -> Self {
+        let context_tag = pools.create_context().expect("Failed to create context");
+        let module_tag = pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module");
+        let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+        let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+        Self { context_tag, module_tag, builder_tag, int_type }
+    }
+
+    /// Declares a function without a body, such as a function of the C library.
+    ///
+    /// Returns the function type and the function, as needed by `build_call`.
+    pub fn declare_function(&self, pools: &mut IRManager, name: &str, return_type: Option<TypeTag>, param_types: &[TypeTag]) -> (TypeTag, ValueTag) {
+        let function_type = pools.create_function(return_type, param_types, false, self.context_tag).expect("Failed to create function prototype");
+        let function_tag = pools.add_function_to_module(self.module_tag, name, function_type).expect("Failed to add function to module");
+        (function_type, function_tag)
+    }
+
+    /// Adds a function and positions the builder at the end of its entry block.
+    pub fn add_function(&self, pools: &mut IRManager, name: &str, return_type: Option<TypeTag>, param_types: &[TypeTag]) -> ValueTag {
+        let (_, function_tag) = self.declare_function(pools, name, return_type, param_types);
+        let bb_tag = pools.create_basic_block(self.context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+        pools.position_builder_at_end(self.builder_tag, bb_tag).expect("Failed to position builder");
+        function_tag
+    }
+
+    /// Adds an `i64` function without parameters returning `value`.
+    pub fn add_constant_function(&self, pools: &mut IRManager, name: &str, value: i64) -> ValueTag {
+        let function_tag = self.add_function(pools, name, Some(self.int_type), &[]);
+        let return_value = pools.create_integer(self.context_tag, value).expect("Failed to create return value");
+        pools.nonvoid_return(self.builder_tag, return_value).expect("Failed to create return");
+        function_tag
+    }
+
+    /// Adds a function returning its `i64` parameter plus one.
+    pub fn add_increment_function(&self, pools: &mut IRManager, name: &str) -> ValueTag {
+        let function_tag = self.add_function(pools, name, Some(self.int_type), &[self.int_type]);
+        let param = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+        let one = pools.create_integer(self.context_tag, 1).expect("Failed to create integer");
+        let sum = pools.build_add(self.builder_tag, param, one, "sum").expect("Failed to build add");
+        pools.nonvoid_return(self.builder_tag, sum).expect("Failed to create return");
+        function_tag
+    }
+
+    /// Builds a call writing `text` to the C stream `stream`, `stdout` or `stderr`, with `fwrite`.
+    ///
+    /// `fwrite` must have been declared with `declare_fwrite`.
+    pub fn build_print(&self, pools: &mut IRManager, fwrite: (TypeTag, ValueTag), stream: &str, text: &str) {
+        let byte_type = pools.int_type(self.context_tag, 8).expect("Failed to create int type");
+        let pointer_type = pools.pointer_type(byte_type).expect("Failed to create pointer type");
+        let text_type = pools.array_type(byte_type, text.len() as u64).expect("Failed to create array type");
+        let text_value = pools.create_string(text).expect("Failed to create string");
+        let text_global = pools.init_global_var(self.module_tag, &format!("{}_text", stream), text_type, Some(text_value)).expect("Failed to add text");
+
+        // Declared without an initializer, so the stream resolves to the C library's global
+        let stream_global = pools.init_global_var(self.module_tag, stream, pointer_type, None).expect("Failed to declare stream");
+        let stream_value = pools.get_var(self.builder_tag, pointer_type, stream_global, stream).expect("Failed to load stream");
+        let one = pools.create_integer(self.context_tag, 1).expect("Failed to create integer");
+        let length = pools.create_integer(self.context_tag, text.len() as i64).expect("Failed to create integer");
+        pools.build_call(self.builder_tag, fwrite.0, fwrite.1, &[text_global, one, length, stream_value], "written").expect("Failed to build call");
+    }
+
+    /// Declares `size_t fwrite(const void *, size_t, size_t, FILE *)`.
+    pub fn declare_fwrite(&self, pools: &mut IRManager) -> (TypeTag, ValueTag) {
+        let byte_type = pools.int_type(self.context_tag, 8).expect("Failed to create int type");
+        let pointer_type = pools.pointer_type(byte_type).expect("Failed to create pointer type");
+        self.declare_function(pools, "fwrite", Some(self.int_type), &[pointer_type, self.int_type, self.int_type, pointer_type])
+    }
+
+    /// Retrieves the finished module.
+    pub fn get_module(&self, pools: &IRManager) -> Arc<RwLock<SafeLLVMPointer>> {
+        pools.get_module(self.module_tag).expect("Failed to retrieve module")
+    }
+}
+
+/// Builds a module whose `main` function returns `value`.
+pub fn build_constant_module(pools: &mut IRManager, value: i64) -> Arc<RwLock<SafeLLVMPointer>> {
+    let fixture = ModuleFixture::new(pools);
+    fixture.add_constant_function(pools, DEFAULT_FUNCTION_NAME, value);
+    fixture.get_module(pools)
+}
@@ -0,0 +1,77 @@
+mod fixtures;
+
+use std::thread;
+use ir::core::{IRManager, ModuleTag};
+use jit::{core::ExecutionEngine, shared::SharedExecutionEngine};
+use fixtures::ModuleFixture;
+
+type AddFn = extern "C" fn(i64, i64) -> i64;
+
+/// Builds a module with a function `add` returning the sum of its two i64 parameters.
+fn build_add_module(pools: &mut IRManager) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    let function_tag = fixture.add_function(pools, "add", Some(fixture.int_type), &[fixture.int_type, fixture.int_type]);
+    let lhs = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+    let rhs = pools.get_param(function_tag, 1).expect("Failed to get parameter");
+    let sum = pools.build_add(fixture.builder_tag, lhs, rhs, "sum").expect("Failed to build add");
+    pools.nonvoid_return(fixture.builder_tag, sum).expect("Failed to create return");
+    fixture.module_tag
+}
+
+#[test]
+fn test_typed_function_call() {
+    let mut pools = IRManager::new();
+    let module_tag = build_add_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    let add = engine.get_function::<AddFn>("add").expect("Failed to look up function");
+    let total = (0..1000i64).fold(0, |total, i| add.call(total, i));
+    assert_eq!(total, 499500, "Typed handle returned an unexpected value");
+    assert_eq!(add.get_name(), "add", "Handle should keep the function name");
+}
+
+#[test]
+fn test_typed_function_rejects_mismatched_signature() {
+    let mut pools = IRManager::new();
+    let module_tag = build_add_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.get_function::<extern "C" fn(i64) -> i64>("add").is_err(), "Wrong parameter count should be rejected");
+    assert!(engine.get_function::<extern "C" fn(i64, f64) -> i64>("add").is_err(), "Wrong parameter type should be rejected");
+    assert!(engine.get_function::<extern "C" fn(i64, i64) -> i32>("add").is_err(), "Wrong return type should be rejected");
+    assert!(engine.get_function::<extern "C" fn(i64, i64)>("add").is_err(), "Void return should be rejected");
+    assert!(engine.get_function::<AddFn>("missing").is_err(), "Looking up a missing function should fail");
+}
+
+#[test]
+fn test_typed_function_outlives_engine() {
+    let mut pools = IRManager::new();
+    let module_tag = build_add_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    let add = engine.get_function::<AddFn>("add").expect("Failed to look up function");
+    drop(engine);
+
+    assert_eq!(add.call(40, 2), 42, "Handle did not call the compiled function");
+}
+
+#[test]
+fn test_typed_function_from_shared_engine() {
+    let mut pools = IRManager::new();
+    let module_tag = build_add_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let shared = SharedExecutionEngine::new(ExecutionEngine::new(module, false));
+
+    let add = shared.get_function::<AddFn>("add").expect("Failed to look up function");
+    let workers: Vec<_> = (0..4i64).map(|worker| {
+        let add = add.clone();
+        thread::spawn(move || add.call(worker, 1))
+    }).collect();
+
+    for (worker, handle) in workers.into_iter().enumerate() {
+        assert_eq!(handle.join().expect("Worker thread panicked"), worker as i64 + 1, "Concurrent call returned an unexpected value");
+    }
+}
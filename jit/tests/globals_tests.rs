@@ -0,0 +1,69 @@
+mod fixtures;
+
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::{IRManager, ModuleTag};
+use jit::core::ExecutionEngine;
+use fixtures::ModuleFixture;
+
+/// Builds a module with an `i64` global `counter` starting at 7, whose `main` function adds five
+/// to `counter`, stores it back and returns it.
+fn build_counter_module(pools: &mut IRManager) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    let initial_value = pools.create_integer(fixture.context_tag, 7).expect("Failed to create integer");
+    let counter_tag = pools.init_global_var(fixture.module_tag, "counter", fixture.int_type, Some(initial_value)).expect("Failed to add global");
+
+    fixture.add_function(pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    let current = pools.get_var(fixture.builder_tag, fixture.int_type, counter_tag, "current").expect("Failed to load counter");
+    let five = pools.create_integer(fixture.context_tag, 5).expect("Failed to create integer");
+    let updated = pools.build_add(fixture.builder_tag, current, five, "updated").expect("Failed to build add");
+    pools.reassign_var(fixture.builder_tag, counter_tag, updated).expect("Failed to store counter");
+    pools.nonvoid_return(fixture.builder_tag, updated).expect("Failed to create return");
+
+    fixture.module_tag
+}
+
+#[test]
+fn test_read_global_initial_value() {
+    let mut pools = IRManager::new();
+    let module_tag = build_counter_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.global_address("counter").expect("Failed to get global address") != 0, "Global should have an address");
+    assert_eq!(engine.read_global::<i64>("counter").expect("Failed to read global"), 7, "Global did not hold its initial value");
+}
+
+#[test]
+fn test_write_global_before_execution() {
+    let mut pools = IRManager::new();
+    let module_tag = build_counter_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    engine.write_global::<i64>("counter", 10).expect("Failed to write global");
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 15, "Function did not see the seeded global");
+    assert_eq!(engine.read_global::<i64>("counter").expect("Failed to read global"), 15, "Function did not update the global");
+}
+
+#[test]
+fn test_global_type_mismatch() {
+    let mut pools = IRManager::new();
+    let module_tag = build_counter_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.read_global::<i32>("counter").is_err(), "Reading an i64 global as i32 should fail");
+    assert!(engine.write_global::<f64>("counter", 1.0).is_err(), "Writing an i64 global as f64 should fail");
+}
+
+#[test]
+fn test_missing_global() {
+    let mut pools = IRManager::new();
+    let module_tag = build_counter_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.global_address("missing").is_err(), "Looking up a missing global should fail");
+    assert!(engine.read_global::<i64>("missing").is_err(), "Reading a missing global should fail");
+}
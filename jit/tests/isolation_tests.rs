@@ -0,0 +1,140 @@
+mod fixtures;
+
+use std::{ffi::c_void, time::Duration};
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::{IRManager, ModuleTag};
+use jit::{core::ExecutionEngine, isolation::{ExecutionLimits, ExitStatus}};
+use fixtures::ModuleFixture;
+
+/// Builds a module whose function calls `malloc` with `size` and returns the pointer it got.
+fn build_malloc_module(pools: &mut IRManager, size: i64) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    let byte_type = pools.int_type(fixture.context_tag, 8).expect("Failed to create int type");
+    let pointer_type = pools.pointer_type(byte_type).expect("Failed to create pointer type");
+    let malloc = fixture.declare_function(pools, "malloc", Some(pointer_type), &[fixture.int_type]);
+
+    fixture.add_function(pools, DEFAULT_FUNCTION_NAME, Some(pointer_type), &[]);
+    let size = pools.create_integer(fixture.context_tag, size).expect("Failed to create integer");
+    let memory = pools.build_call(fixture.builder_tag, malloc.0, malloc.1, &[size], "memory").expect("Failed to build call");
+    pools.nonvoid_return(fixture.builder_tag, memory).expect("Failed to create return");
+
+    fixture.module_tag
+}
+
+#[test]
+fn test_execute_isolated_returns_value() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    fixture.add_constant_function(&mut pools, DEFAULT_FUNCTION_NAME, 42);
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let outcome = engine.execute_isolated::<i64, ()>(DEFAULT_FUNCTION_NAME, (), ExecutionLimits::default()).expect("Failed to execute function");
+    assert_eq!(outcome.status, ExitStatus::Exited(0), "Child did not exit cleanly");
+    assert_eq!(outcome.return_value, Some(42), "Function did not return the expected integer value");
+    assert!(outcome.is_success(), "Execution should be reported as successful");
+}
+
+#[test]
+fn test_execute_isolated_times_out() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    let function_tag = fixture.add_function(&mut pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    let loop_tag = pools.create_basic_block(fixture.context_tag, function_tag, "loop").expect("Failed to create loop block");
+    pools.create_br(fixture.builder_tag, loop_tag).expect("Failed to create branch");
+    pools.position_builder_at_end(fixture.builder_tag, loop_tag).expect("Failed to position builder");
+    pools.create_br(fixture.builder_tag, loop_tag).expect("Failed to create branch");
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let limits = ExecutionLimits::new(Duration::from_millis(200));
+    let outcome = engine.execute_isolated::<i64, ()>(DEFAULT_FUNCTION_NAME, (), limits).expect("Failed to execute function");
+    assert_eq!(outcome.status, ExitStatus::TimedOut, "Infinite loop should time out");
+    assert!(outcome.return_value.is_none(), "Timed out function should not produce a value");
+}
+
+#[test]
+fn test_execute_isolated_missing_function() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let result = engine.execute_isolated::<i64, ()>("missing", (), ExecutionLimits::default());
+    assert!(result.is_err(), "Executing a missing function should fail before forking");
+}
+
+#[test]
+fn test_execute_isolated_contains_segfault() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    let pointer_type = pools.pointer_type(fixture.int_type).expect("Failed to create pointer type");
+    let null = pools.create_null_pointer(pointer_type).expect("Failed to create null pointer");
+    // The null pointer is read from a mutable global, so the store cannot be folded away
+    let target_tag = pools.init_global_var(fixture.module_tag, "target", pointer_type, Some(null)).expect("Failed to add global");
+
+    fixture.add_function(&mut pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    let target = pools.get_var(fixture.builder_tag, pointer_type, target_tag, "target").expect("Failed to load target");
+    let forty_two = pools.create_integer(fixture.context_tag, 42).expect("Failed to create integer");
+    pools.reassign_var(fixture.builder_tag, target, forty_two).expect("Failed to store through target");
+    pools.nonvoid_return(fixture.builder_tag, forty_two).expect("Failed to create return");
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let outcome = engine.execute_isolated::<i64, ()>(DEFAULT_FUNCTION_NAME, (), ExecutionLimits::default()).expect("Failed to execute function");
+    assert_eq!(outcome.status, ExitStatus::Signaled(libc::SIGSEGV), "A store through a null pointer should crash the child only");
+    assert!(outcome.return_value.is_none(), "A crashed function should not produce a value");
+    assert!(!outcome.is_success(), "A crash should not be reported as successful");
+}
+
+#[test]
+fn test_execute_isolated_contains_abort() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    let abort = fixture.declare_function(&mut pools, "abort", None, &[]);
+
+    fixture.add_function(&mut pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    pools.build_call(fixture.builder_tag, abort.0, abort.1, &[], "").expect("Failed to build call");
+    let zero = pools.create_integer(fixture.context_tag, 0).expect("Failed to create integer");
+    pools.nonvoid_return(fixture.builder_tag, zero).expect("Failed to create return");
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let outcome = engine.execute_isolated::<i64, ()>(DEFAULT_FUNCTION_NAME, (), ExecutionLimits::default()).expect("Failed to execute function");
+    assert_eq!(outcome.status, ExitStatus::Signaled(libc::SIGABRT), "Calling abort should terminate the child only");
+    assert!(outcome.return_value.is_none(), "An aborted function should not produce a value");
+}
+
+#[test]
+fn test_execute_isolated_memory_limit() {
+    const GIGABYTE: u64 = 1 << 30;
+
+    let mut pools = IRManager::new();
+    let module_tag = build_malloc_module(&mut pools, GIGABYTE as i64);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    let unlimited = engine.execute_isolated::<*mut c_void, ()>(DEFAULT_FUNCTION_NAME, (), ExecutionLimits::default()).expect("Failed to execute function");
+    assert!(unlimited.is_success(), "Function should run without a memory limit");
+    assert!(unlimited.return_value.is_some_and(|memory| !memory.is_null()), "Allocating a gigabyte should succeed without a memory limit");
+
+    let limits = ExecutionLimits::default().with_memory_limit(GIGABYTE / 2);
+    let limited = engine.execute_isolated::<*mut c_void, ()>(DEFAULT_FUNCTION_NAME, (), limits).expect("Failed to execute function");
+    let allocated = limited.is_success() && limited.return_value.is_some_and(|memory| !memory.is_null());
+    assert!(!allocated, "The allocation should fail or the child should be killed once the address space is limited");
+}
+
+#[test]
+fn test_execute_isolated_captures_output() {
+    let mut pools = IRManager::new();
+    let fixture = ModuleFixture::new(&mut pools);
+    let fwrite = fixture.declare_fwrite(&mut pools);
+
+    fixture.add_function(&mut pools, DEFAULT_FUNCTION_NAME, Some(fixture.int_type), &[]);
+    fixture.build_print(&mut pools, fwrite, "stdout", "to stdout\n");
+    fixture.build_print(&mut pools, fwrite, "stderr", "to stderr\n");
+    let seven = pools.create_integer(fixture.context_tag, 7).expect("Failed to create integer");
+    pools.nonvoid_return(fixture.builder_tag, seven).expect("Failed to create return");
+    let mut engine = ExecutionEngine::new(fixture.get_module(&pools), false);
+
+    let outcome = engine.execute_isolated::<i64, ()>(DEFAULT_FUNCTION_NAME, (), ExecutionLimits::default()).expect("Failed to execute function");
+    assert!(outcome.is_success(), "Function should run successfully");
+    assert_eq!(outcome.return_value, Some(7), "Function did not return the expected value");
+    assert_eq!(outcome.stdout, "to stdout\n", "Standard output of the child was not captured");
+    assert_eq!(outcome.stderr, "to stderr\n", "Standard error of the child was not captured");
+}
@@ -0,0 +1,51 @@
+mod fixtures;
+
+use std::sync::{Arc, Mutex, RwLock};
+use common::{constants::DEFAULT_FUNCTION_NAME, pointer::SafeLLVMPointer};
+use ir::core::IRManager;
+use jit::{core::ExecutionEngine, listeners::{EmittedFunction, JitEventListeners}};
+use fixtures::ModuleFixture;
+
+/// Builds a module with a function `helper` returning 5 and a `main` function returning 42.
+fn build_two_function_module(pools: &mut IRManager) -> Arc<RwLock<SafeLLVMPointer>> {
+    let fixture = ModuleFixture::new(pools);
+    fixture.add_constant_function(pools, "helper", 5);
+    fixture.add_constant_function(pools, DEFAULT_FUNCTION_NAME, 42);
+    fixture.get_module(pools)
+}
+
+#[test]
+fn test_callback_reports_emitted_functions() {
+    let mut pools = IRManager::new();
+    let module = build_two_function_module(&mut pools);
+
+    let emitted: Arc<Mutex<Vec<EmittedFunction>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&emitted);
+    let listeners = JitEventListeners::new().with_callback(move |function| sink.lock().expect("Failed to lock sink").push(function.clone()));
+
+    let mut engine = ExecutionEngine::with_event_listeners(module, &listeners, false).expect("Failed to create engine");
+
+    let emitted = emitted.lock().expect("Failed to lock sink");
+    let names: Vec<&str> = emitted.iter().map(|function| function.name.as_str()).collect();
+    assert_eq!(names, vec!["helper", DEFAULT_FUNCTION_NAME], "Callback did not receive every defined function");
+    for function in emitted.iter() {
+        assert!(function.address != 0, "Emitted function should have an address");
+        assert!(function.size > 0, "Emitted function should have machine code");
+    }
+    assert_ne!(emitted[0].address, emitted[1].address, "Functions should be emitted at distinct addresses");
+
+    let result: Result<i64, String> = engine.execute(DEFAULT_FUNCTION_NAME, ());
+    assert_eq!(result.expect("Failed to execute function"), 42, "Function did not return the expected integer value");
+}
+
+#[test]
+fn test_gdb_listener_engine_executes() {
+    let mut pools = IRManager::new();
+    let module = build_two_function_module(&mut pools);
+    let listeners = JitEventListeners::new().with_gdb();
+
+    let mut engine = ExecutionEngine::with_event_listeners(module, &listeners, false).expect("Failed to create engine");
+
+    let result: Result<i64, String> = engine.execute("helper", ());
+    assert_eq!(result.expect("Failed to execute function"), 5, "Function did not return the expected integer value");
+}
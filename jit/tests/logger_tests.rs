@@ -0,0 +1,46 @@
+mod fixtures;
+
+use std::fs;
+use common::constants::DEFAULT_FUNCTION_NAME;
+use ir::core::IRManager;
+use jit::{backend::ExecutionBackend, core::ExecutionEngine, interpreter::Interpreter};
+use logging::{capture::LogCapture, config::{FileFormat, LoggerConfig}};
+use fixtures::build_constant_module;
+
+#[test]
+fn test_injected_logger_records_errors() {
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+
+    let path = std::env::temp_dir().join(format!("safe_llvm_jit_logger_{}.log", std::process::id()));
+    let logger = LoggerConfig::new().with_terminal(false).with_file(&path).with_file_format(FileFormat::Plain).build().expect("Failed to build logger");
+
+    let mut engine = ExecutionEngine::new(module.clone(), false).with_logger(logger.clone());
+    let mut interpreter = Interpreter::new(module, false).with_logger(logger);
+    assert!(engine.execute::<i64, ()>("missing", ()).is_err(), "Executing a missing function should fail");
+    assert_eq!(interpreter.run_function(DEFAULT_FUNCTION_NAME, &[]).expect("Failed to run function").to_i64(), 42, "Interpreter returned an unexpected value");
+    drop(engine);
+    drop(interpreter);
+
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    assert!(contents.contains("Function \"missing\" not found."), "Engine should log to the injected logger");
+    assert!(contents.contains("Function 'main' executed successfully."), "Interpreter should log to the injected logger");
+    fs::remove_file(&path).expect("Failed to remove log file");
+}
+
+#[test]
+fn test_missing_function_is_logged() {
+    let mut pools = IRManager::new();
+    let module = build_constant_module(&mut pools, 42);
+    let capture = LogCapture::new();
+    let mut engine = ExecutionEngine::new(module, false).with_logger(capture.logger());
+
+    assert!(engine.execute::<i64, ()>("missing", ()).is_err(), "Executing a missing function should fail");
+    capture.assert_warning_logged("Function \"missing\" not found.");
+    capture.assert_error_logged("Execution error: Function not found in given module.");
+
+    capture.clear();
+    assert_eq!(engine.execute::<i64, ()>(DEFAULT_FUNCTION_NAME, ()), Ok(42), "Engine returned an unexpected value");
+    capture.assert_info_logged("Function 'main' executed successfully.");
+    assert_eq!(capture.records().len(), 1, "Only the successful execution should be logged");
+}
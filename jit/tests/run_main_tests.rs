@@ -0,0 +1,69 @@
+use common::constants::{DEFAULT_BASIC_BLOCK_NAME, DEFAULT_FUNCTION_NAME, DEFAULT_MODULE_NAME};
+use ir::core::IRManager;
+use jit::core::ExecutionEngine;
+
+#[test]
+fn test_run_main_returns_argc() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 32).expect("Failed to create int type");
+    let char_type = pools.int_type(context_tag, 8).expect("Failed to create char type");
+    let argv_type = pools.pointer_type(char_type).expect("Failed to create pointer type");
+    let function_value = pools.create_function(Some(int_type), &[int_type, argv_type], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = pools.add_function_to_module(module_tag, DEFAULT_FUNCTION_NAME, function_value).expect("Failed to add function to module");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let bb_tag = pools.create_basic_block(context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    let argc = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+
+    pools.position_builder_at_end(builder_tag, bb_tag).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, argc).expect("Failed to create return");
+
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    let exit_code = engine.run_main(&["program", "first", "second"], &[("HOME", "/tmp")]).expect("Failed to run main");
+    assert_eq!(exit_code, 3, "Program did not receive the expected argument count");
+}
+
+#[test]
+fn test_run_main_without_main() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 32).expect("Failed to create int type");
+    let function_value = pools.create_function(Some(int_type), &[int_type], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = pools.add_function_to_module(module_tag, "not_main", function_value).expect("Failed to add function to module");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let bb_tag = pools.create_basic_block(context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    let param = pools.get_param(function_tag, 0).expect("Failed to get parameter");
+
+    pools.position_builder_at_end(builder_tag, bb_tag).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, param).expect("Failed to create return");
+
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.run_main(&["program"], &[]).is_err(), "Running a module without main should fail");
+}
+
+#[test]
+fn test_run_main_rejects_invalid_signature() {
+    let mut pools = IRManager::new();
+    let context_tag = pools.create_context().expect("Failed to create context");
+    let module_tag = pools.create_module(DEFAULT_MODULE_NAME, context_tag).expect("Failed to create module");
+    let int_type = pools.int_type(context_tag, 64).expect("Failed to create int type");
+    let function_value = pools.create_function(Some(int_type), &[], false, context_tag).expect("Failed to create function prototype");
+    let function_tag = pools.add_function_to_module(module_tag, DEFAULT_FUNCTION_NAME, function_value).expect("Failed to add function to module");
+    let builder_tag = pools.create_builder(context_tag).expect("Failed to create builder");
+    let bb_tag = pools.create_basic_block(context_tag, function_tag, DEFAULT_BASIC_BLOCK_NAME).expect("Failed to create entry block");
+    let return_value = pools.create_integer(context_tag, 0).expect("Failed to create return value");
+
+    pools.position_builder_at_end(builder_tag, bb_tag).expect("Failed to position builder");
+    pools.nonvoid_return(builder_tag, return_value).expect("Failed to create return");
+
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let mut engine = ExecutionEngine::new(module, false);
+
+    assert!(engine.run_main(&["program"], &[]).is_err(), "A main returning i64 should be rejected");
+}
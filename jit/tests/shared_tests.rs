@@ -0,0 +1,58 @@
+mod fixtures;
+
+use std::thread;
+use ir::core::{IRManager, ModuleTag};
+use jit::{core::ExecutionEngine, shared::SharedExecutionEngine};
+use fixtures::ModuleFixture;
+
+/// Builds a module with a function `increment` adding one to its i64 parameter.
+fn build_increment_module(pools: &mut IRManager) -> ModuleTag {
+    let fixture = ModuleFixture::new(pools);
+    fixture.add_increment_function(pools, "increment");
+    fixture.module_tag
+}
+
+#[test]
+fn test_concurrent_calls() {
+    let mut pools = IRManager::new();
+    let module_tag = build_increment_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let shared = SharedExecutionEngine::new(ExecutionEngine::new(module, false));
+
+    let increment = shared.get_function::<extern "C" fn(i64) -> i64>("increment").expect("Failed to look up function");
+    let workers: Vec<_> = (0..8i64).map(|worker| {
+        let increment = increment.clone();
+        thread::spawn(move || (0..1000i64).all(|i| increment.call(worker * 1000 + i) == worker * 1000 + i + 1))
+    }).collect();
+
+    for worker in workers {
+        assert!(worker.join().expect("Worker thread panicked"), "Concurrent call returned an unexpected value");
+    }
+}
+
+#[test]
+fn test_handle_outlives_engine() {
+    let mut pools = IRManager::new();
+    let module_tag = build_increment_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let shared = SharedExecutionEngine::new(ExecutionEngine::new(module, false));
+
+    let increment = shared.get_function::<extern "C" fn(i64) -> i64>("increment").expect("Failed to look up function");
+    drop(shared);
+
+    let result = thread::spawn(move || increment.call(41)).join().expect("Worker thread panicked");
+    assert_eq!(result, 42, "Handle did not call the compiled function");
+}
+
+#[test]
+fn test_locked_access_and_missing_function() {
+    let mut pools = IRManager::new();
+    let module_tag = build_increment_module(&mut pools);
+    let module = pools.get_module(module_tag).expect("Failed to retrieve module");
+    let shared = SharedExecutionEngine::new(ExecutionEngine::new(module, false));
+
+    let result = shared.with_engine(|engine| engine.execute::<i64, i64>("increment", 1)).expect("Failed to lock engine");
+    assert_eq!(result, Ok(2), "Locked execution returned an unexpected value");
+    assert!(shared.get_function::<extern "C" fn(i64) -> i64>("missing").is_err(), "Looking up a missing function should fail");
+    assert!(shared.get_function::<extern "C" fn(f64) -> i64>("increment").is_err(), "Looking up a function with the wrong signature should fail");
+}
@@ -0,0 +1,64 @@
+//! Forwarding of slog records to the `tracing` ecosystem.
+//!
+//! `TracingDrain` turns every slog record into a `tracing` event at the corresponding level, so an
+//! application that installs a `tracing` subscriber sees the records of every crate in this
+//! workspace, nested in whatever spans are active when they are logged.
+
+use std::fmt::{self, Write};
+use slog::{o, Drain, Key, Level, Logger, Never, OwnedKVList, Record, Serializer, KV};
+
+/// The `tracing` target forwarded records are emitted under.
+pub const TRACING_TARGET: &str = "safe_llvm";
+
+/// Renders key-value pairs as `key=value` separated by spaces.
+struct KeyValueFormatter {
+    output: String,
+}
+
+impl Serializer for KeyValueFormatter {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        if !self.output.is_empty() {
+            self.output.push(' ');
+        }
+        let _ = write!(self.output, "{}={}", key, value);
+        Ok(())
+    }
+}
+
+/// A drain forwarding every record to `tracing` as an event.
+///
+/// The record's message becomes the event message, its key-value pairs and those of its logger are
+/// rendered into a `kv` field, and its source module is recorded in a `module` field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingDrain;
+
+impl Drain for TracingDrain {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), Never> {
+        let mut formatter = KeyValueFormatter { output: String::new() };
+        let _ = record.kv().serialize(record, &mut formatter);
+        let _ = values.serialize(record, &mut formatter);
+
+        let message = record.msg();
+        let module = record.module();
+        let kv = formatter.output;
+        match record.level() {
+            Level::Critical | Level::Error => tracing::error!(target: TRACING_TARGET, module, kv = %kv, "{}", message),
+            Level::Warning => tracing::warn!(target: TRACING_TARGET, module, kv = %kv, "{}", message),
+            Level::Info => tracing::info!(target: TRACING_TARGET, module, kv = %kv, "{}", message),
+            Level::Debug => tracing::debug!(target: TRACING_TARGET, module, kv = %kv, "{}", message),
+            Level::Trace => tracing::trace!(target: TRACING_TARGET, module, kv = %kv, "{}", message),
+        }
+        Ok(())
+    }
+}
+
+/// Creates a logger that forwards every record to `tracing` and writes nowhere else.
+///
+/// # Returns
+/// A `Logger` emitting `tracing` events synchronously, inside the spans active at the call site.
+pub fn tracing_logger() -> Logger {
+    Logger::root(TracingDrain, o!())
+}
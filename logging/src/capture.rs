@@ -0,0 +1,186 @@
+//! In-memory capture of log records for tests.
+//!
+//! A `LogCapture` hands out loggers whose records are stored in memory, synchronously, so a test
+//! can inspect what a component logged as soon as the call under test returns.
+
+use std::{fmt, sync::{Arc, Mutex}};
+use slog::{o, Drain, Key, Level, Logger, Never, OwnedKVList, Record, Serializer, KV};
+
+/// A log record stored by a `LogCapture`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedRecord {
+    /// The level the record was logged at.
+    pub level: Level,
+    /// The formatted message of the record.
+    pub message: String,
+    /// The key-value pairs of the record and its logger, rendered as strings.
+    pub key_values: Vec<(String, String)>,
+}
+
+impl fmt::Display for CapturedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.level.as_short_str(), self.message)?;
+        for (key, value) in &self.key_values {
+            write!(f, ", {}: {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects key-value pairs as strings.
+struct KeyValueCollector {
+    key_values: Vec<(String, String)>,
+}
+
+impl Serializer for KeyValueCollector {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        self.key_values.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+/// A drain storing every record in a shared buffer.
+struct CaptureDrain {
+    records: Arc<Mutex<Vec<CapturedRecord>>>,
+}
+
+impl Drain for CaptureDrain {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), Never> {
+        let mut collector = KeyValueCollector { key_values: Vec::new() };
+        let _ = record.kv().serialize(record, &mut collector);
+        let _ = values.serialize(record, &mut collector);
+
+        let captured = CapturedRecord {
+            level: record.level(),
+            message: record.msg().to_string(),
+            key_values: collector.key_values,
+        };
+        if let Ok(mut records) = self.records.lock() {
+            records.push(captured);
+        }
+        Ok(())
+    }
+}
+
+/// An inspectable in-memory buffer of log records.
+///
+/// Cloning a `LogCapture` yields another handle to the same buffer.
+#[derive(Clone, Default)]
+pub struct LogCapture {
+    records: Arc<Mutex<Vec<CapturedRecord>>>,
+}
+
+impl LogCapture {
+    /// Constructs an empty capture buffer.
+    ///
+    /// # Returns
+    /// A new instance of `LogCapture`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a logger that records into this buffer.
+    ///
+    /// Records are stored synchronously, before the logging call returns.
+    ///
+    /// # Returns
+    /// A `Logger` writing to the buffer.
+    pub fn logger(&self) -> Logger {
+        let drain = CaptureDrain { records: Arc::clone(&self.records) };
+        Logger::root(drain.fuse(), o!())
+    }
+
+    /// Retrieves a copy of every record captured so far.
+    ///
+    /// # Returns
+    /// The captured records, in the order they were logged.
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        self.records.lock().map(|records| records.clone()).unwrap_or_default()
+    }
+
+    /// Discards every record captured so far.
+    pub fn clear(&self) {
+        if let Ok(mut records) = self.records.lock() {
+            records.clear();
+        }
+    }
+
+    /// Determines whether a record at the given level contains the given text.
+    ///
+    /// # Parameters
+    /// * `level` - The level the record must have been logged at.
+    /// * `text` - The text the record's message must contain.
+    ///
+    /// # Returns
+    /// True if a matching record was captured, false otherwise.
+    pub fn contains(&self, level: Level, text: &str) -> bool {
+        self.records().iter().any(|record| record.level == level && record.message.contains(text))
+    }
+
+    /// Asserts that a record at the given level contains the given text.
+    ///
+    /// # Parameters
+    /// * `level` - The level the record must have been logged at.
+    /// * `text` - The text the record's message must contain.
+    ///
+    /// # Panics
+    /// Panics, listing every captured record, if no matching record was captured.
+    pub fn assert_logged(&self, level: Level, text: &str) {
+        if !self.contains(level, text) {
+            panic!("Expected a {} record containing {:?}, but captured:\n{}", level.as_str(), text, self.render());
+        }
+    }
+
+    /// Asserts that no record at the given level contains the given text.
+    ///
+    /// # Parameters
+    /// * `level` - The level to check.
+    /// * `text` - The text no record's message may contain.
+    ///
+    /// # Panics
+    /// Panics, listing every captured record, if a matching record was captured.
+    pub fn assert_not_logged(&self, level: Level, text: &str) {
+        if self.contains(level, text) {
+            panic!("Expected no {} record containing {:?}, but captured:\n{}", level.as_str(), text, self.render());
+        }
+    }
+
+    /// Asserts that an info record containing the given text was logged.
+    ///
+    /// # Parameters
+    /// * `text` - The text the record's message must contain.
+    pub fn assert_info_logged(&self, text: &str) {
+        self.assert_logged(Level::Info, text);
+    }
+
+    /// Asserts that a warning containing the given text was logged.
+    ///
+    /// # Parameters
+    /// * `text` - The text the record's message must contain.
+    pub fn assert_warning_logged(&self, text: &str) {
+        self.assert_logged(Level::Warning, text);
+    }
+
+    /// Asserts that an error containing the given text was logged.
+    ///
+    /// # Parameters
+    /// * `text` - The text the record's message must contain.
+    pub fn assert_error_logged(&self, text: &str) {
+        self.assert_logged(Level::Error, text);
+    }
+
+    /// Renders every captured record, one per line, for assertion messages.
+    ///
+    /// # Returns
+    /// The rendered records.
+    fn render(&self) -> String {
+        let records = self.records();
+        if records.is_empty() {
+            return "  (no records)".to_string();
+        }
+        records.iter().map(|record| format!("  {}", record)).collect::<Vec<_>>().join("\n")
+    }
+}
@@ -0,0 +1,257 @@
+//! Configuration of loggers.
+//!
+//! `LoggerConfig` describes where records go and which are kept: an optional terminal output, an
+//! optional log file written as plain text or JSON, and a minimum level below which records are
+//! discarded. The default configuration matches `init_logger`.
+
+use std::{fs::{File, OpenOptions}, panic::{RefUnwindSafe, UnwindSafe}, path::{Path, PathBuf}, sync::Mutex};
+use slog::{o, Discard, Drain, Duplicate, Level, LevelFilter, Logger, Never};
+use slog_async::Async;
+use slog_json::Json;
+use slog_term::{CompactFormat, FullFormat, PlainSyncDecorator, TermDecorator};
+
+/// The name of the log file written by the default configuration.
+pub const DEFAULT_LOG_FILE: &str = "app.log";
+
+/// A drain that can be combined with other drains and moved to the logging thread.
+type BoxedDrain = Box<dyn Drain<Ok = (), Err = Never> + Send>;
+
+/// The format records are written to a log file in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Human-readable lines, one per record.
+    Plain,
+    /// One JSON object per record.
+    Json,
+}
+
+/// A builder describing the outputs and level of a logger.
+#[derive(Debug, Clone)]
+pub struct LoggerConfig {
+    min_level: Level,
+    terminal: bool,
+    file_path: Option<PathBuf>,
+    append: bool,
+    file_format: FileFormat,
+    asynchronous: bool,
+    #[cfg(feature = "tracing")]
+    tracing: bool,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            min_level: Level::Info,
+            terminal: true,
+            file_path: Some(PathBuf::from(DEFAULT_LOG_FILE)),
+            append: false,
+            file_format: FileFormat::Json,
+            asynchronous: true,
+            #[cfg(feature = "tracing")]
+            tracing: false,
+        }
+    }
+}
+
+impl LoggerConfig {
+    /// Constructs the default configuration: info level and above, written to the terminal and
+    /// as JSON to a truncated `app.log`.
+    ///
+    /// # Returns
+    /// A new instance of `LoggerConfig`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the least severe level that is logged.
+    ///
+    /// # Parameters
+    /// * `level` - The minimum level.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_min_level(mut self, level: Level) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Enables or disables output to the terminal.
+    ///
+    /// # Parameters
+    /// * `enabled` - Whether records are written to the terminal.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_terminal(mut self, enabled: bool) -> Self {
+        self.terminal = enabled;
+        self
+    }
+
+    /// Sets the file records are written to.
+    ///
+    /// # Parameters
+    /// * `path` - The path of the log file.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.file_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Disables output to a file.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn without_file(mut self) -> Self {
+        self.file_path = None;
+        self
+    }
+
+    /// Chooses whether an existing log file is appended to or truncated.
+    ///
+    /// # Parameters
+    /// * `append` - If true, records are appended to an existing file instead of replacing it.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Sets the format records are written to the log file in.
+    ///
+    /// # Parameters
+    /// * `format` - The file format.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_file_format(mut self, format: FileFormat) -> Self {
+        self.file_format = format;
+        self
+    }
+
+    /// Chooses whether records are written on a background thread or by the logging thread.
+    ///
+    /// Asynchronous loggers only write their queued records once every handle to them has been
+    /// dropped, so records are lost if a logger that is never dropped, such as one held in a
+    /// `static`, is asynchronous. Synchronous loggers write each record before returning.
+    ///
+    /// # Parameters
+    /// * `asynchronous` - If true, records are queued and written on a background thread.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    pub fn with_async(mut self, asynchronous: bool) -> Self {
+        self.asynchronous = asynchronous;
+        self
+    }
+
+    /// Enables or disables forwarding of records to `tracing`.
+    ///
+    /// Forwarded records are emitted synchronously on the logging thread, so they are nested in
+    /// the `tracing` spans active where they were logged.
+    ///
+    /// # Parameters
+    /// * `enabled` - Whether records are forwarded to `tracing`.
+    ///
+    /// # Returns
+    /// The updated `LoggerConfig`.
+    #[cfg(feature = "tracing")]
+    pub fn with_tracing(mut self, enabled: bool) -> Self {
+        self.tracing = enabled;
+        self
+    }
+
+    /// Retrieves the least severe level that is logged.
+    ///
+    /// # Returns
+    /// The minimum level.
+    pub fn get_min_level(&self) -> Level {
+        self.min_level
+    }
+
+    /// Retrieves the path of the log file.
+    ///
+    /// # Returns
+    /// An `Option` containing the path, or `None` if no file is written.
+    pub fn get_file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Opens the log file according to the append setting.
+    ///
+    /// # Parameters
+    /// * `path` - The path of the log file.
+    ///
+    /// # Returns
+    /// Returns `Ok(File)` on success, or `Err(String)` if the file could not be opened.
+    fn open_file(&self, path: &Path) -> Result<File, String> {
+        let mut options = OpenOptions::new();
+        options.create(true);
+        if self.append {
+            options.append(true);
+        } else {
+            options.write(true).truncate(true);
+        }
+        options.open(path).map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))
+    }
+
+    /// Builds a logger with the configured outputs.
+    ///
+    /// Records are written asynchronously unless `with_async(false)` was chosen. A configuration
+    /// without any output yields a logger that discards every record.
+    ///
+    /// # Returns
+    /// Returns `Ok(Logger)` on success, or `Err(String)` if the log file could not be opened.
+    pub fn build(&self) -> Result<Logger, String> {
+        let mut drains: Vec<BoxedDrain> = Vec::new();
+
+        if self.terminal {
+            let decorator = TermDecorator::new().build();
+            drains.push(Box::new(CompactFormat::new(decorator).build().fuse()));
+        }
+
+        if let Some(path) = &self.file_path {
+            let file = self.open_file(path)?;
+            match self.file_format {
+                FileFormat::Json => drains.push(Box::new(Json::new(file).add_default_keys().build().fuse())),
+                FileFormat::Plain => drains.push(Box::new(FullFormat::new(PlainSyncDecorator::new(file)).build().fuse())),
+            }
+        }
+
+        let drain = drains.into_iter()
+            .reduce(|first, second| Box::new(Duplicate::new(first, second).fuse()))
+            .unwrap_or_else(|| Box::new(Discard));
+
+        let filtered_drain = LevelFilter::new(drain, self.min_level).fuse();
+        if self.asynchronous {
+            Ok(self.build_root(Async::new(filtered_drain).build().fuse()))
+        } else {
+            Ok(self.build_root(Mutex::new(filtered_drain).fuse()))
+        }
+    }
+
+    /// Builds the root logger around the drain that writes the configured outputs.
+    ///
+    /// # Parameters
+    /// * `output_drain` - The drain writing to the terminal and log file.
+    ///
+    /// # Returns
+    /// The root `Logger`.
+    fn build_root<D>(&self, output_drain: D) -> Logger
+    where
+        D: Drain<Ok = (), Err = Never> + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        if self.tracing {
+            // Forwarded synchronously, so events stay inside the spans active at the call site.
+            let tracing_drain = LevelFilter::new(crate::bridge::TracingDrain, self.min_level);
+            let combined_drain = Duplicate::new(tracing_drain, output_drain).fuse();
+            return Logger::root(combined_drain, o!("version" => env!("CARGO_PKG_VERSION")));
+        }
+
+        Logger::root(output_drain, o!("version" => env!("CARGO_PKG_VERSION")))
+    }
+}
@@ -3,11 +3,9 @@
 //! This module defines several utility functions for logging various levels of messages
 //! and a function to initialize a global logger. It uses `slog` for structured, asynchronous logging.
 
-use std::fs::File;
-use slog::{error, info, o, warn, Drain, Duplicate, Fuse, Logger};
-use slog_async::Async;
-use slog_json::Json;
-use slog_term::{CompactFormat, TermDecorator};
+use std::sync::OnceLock;
+use slog::{error, info, warn, Logger};
+use crate::config::LoggerConfig;
 
 /// Logs informational messages using the provided logger.
 ///
@@ -39,24 +37,23 @@ pub fn log_error(logger: &Logger, msg: &str) {
 /// Initializes and returns a logger with both terminal and file (JSON format) outputs.
 ///
 /// This logger is asynchronous and writes logs to `app.log` in the JSON format and also outputs to the terminal in a compact format.
+/// Use `LoggerConfig` to choose different outputs.
 ///
 /// # Returns
 /// A `Logger` instance ready for use throughout the application.
 pub fn init_logger() -> Logger {
-    // Terminal output drain
-    let decorator: TermDecorator = TermDecorator::new().build();
-    let term_drain: Fuse<CompactFormat<TermDecorator>> = CompactFormat::new(decorator).build().fuse();
-
-    // File output for JSON 
-    let file: File = File::create("app.log").unwrap();
-    let json_drain = Json::new(file)
-        .add_default_keys() 
-        .build()
-        .fuse();
-
-    // Make the logging asynchronous
-    let drain: Fuse<Duplicate<Fuse<CompactFormat<TermDecorator>>, Fuse<Json<File>>>> = Duplicate::new(term_drain, json_drain).fuse();
-    let async_drain: Fuse<Async> = Async::new(drain).build().fuse();
+    LoggerConfig::default().build().unwrap()
+}
 
-    Logger::root(async_drain, o!("version" => env!("CARGO_PKG_VERSION")))
+/// Returns a logger with the default outputs that is shared by the whole process.
+///
+/// The logger is created on first use, so `app.log` is only truncated once per process no matter
+/// how many components request it. Since it lives in a `static` and is never dropped, it writes
+/// synchronously, so no record is left queued when the process exits.
+///
+/// # Returns
+/// A handle to the shared `Logger`.
+pub fn shared_logger() -> Logger {
+    static SHARED_LOGGER: OnceLock<Logger> = OnceLock::new();
+    SHARED_LOGGER.get_or_init(|| LoggerConfig::default().with_async(false).build().unwrap()).clone()
 }
@@ -3,4 +3,18 @@
 //! This module leverages the `slog` library to create a logging system that
 //! supports both terminal and file outputs. 
 
-pub mod core;
\ No newline at end of file
+pub mod core;
+
+/// Configuration of logger outputs, formats and levels.
+pub mod config;
+
+/// In-memory capture of log records, with assertion helpers for tests.
+pub mod capture;
+
+/// Spans around module generation, verification and JIT execution, entered when the `tracing`
+/// feature is enabled.
+pub mod spans;
+
+/// Forwarding of slog records to `tracing` events.
+#[cfg(feature = "tracing")]
+pub mod bridge;
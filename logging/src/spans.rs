@@ -0,0 +1,74 @@
+//! Spans around the main stages of compiling and running a module.
+//!
+//! With the `tracing` feature enabled, each guard enters a `tracing` span for as long as it is
+//! alive, so events recorded by `tracing` subscribers, including slog records forwarded through
+//! the bridge, are attributed to the stage that produced them. Without the feature the guards do
+//! nothing and cost nothing.
+
+#[cfg(feature = "tracing")]
+use tracing::span::EnteredSpan;
+
+/// A guard keeping a span entered until it is dropped.
+#[must_use = "the span is exited as soon as the guard is dropped"]
+pub struct StageSpan {
+    #[cfg(feature = "tracing")]
+    _span: EnteredSpan,
+}
+
+/// Enters the span covering the generation of a module's IR.
+///
+/// `IRManager::create_module` enters this span while it creates the module. Code generators can
+/// enter it themselves to cover the rest of a module's construction.
+///
+/// # Parameters
+/// * `module_name` - The name of the module being generated.
+///
+/// # Returns
+/// A guard that exits the span when dropped.
+pub fn module_generation(module_name: &str) -> StageSpan {
+    #[cfg(feature = "tracing")]
+    return StageSpan { _span: tracing::info_span!("module_generation", module = module_name).entered() };
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = module_name;
+        StageSpan {}
+    }
+}
+
+/// Enters the span covering the verification of a module or function.
+///
+/// `Validator` enters this span around each verification.
+///
+/// # Parameters
+/// * `subject` - A description of what is being verified, such as `module` or a function name.
+///
+/// # Returns
+/// A guard that exits the span when dropped.
+pub fn verification(subject: &str) -> StageSpan {
+    #[cfg(feature = "tracing")]
+    return StageSpan { _span: tracing::info_span!("verification", subject = subject).entered() };
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = subject;
+        StageSpan {}
+    }
+}
+
+/// Enters the span covering the JIT compilation or execution of a function.
+///
+/// `ExecutionEngine` enters this span while it runs a function.
+///
+/// # Parameters
+/// * `function_name` - The name of the function being executed.
+///
+/// # Returns
+/// A guard that exits the span when dropped.
+pub fn jit_execution(function_name: &str) -> StageSpan {
+    #[cfg(feature = "tracing")]
+    return StageSpan { _span: tracing::info_span!("jit_execution", function = function_name).entered() };
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = function_name;
+        StageSpan {}
+    }
+}
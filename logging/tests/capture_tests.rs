@@ -0,0 +1,51 @@
+use slog::{info, o, Level};
+use logging::capture::LogCapture;
+
+#[test]
+fn test_capture_records_messages() {
+    let capture = LogCapture::new();
+    let logger = capture.logger();
+
+    logging::core::log_info(&logger, "Function 'main' executed successfully.");
+    logging::core::log_warning(&logger, "Function \"missing\" not found.");
+    logging::core::log_error(&logger, "Execution error: Function not found in given module.");
+
+    let records = capture.records();
+    assert_eq!(records.len(), 3, "Every record should be captured");
+    assert_eq!(records[1].level, Level::Warning, "Records should keep their level");
+
+    capture.assert_info_logged("executed successfully");
+    capture.assert_warning_logged("\"missing\" not found");
+    capture.assert_error_logged("Function not found in given module.");
+    capture.assert_not_logged(Level::Error, "\"missing\" not found");
+}
+
+#[test]
+fn test_capture_records_key_values() {
+    let capture = LogCapture::new();
+    let logger = capture.logger().new(o!("component" => "jit"));
+
+    info!(logger, "compiled"; "function" => "main");
+
+    let records = capture.records();
+    assert!(records[0].key_values.contains(&("function".to_string(), "main".to_string())), "Record key-values should be captured");
+    assert!(records[0].key_values.contains(&("component".to_string(), "jit".to_string())), "Logger key-values should be captured");
+}
+
+#[test]
+fn test_capture_clear() {
+    let capture = LogCapture::new();
+    logging::core::log_warning(&capture.logger(), "first warning");
+    capture.clear();
+
+    assert!(capture.records().is_empty(), "Clearing should discard captured records");
+    assert!(!capture.contains(Level::Warning, "first warning"), "Cleared records should not match");
+}
+
+#[test]
+#[should_panic(expected = "Expected a WARNING record containing \"absent\"")]
+fn test_assert_logged_failure() {
+    let capture = LogCapture::new();
+    logging::core::log_info(&capture.logger(), "present");
+    capture.assert_warning_logged("absent");
+}
@@ -0,0 +1,78 @@
+use std::{fs, path::PathBuf};
+use slog::Level;
+use logging::config::{FileFormat, LoggerConfig};
+
+/// Returns a fresh log file path for a test.
+fn temp_log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("safe_llvm_logging_{}_{}.log", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// Logs the given messages with a logger built from `config`, waiting for them to be written.
+fn log_messages(config: &LoggerConfig, info: &str, warning: &str) {
+    let logger = config.build().expect("Failed to build logger");
+    logging::core::log_info(&logger, info);
+    logging::core::log_warning(&logger, warning);
+    drop(logger);
+}
+
+#[test]
+fn test_json_file_output() {
+    let path = temp_log_path("json");
+    let config = LoggerConfig::new().with_terminal(false).with_file(&path);
+    log_messages(&config, "first message", "second message");
+
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 2, "Each record should be written as one line");
+    assert!(lines[0].starts_with('{') && lines[0].contains("\"msg\":\"first message\""), "Records should be written as JSON");
+    fs::remove_file(&path).expect("Failed to remove log file");
+}
+
+#[test]
+fn test_min_level_filters_records() {
+    let path = temp_log_path("level");
+    let config = LoggerConfig::new().with_terminal(false).with_file(&path).with_min_level(Level::Warning);
+    log_messages(&config, "filtered message", "kept message");
+
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    assert!(!contents.contains("filtered message"), "Records below the minimum level should be discarded");
+    assert!(contents.contains("kept message"), "Records at the minimum level should be kept");
+    fs::remove_file(&path).expect("Failed to remove log file");
+}
+
+#[test]
+fn test_append_and_truncate() {
+    let path = temp_log_path("append");
+    let config = LoggerConfig::new().with_terminal(false).with_file(&path).with_file_format(FileFormat::Plain);
+    log_messages(&config, "first run", "first run warning");
+    log_messages(&config.clone().with_append(true), "second run", "second run warning");
+
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    assert!(contents.contains("first run") && contents.contains("second run"), "Appending should keep earlier records");
+    assert!(!contents.starts_with('{'), "Plain records should not be written as JSON");
+
+    log_messages(&config, "third run", "third run warning");
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    assert!(!contents.contains("first run") && contents.contains("third run"), "Truncating should discard earlier records");
+    fs::remove_file(&path).expect("Failed to remove log file");
+}
+
+#[test]
+fn test_unopenable_file() {
+    let path = std::env::temp_dir().join("safe_llvm_missing_directory").join("app.log");
+    assert!(LoggerConfig::new().with_terminal(false).with_file(path).build().is_err(), "Building with an unopenable file should fail");
+}
+
+#[test]
+fn test_synchronous_logger_writes_immediately() {
+    let path = temp_log_path("sync");
+    let logger = LoggerConfig::new().with_terminal(false).with_file(&path).with_async(false).build().expect("Failed to build logger");
+    logging::core::log_info(&logger, "written before drop");
+
+    let contents = fs::read_to_string(&path).expect("Failed to read log file");
+    assert!(contents.contains("written before drop"), "Synchronous loggers should write records before returning");
+    drop(logger);
+    fs::remove_file(&path).expect("Failed to remove log file");
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex};
+use slog::Level;
+use tracing::{field::{Field, Visit}, span::{Attributes, Id, Record}, Event, Metadata, Subscriber};
+use logging::{bridge::{tracing_logger, TRACING_TARGET}, config::LoggerConfig, spans};
+
+/// An event recorded by `RecordingSubscriber`: its level, message and the span it was emitted in.
+type RecordedEvent = (tracing::Level, String, Option<String>);
+
+/// Collects the message field of an event.
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+/// A subscriber recording events together with the name of the innermost entered span.
+#[derive(Default)]
+struct RecordingSubscriber {
+    next_id: AtomicU64,
+    span_names: Mutex<Vec<(u64, &'static str)>>,
+    entered: Mutex<Vec<u64>>,
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        self.span_names.lock().unwrap().push((id, span.metadata().name()));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor { message: String::new() };
+        event.record(&mut visitor);
+        let span = self.entered.lock().unwrap().last().and_then(|id| {
+            self.span_names.lock().unwrap().iter().find(|(span_id, _)| span_id == id).map(|(_, name)| name.to_string())
+        });
+        if event.metadata().target() == TRACING_TARGET {
+            self.events.lock().unwrap().push((*event.metadata().level(), visitor.message, span));
+        }
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut entered = self.entered.lock().unwrap();
+        if let Some(position) = entered.iter().rposition(|id| *id == span.into_u64()) {
+            entered.remove(position);
+        }
+    }
+}
+
+/// Runs a closure with a `RecordingSubscriber` as the default subscriber.
+///
+/// # Returns
+/// The events recorded while the closure ran.
+fn record_events<F: FnOnce()>(f: F) -> Vec<RecordedEvent> {
+    let subscriber = RecordingSubscriber::default();
+    let events = Arc::clone(&subscriber.events);
+    tracing::subscriber::with_default(subscriber, f);
+    let recorded = events.lock().unwrap().clone();
+    recorded
+}
+
+#[test]
+fn test_records_are_forwarded_by_level() {
+    let events = record_events(|| {
+        let logger = tracing_logger();
+        logging::core::log_info(&logger, "Function 'main' executed successfully.");
+        logging::core::log_warning(&logger, "Function \"missing\" not found.");
+        logging::core::log_error(&logger, "Execution error: Function not found in given module.");
+    });
+
+    assert_eq!(events.len(), 3, "Every record should be forwarded");
+    assert_eq!(events[0].0, tracing::Level::INFO);
+    assert_eq!(events[1].0, tracing::Level::WARN);
+    assert_eq!(events[2].0, tracing::Level::ERROR);
+    assert_eq!(events[0].1, "Function 'main' executed successfully.");
+}
+
+#[test]
+fn test_records_are_nested_in_stage_spans() {
+    let events = record_events(|| {
+        let logger = tracing_logger();
+        {
+            let _span = spans::module_generation("test_module");
+            logging::core::log_info(&logger, "generating");
+        }
+        {
+            let _span = spans::verification("module");
+            logging::core::log_info(&logger, "verifying");
+        }
+        {
+            let _span = spans::jit_execution("main");
+            logging::core::log_info(&logger, "executing");
+        }
+        logging::core::log_info(&logger, "done");
+    });
+
+    assert_eq!(events[0].2.as_deref(), Some("module_generation"));
+    assert_eq!(events[1].2.as_deref(), Some("verification"));
+    assert_eq!(events[2].2.as_deref(), Some("jit_execution"));
+    assert_eq!(events[3].2, None, "Records logged after a span is exited should not be nested in it");
+}
+
+#[test]
+fn test_config_forwards_to_tracing() {
+    let events = record_events(|| {
+        let logger = LoggerConfig::new()
+            .with_terminal(false)
+            .without_file()
+            .with_min_level(Level::Warning)
+            .with_tracing(true)
+            .build()
+            .expect("Logger should build");
+        let _span = spans::jit_execution("main");
+        logging::core::log_info(&logger, "filtered out");
+        logging::core::log_warning(&logger, "kept");
+    });
+
+    assert_eq!(events.len(), 1, "Records below the minimum level should not be forwarded");
+    assert_eq!(events[0].1, "kept");
+    assert_eq!(events[0].2.as_deref(), Some("jit_execution"));
+}
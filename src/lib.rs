@@ -9,10 +9,12 @@
 //! - `common`: Contains common utilities and helpers used across the project.
 //! - `ir`: Responsible for the generation and manipulation of LLVM Intermediate Representation (IR).
 //! - `jit`: Manages Just-In-Time (JIT) compilation features leveraging LLVM's JIT compilers.
+//! - `interpreter`: Executes IR step by step with breakpoints and detects undefined behavior.
 //! - `logging`: Facilitates logging across all modules, supporting both terminal and file-based outputs.
 
 pub use analysis;
 pub use common;
 pub use ir;
 pub use jit;
+pub use interpreter;
 pub use logging;